@@ -7,6 +7,7 @@ import kotlinx.coroutines.launch
 import kotlinx.coroutines.test.runTest
 import org.junit.Test
 import proton.android.authenticator.AuthenticatorCodeResponseModel
+import proton.android.authenticator.commonrust.AuthenticatorMobileClient
 import proton.android.authenticator.commonrust.MobileCurrentTimeProvider
 import proton.android.authenticator.commonrust.MobileTotpGenerator
 import proton.android.authenticator.commonrust.MobileTotpGeneratorCallback
@@ -25,10 +26,12 @@ class TotpGeneratorTest {
                 onlyOnCodeChange = false,
                 currentTime = object : MobileCurrentTimeProvider {
                     var idx = 0
-                    override fun now(): ULong = when (idx) {
-                        0 -> 1741764120
-                        1 -> 1741789012
-                        2 -> 1741890123
+                    override fun now(): ULong = nowMs() / 1000u
+
+                    override fun nowMs(): ULong = when (idx) {
+                        0 -> 1741764120000
+                        1 -> 1741789012000
+                        2 -> 1741890123000
                         else -> throw RuntimeException("Should not request")
                     }.toULong().also { idx += 1 }
                 }
@@ -80,27 +83,29 @@ class TotpGeneratorTest {
     @Test
     fun canInvokeGeneratorOnlyOnCodeChange() = runTest {
         launch(Dispatchers.Default) {
-            val entry = TestUtils.getEntry1()
+            val entry15 = TestUtils.getEntry1()
+            val entry30 = AuthenticatorMobileClient().entryFromUri(
+                "otpauth://totp/MYLABEL?secret=MYSECRET123&issuer=MYISSUER&algorithm=SHA256&digits=8&period=30"
+            )
 
-            val period = 100 // generate codes every 100ms
+            // 1741764135 is a boundary for the 15s period, but not for the 30s one.
+            // The clock starts 200ms before it and moves with the real time
+            val startMs = 1741764135000 - 200
+            val started = System.nanoTime()
             val generator = MobileTotpGenerator(
-                periodMs = period.toUInt(),
+                periodMs = 100u,
                 onlyOnCodeChange = true,
                 currentTime = object : MobileCurrentTimeProvider {
-                    var idx = 0
-                    override fun now(): ULong = when (idx) {
-                        0 -> 1741764120 // Generate
-                        1 -> 1741764121 // Don't generate
-                        2 -> 1741764122 // Don't generate
-                        3 -> 1741890120 // Generate
-                        else -> throw RuntimeException("Should not request")
-                    }.toULong().also { idx += 1 }
+                    override fun now(): ULong = nowMs() / 1000u
+
+                    override fun nowMs(): ULong =
+                        (startMs + (System.nanoTime() - started) / 1_000_000).toULong()
                 }
             )
 
             val generated = mutableListOf<List<AuthenticatorCodeResponseModel>>()
             val handle = generator.start(
-                entries = listOf(entry),
+                entries = listOf(entry15, entry30),
                 callback = object : MobileTotpGeneratorCallback {
                     override fun onCodes(codes: List<AuthenticatorCodeResponseModel>) {
                         generated.add(codes)
@@ -108,25 +113,24 @@ class TotpGeneratorTest {
                 }
             )
 
-            // Wait for the necessary time to generate 4 ticks
-            val times = 4
-            delay((times * period).toLong())
+            // The first codes of all the entries are emitted right away
+            delay(100)
+            assertThat(generated.size).isEqualTo(1)
+            assertThat(generated[0].size).isEqualTo(2)
+            assertThat(generated[0][0].currentCode).isEqualTo("55894277")
+            assertThat(generated[0][0].nextCode).isEqualTo("32755418")
 
+            // Wakes up at the boundary and only emits the entry whose code changed
+            delay(400)
             assertThat(generated.size).isEqualTo(2)
+            assertThat(generated[1].size).isEqualTo(1)
+            assertThat(generated[1][0].entry.id).isEqualTo(entry15.id)
+            assertThat(generated[1][0].currentCode).isEqualTo("32755418")
 
             // Cancel the generation
             handle.cancel()
-
-            // Assert codes are right
-            assertThat(generated[0].size).isEqualTo(1)
-            assertThat(generated[0][0].currentCode).isEqualTo("55894277")
-            assertThat(generated[0][0].nextCode).isEqualTo("32755418")
-
-            assertThat(generated[1].size).isEqualTo(1)
-            assertThat(generated[1][0].currentCode).isEqualTo("07871325")
-            assertThat(generated[1][0].nextCode).isEqualTo("49179669")
         }
 
     }
 
-}
\ No newline at end of file
+}
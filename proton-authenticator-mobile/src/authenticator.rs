@@ -1,6 +1,7 @@
-use crate::AuthenticatorEntryModel;
+use crate::{AuthenticatorEntryModel, AuthenticatorIssuerIcon};
 use proton_authenticator::entry::AuthenticatorInvalidDataParam;
 use proton_authenticator::{AuthenticatorClient, AuthenticatorEntry, warn};
+use proton_pass_common::qr::QrLogo;
 
 #[derive(Debug, uniffi::Error)]
 #[uniffi(flat_error)]
@@ -17,6 +18,7 @@ pub enum AuthenticatorError {
     ImportBadPassword,
     ImportMissingPassword,
     ImportDecryptionFailed,
+    InvalidOrderKey,
 }
 
 impl std::fmt::Display for AuthenticatorError {
@@ -55,6 +57,13 @@ impl From<proton_authenticator::AuthenticatorEntryError> for AuthenticatorError
     }
 }
 
+impl From<proton_authenticator::ordering::OrderKeyError> for AuthenticatorError {
+    fn from(e: proton_authenticator::ordering::OrderKeyError) -> Self {
+        warn!("OrderKeyError: {:?}", e);
+        AuthenticatorError::InvalidOrderKey
+    }
+}
+
 // Re-export core types directly
 pub use proton_authenticator::{
     Algorithm as AuthenticatorTotpAlgorithm, AuthenticatorEntrySteamCreateParameters,
@@ -67,6 +76,60 @@ pub use proton_authenticator::AuthenticatorCodeResponseModel as AuthenticatorCod
 
 // These types are now re-exported from the core crate above
 
+#[derive(uniffi::Record)]
+pub struct GoogleMigrationQrExport {
+    /// SVG QR codes, to be scanned in order
+    pub qr_codes: Vec<String>,
+    /// Entries that could not be exported as Google Authenticator does not support them
+    pub skipped_entry_ids: Vec<String>,
+}
+
+#[derive(uniffi::Enum)]
+pub enum AuthenticatorKdfProfile {
+    Standard,
+    Strong,
+    Paranoid,
+}
+
+impl From<AuthenticatorKdfProfile> for proton_authenticator::KdfProfile {
+    fn from(value: AuthenticatorKdfProfile) -> Self {
+        match value {
+            AuthenticatorKdfProfile::Standard => Self::Standard,
+            AuthenticatorKdfProfile::Strong => Self::Strong,
+            AuthenticatorKdfProfile::Paranoid => Self::Paranoid,
+        }
+    }
+}
+
+/// Argon2id parameters used to derive the key of a password protected export
+#[derive(uniffi::Record)]
+pub struct AuthenticatorKdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl From<proton_authenticator::KdfParams> for AuthenticatorKdfParams {
+    fn from(value: proton_authenticator::KdfParams) -> Self {
+        Self {
+            memory_kib: value.memory_kib,
+            iterations: value.iterations,
+            parallelism: value.parallelism,
+        }
+    }
+}
+
+impl From<AuthenticatorKdfParams> for proton_authenticator::KdfParams {
+    fn from(value: AuthenticatorKdfParams) -> Self {
+        Self {
+            algorithm: proton_authenticator::KdfAlgorithm::Argon2id,
+            memory_kib: value.memory_kib,
+            iterations: value.iterations,
+            parallelism: value.parallelism,
+        }
+    }
+}
+
 #[derive(uniffi::Object)]
 pub struct AuthenticatorMobileClient {
     inner: AuthenticatorClient,
@@ -168,6 +231,133 @@ impl AuthenticatorMobileClient {
         Ok(self.inner.export_entries_with_password(mapped, &password)?)
     }
 
+    pub fn export_entries_with_password_and_params(
+        &self,
+        entries: Vec<AuthenticatorEntryModel>,
+        password: String,
+        params: AuthenticatorKdfParams,
+    ) -> Result<String, AuthenticatorError> {
+        let mut mapped = vec![];
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        Ok(self
+            .inner
+            .export_entries_with_password_and_params(mapped, &password, params.into())?)
+    }
+
+    pub fn kdf_params_for_profile(&self, profile: AuthenticatorKdfProfile) -> AuthenticatorKdfParams {
+        proton_authenticator::KdfParams::for_profile(profile.into()).into()
+    }
+
+    /// Benchmarks the device to find the strongest KDF parameters that take at most `target_ms`
+    pub fn calibrate_kdf_params(&self, target_ms: u64) -> Result<AuthenticatorKdfParams, AuthenticatorError> {
+        let params =
+            proton_authenticator::calibrate_kdf_params(target_ms, || jiff::Timestamp::now().as_millisecond() as u64)?;
+        Ok(params.into())
+    }
+
+    pub fn export_entries_to_aegis(
+        &self,
+        entries: Vec<AuthenticatorEntryModel>,
+        password: Option<String>,
+    ) -> Result<String, AuthenticatorError> {
+        let mut mapped = vec![];
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        Ok(self.inner.export_entries_to_aegis(mapped, password.as_deref())?)
+    }
+
+    pub fn export_entries_to_2fas(&self, entries: Vec<AuthenticatorEntryModel>) -> Result<String, AuthenticatorError> {
+        let mut mapped = vec![];
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        Ok(self.inner.export_entries_to_2fas(mapped)?)
+    }
+
+    pub fn export_entries_to_bitwarden(
+        &self,
+        entries: Vec<AuthenticatorEntryModel>,
+    ) -> Result<String, AuthenticatorError> {
+        let mut mapped = vec![];
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        Ok(self.inner.export_entries_to_bitwarden(mapped)?)
+    }
+
+    pub fn export_entries_to_otpauth_list(
+        &self,
+        entries: Vec<AuthenticatorEntryModel>,
+    ) -> Result<String, AuthenticatorError> {
+        let mut mapped = vec![];
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        Ok(self.inner.export_entries_to_otpauth_list(mapped))
+    }
+
+    pub fn export_entries_to_google_migration_qr(
+        &self,
+        entries: Vec<AuthenticatorEntryModel>,
+    ) -> Result<GoogleMigrationQrExport, AuthenticatorError> {
+        let mut mapped = vec![];
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        let export = self.inner.export_entries_to_google_migration(mapped)?;
+
+        let mut qr_codes = vec![];
+        for payload in export.payloads {
+            let svg = proton_pass_common::qr::generate_svg_qr_code(&payload).map_err(|e| {
+                warn!("Error generating Google Authenticator migration QR code: {:?}", e);
+                AuthenticatorError::SerializationError
+            })?;
+            qr_codes.push(svg);
+        }
+
+        Ok(GoogleMigrationQrExport {
+            qr_codes,
+            skipped_entry_ids: export.skipped,
+        })
+    }
+
+    /// Renders the entry as a QR code SVG that can be scanned by another device, with the issuer icon in the center
+    /// if provided. The icon must be a PNG, JPEG or SVG image
+    pub fn export_entry_to_svg_qr(
+        &self,
+        entry: AuthenticatorEntryModel,
+        icon: Option<AuthenticatorIssuerIcon>,
+    ) -> Result<String, AuthenticatorError> {
+        let uri = entry.to_entry()?.uri();
+        let logo = icon.as_ref().map(|icon| QrLogo {
+            data: &icon.data,
+            mime_type: &icon.mime_type,
+        });
+        proton_pass_common::qr::generate_svg_qr_code_with_logo(&uri, logo.as_ref()).map_err(|e| {
+            warn!("Error generating entry QR code: {:?}", e);
+            AuthenticatorError::SerializationError
+        })
+    }
+
+    /// Renders the entry as a PNG QR code of at least `size` pixels, with the issuer icon in the center if provided.
+    /// Sizes above 2048 pixels are rejected
+    pub fn export_entry_to_png_qr(
+        &self,
+        entry: AuthenticatorEntryModel,
+        size: u32,
+        icon: Option<AuthenticatorIssuerIcon>,
+    ) -> Result<Vec<u8>, AuthenticatorError> {
+        let uri = entry.to_entry()?.uri();
+        let logo = icon.as_ref().map(|icon| icon.data.as_slice());
+        proton_pass_common::qr::generate_png_qr_code(&uri, size, logo).map_err(|e| {
+            warn!("Error generating entry QR code: {:?}", e);
+            AuthenticatorError::SerializationError
+        })
+    }
+
     pub fn get_totp_params(
         &self,
         entry: AuthenticatorEntryModel,
@@ -0,0 +1,211 @@
+use crate::{AuthenticatorEntryModel, AuthenticatorError, AuthenticatorImportResult};
+use proton_authenticator::{BackupError, BackupProtection, BackupProtectionInfo, BackupRetentionPolicy, warn};
+
+#[derive(Debug, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum AuthenticatorBackupError {
+    BadContent,
+    UnsupportedVersion,
+    Corrupted,
+    AuthenticationFailed,
+    WrongProtection,
+    InvalidKey,
+    InvalidEntry,
+    DecryptionFailed,
+    Unknown,
+}
+
+impl std::fmt::Display for AuthenticatorBackupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<BackupError> for AuthenticatorBackupError {
+    fn from(e: BackupError) -> Self {
+        warn!("BackupError: {:?}", e);
+        match e {
+            BackupError::BadContent => Self::BadContent,
+            BackupError::UnsupportedVersion(_) => Self::UnsupportedVersion,
+            BackupError::Corrupted => Self::Corrupted,
+            BackupError::AuthenticationFailed => Self::AuthenticationFailed,
+            BackupError::WrongProtection => Self::WrongProtection,
+            BackupError::InvalidKey => Self::InvalidKey,
+            BackupError::Authenticator(e) => match AuthenticatorError::from(e) {
+                AuthenticatorError::ImportBadContent => Self::BadContent,
+                AuthenticatorError::ImportBadPassword | AuthenticatorError::ImportDecryptionFailed => {
+                    Self::DecryptionFailed
+                }
+                _ => Self::Unknown,
+            },
+        }
+    }
+}
+
+impl From<AuthenticatorError> for AuthenticatorBackupError {
+    fn from(e: AuthenticatorError) -> Self {
+        warn!("Invalid entry in backup: {:?}", e);
+        Self::InvalidEntry
+    }
+}
+
+#[derive(uniffi::Enum)]
+pub enum AuthenticatorBackupProtection {
+    Password { password: String },
+    Key { key: Vec<u8> },
+}
+
+impl From<AuthenticatorBackupProtection> for BackupProtection {
+    fn from(value: AuthenticatorBackupProtection) -> Self {
+        match value {
+            AuthenticatorBackupProtection::Password { password } => Self::Password(password),
+            AuthenticatorBackupProtection::Key { key } => Self::Key(key),
+        }
+    }
+}
+
+#[derive(uniffi::Enum)]
+pub enum AuthenticatorBackupProtectionType {
+    Password,
+    Key,
+}
+
+#[derive(uniffi::Record)]
+pub struct AuthenticatorBackupManifest {
+    pub version: u8,
+    /// Unix timestamp in seconds
+    pub created_at: i64,
+    pub app_version: String,
+    pub entry_count: u32,
+    pub content_hash: String,
+    pub protection: AuthenticatorBackupProtectionType,
+}
+
+impl From<proton_authenticator::BackupManifest> for AuthenticatorBackupManifest {
+    fn from(value: proton_authenticator::BackupManifest) -> Self {
+        Self {
+            version: value.version,
+            created_at: value.created_at,
+            app_version: value.app_version,
+            entry_count: value.entry_count,
+            content_hash: value.content_hash,
+            protection: match value.protection {
+                BackupProtectionInfo::Password { .. } => AuthenticatorBackupProtectionType::Password,
+                BackupProtectionInfo::Key { .. } => AuthenticatorBackupProtectionType::Key,
+            },
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct AuthenticatorBackupSnapshot {
+    pub file_name: String,
+    pub manifest: AuthenticatorBackupManifest,
+    pub contents: String,
+}
+
+impl From<proton_authenticator::BackupSnapshot> for AuthenticatorBackupSnapshot {
+    fn from(value: proton_authenticator::BackupSnapshot) -> Self {
+        Self {
+            file_name: value.file_name,
+            manifest: value.manifest.into(),
+            contents: value.contents,
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct AuthenticatorBackupFileInfo {
+    pub file_name: String,
+    pub created_at: i64,
+}
+
+impl From<proton_authenticator::BackupFileInfo> for AuthenticatorBackupFileInfo {
+    fn from(value: proton_authenticator::BackupFileInfo) -> Self {
+        Self {
+            file_name: value.file_name,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct AuthenticatorBackupRetentionPolicy {
+    pub keep_last: u32,
+    pub keep_daily: u32,
+}
+
+impl From<AuthenticatorBackupRetentionPolicy> for BackupRetentionPolicy {
+    fn from(value: AuthenticatorBackupRetentionPolicy) -> Self {
+        Self {
+            keep_last: value.keep_last,
+            keep_daily: value.keep_daily,
+        }
+    }
+}
+
+#[derive(uniffi::Object)]
+pub struct AuthenticatorBackupManager;
+
+#[uniffi::export]
+impl AuthenticatorBackupManager {
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn create_backup(
+        &self,
+        entries: Vec<AuthenticatorEntryModel>,
+        protection: AuthenticatorBackupProtection,
+        now: i64,
+    ) -> Result<AuthenticatorBackupSnapshot, AuthenticatorBackupError> {
+        let mut mapped = Vec::with_capacity(entries.len());
+        for entry in entries {
+            mapped.push(entry.to_entry().map_err(AuthenticatorError::from)?);
+        }
+        let snapshot = proton_authenticator::create_backup(mapped, &protection.into(), now)?;
+        Ok(snapshot.into())
+    }
+
+    pub fn read_backup_manifest(
+        &self,
+        contents: String,
+    ) -> Result<AuthenticatorBackupManifest, AuthenticatorBackupError> {
+        let manifest = proton_authenticator::read_backup_manifest(&contents)?;
+        Ok(manifest.into())
+    }
+
+    pub fn verify_backup(
+        &self,
+        contents: String,
+        protection: AuthenticatorBackupProtection,
+    ) -> Result<AuthenticatorBackupManifest, AuthenticatorBackupError> {
+        let manifest = proton_authenticator::verify_backup(&contents, &protection.into())?;
+        Ok(manifest.into())
+    }
+
+    pub fn restore_backup(
+        &self,
+        contents: String,
+        protection: AuthenticatorBackupProtection,
+    ) -> Result<AuthenticatorImportResult, AuthenticatorBackupError> {
+        let result = proton_authenticator::restore_backup(&contents, &protection.into())?;
+        Ok(result.into())
+    }
+
+    pub fn backup_file_name(&self, now: i64) -> String {
+        proton_authenticator::backup_file_name(now)
+    }
+
+    pub fn list_backups(&self, file_names: Vec<String>) -> Vec<AuthenticatorBackupFileInfo> {
+        proton_authenticator::list_backups(&file_names)
+            .into_iter()
+            .map(AuthenticatorBackupFileInfo::from)
+            .collect()
+    }
+
+    pub fn backups_to_prune(&self, file_names: Vec<String>, policy: AuthenticatorBackupRetentionPolicy) -> Vec<String> {
+        proton_authenticator::backups_to_prune(&file_names, &policy.into())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::AuthenticatorEntryModel;
+use crate::{AuthenticatorEntryModel, AuthenticatorEntryUsage};
 
 #[derive(Clone, Debug, uniffi::Error)]
 #[uniffi(flat_error)]
@@ -85,4 +85,56 @@ impl AuthenticatorCrypto {
         }
         Ok(res)
     }
+
+    /// Migrates encrypted entries from `old_key` to `new_key`
+    pub fn reencrypt_entries(
+        &self,
+        ciphertexts: Vec<Vec<u8>>,
+        old_key: Vec<u8>,
+        new_key: Vec<u8>,
+    ) -> Result<Vec<Vec<u8>>, AuthenticatorCryptoError> {
+        proton_authenticator::reencrypt_entries(ciphertexts, old_key, new_key).map_err(|e| {
+            proton_authenticator::emit_log_message(
+                proton_authenticator::LogLevel::Error,
+                format!("error reencrypting authenticator entries: {e:?}"),
+            );
+            AuthenticatorCryptoError::CryptoError
+        })
+    }
+
+    /// Returns the index of the key the entry was encrypted with
+    pub fn find_entry_key(&self, ciphertext: Vec<u8>, keys: Vec<Vec<u8>>) -> Option<u32> {
+        proton_authenticator::find_entry_key(&ciphertext, &keys).map(|idx| idx as u32)
+    }
+
+    /// Encrypts the usage records of all entries, which are synced separately from the entries
+    pub fn encrypt_usage(
+        &self,
+        usage: Vec<AuthenticatorEntryUsage>,
+        key: Vec<u8>,
+    ) -> Result<Vec<u8>, AuthenticatorCryptoError> {
+        let usage: Vec<proton_authenticator::EntryUsage> = usage.into_iter().map(Into::into).collect();
+        proton_authenticator::encrypt_usage(&usage, key).map_err(|e| {
+            proton_authenticator::emit_log_message(
+                proton_authenticator::LogLevel::Error,
+                format!("error encrypting entry usage: {e:?}"),
+            );
+            AuthenticatorCryptoError::CryptoError
+        })
+    }
+
+    pub fn decrypt_usage(
+        &self,
+        ciphertext: Vec<u8>,
+        key: Vec<u8>,
+    ) -> Result<Vec<AuthenticatorEntryUsage>, AuthenticatorCryptoError> {
+        let usage = proton_authenticator::decrypt_usage(&ciphertext, key).map_err(|e| {
+            proton_authenticator::emit_log_message(
+                proton_authenticator::LogLevel::Error,
+                format!("error decrypting entry usage: {e:?}"),
+            );
+            AuthenticatorCryptoError::CryptoError
+        })?;
+        Ok(usage.into_iter().map(AuthenticatorEntryUsage::from).collect())
+    }
 }
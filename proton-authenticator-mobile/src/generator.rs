@@ -8,7 +8,10 @@ use std::sync::{Arc, Mutex};
 // Current Time Provider
 #[uniffi::export(with_foreign)]
 pub trait MobileCurrentTimeProvider: Send + Sync {
+    /// Current unix time in seconds
     fn now(&self) -> u64;
+    /// Current unix time in milliseconds, used to wake up right when the codes change
+    fn now_ms(&self) -> u64;
 }
 
 pub struct MobileTimeAdapter {
@@ -19,12 +22,20 @@ impl GeneratorCurrentTimeProvider for MobileTimeAdapter {
     fn now(&self) -> u64 {
         self.inner.now()
     }
+
+    fn now_ms(&self) -> u64 {
+        self.inner.now_ms()
+    }
 }
 
 // TotpGenerationHandle
 #[uniffi::export(with_foreign)]
 pub trait MobileTotpGenerationHandle: Send + Sync {
     fn cancel(&self);
+    /// Adds entries to the running generator. Entries with the id of an existing one replace it
+    fn add_entries(&self, entries: Vec<AuthenticatorEntryModel>) -> Result<(), AuthenticatorError>;
+    fn remove_entries(&self, entry_ids: Vec<String>);
+    fn set_entries(&self, entries: Vec<AuthenticatorEntryModel>) -> Result<(), AuthenticatorError>;
 }
 
 pub struct MobileTotpGenerationHandleAdapter {
@@ -37,6 +48,38 @@ impl MobileTotpGenerationHandle for MobileTotpGenerationHandleAdapter {
             handle.cancel();
         }
     }
+
+    fn add_entries(&self, entries: Vec<AuthenticatorEntryModel>) -> Result<(), AuthenticatorError> {
+        let as_entries = to_entries(entries)?;
+        if let Ok(handle) = self.inner.lock() {
+            handle.add_entries(as_entries);
+        }
+        Ok(())
+    }
+
+    fn remove_entries(&self, entry_ids: Vec<String>) {
+        if let Ok(handle) = self.inner.lock() {
+            handle.remove_entries(&entry_ids);
+        }
+    }
+
+    fn set_entries(&self, entries: Vec<AuthenticatorEntryModel>) -> Result<(), AuthenticatorError> {
+        let as_entries = to_entries(entries)?;
+        if let Ok(handle) = self.inner.lock() {
+            handle.set_entries(as_entries);
+        }
+        Ok(())
+    }
+}
+
+fn to_entries(
+    entries: Vec<AuthenticatorEntryModel>,
+) -> Result<Vec<proton_authenticator::AuthenticatorEntry>, AuthenticatorError> {
+    let mut as_entries = vec![];
+    for entry in entries {
+        as_entries.push(entry.to_entry()?);
+    }
+    Ok(as_entries)
 }
 
 // TotpGeneratorCallback
@@ -76,8 +119,9 @@ const RUNTIME_THREADS: usize = 1;
 #[uniffi::export]
 impl MobileTotpGenerator {
     /// Create a new instance of the TOTP generator
-    /// - period_ms: how often the generator should check if the codes have changed. Time in ms
-    /// - only_on_code_change: if true, only invoke the callback if the codes have changed. If false, it will always be called
+    /// - period_ms: how often the callback is invoked when only_on_code_change is false. Time in ms
+    /// - only_on_code_change: if true, the generator sleeps until the next code change and only invokes the callback
+    ///   with the entries whose code changed. If false, it will be called with all the codes every period_ms
     /// - current_time_provider: callback that will be invoked to get the current time
     #[uniffi::constructor]
     pub fn new(
@@ -107,10 +151,7 @@ impl MobileTotpGenerator {
         entries: Vec<AuthenticatorEntryModel>,
         callback: Arc<dyn MobileTotpGeneratorCallback>,
     ) -> Result<Arc<dyn MobileTotpGenerationHandle>, AuthenticatorError> {
-        let mut as_entries = vec![];
-        for entry in entries {
-            as_entries.push(entry.to_entry()?);
-        }
+        let as_entries = to_entries(entries)?;
         let adapted_callback = MobileTotpGeneratorCallbackAdapter { inner: callback };
         Ok(self.rt.handle().block_on(async move {
             let res = self.inner.start_async(as_entries, adapted_callback).await;
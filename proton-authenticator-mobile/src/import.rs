@@ -1,5 +1,5 @@
 use crate::{AuthenticatorEntryModel, AuthenticatorError};
-use proton_authenticator::ThirdPartyImportError;
+use proton_authenticator::{DetectedFormat, DetectedImportError, ImportFormat, ThirdPartyImportError};
 
 type ImportResult = Result<AuthenticatorImportResult, AuthenticatorImportException>;
 
@@ -16,6 +16,7 @@ pub enum AuthenticatorImportException {
     BadPassword,
     MissingPassword,
     DecryptionFailed,
+    UnknownFormat,
 }
 
 impl std::fmt::Display for AuthenticatorImportException {
@@ -35,6 +36,15 @@ impl From<ThirdPartyImportError> for AuthenticatorImportException {
     }
 }
 
+impl From<DetectedImportError> for AuthenticatorImportException {
+    fn from(err: DetectedImportError) -> Self {
+        match err {
+            DetectedImportError::UnknownFormat => Self::UnknownFormat,
+            DetectedImportError::Import(_, err) => Self::from(err),
+        }
+    }
+}
+
 impl From<proton_authenticator::ImportError> for AuthenticatorImportError {
     fn from(err: proton_authenticator::ImportError) -> Self {
         Self {
@@ -70,6 +80,28 @@ impl From<proton_authenticator::ImportResult> for AuthenticatorImportResult {
     }
 }
 
+#[derive(uniffi::Record)]
+pub struct AuthenticatorDetectedImportResult {
+    pub format: ImportFormat,
+    pub result: AuthenticatorImportResult,
+}
+
+/// A file of the maFiles folder of Steam Desktop Authenticator
+#[derive(uniffi::Record)]
+pub struct AuthenticatorSteamMaFile {
+    pub file_name: String,
+    pub contents: String,
+}
+
+impl From<AuthenticatorSteamMaFile> for proton_authenticator::SteamMaFile {
+    fn from(file: AuthenticatorSteamMaFile) -> Self {
+        Self {
+            file_name: file.file_name,
+            contents: file.contents,
+        }
+    }
+}
+
 #[derive(uniffi::Object)]
 pub struct AuthenticatorImporter;
 
@@ -80,6 +112,22 @@ impl AuthenticatorImporter {
         Self
     }
 
+    pub fn detect_import_format(&self, contents: Vec<u8>) -> Option<DetectedFormat> {
+        proton_authenticator::detect_import_format(&contents)
+    }
+
+    pub fn import_with_detected_format(
+        &self,
+        contents: Vec<u8>,
+        password: Option<String>,
+    ) -> Result<AuthenticatorDetectedImportResult, AuthenticatorImportException> {
+        let res = proton_authenticator::detect_and_parse(&contents, password)?;
+        Ok(AuthenticatorDetectedImportResult {
+            format: res.format,
+            result: AuthenticatorImportResult::from(res.result),
+        })
+    }
+
     pub fn import_from_aegis_json(&self, contents: String, password: Option<String>) -> ImportResult {
         let res = proton_authenticator::parse_aegis_json(&contents, password).map_err(ThirdPartyImportError::from)?;
         Ok(AuthenticatorImportResult::from(res))
@@ -90,6 +138,16 @@ impl AuthenticatorImporter {
         Ok(AuthenticatorImportResult::from(res))
     }
 
+    pub fn import_from_andotp(&self, contents: Vec<u8>, password: Option<String>) -> ImportResult {
+        let res = proton_authenticator::parse_andotp_file(&contents, password).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_authy_json(&self, contents: String) -> ImportResult {
+        let res = proton_authenticator::parse_authy_json(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
     pub fn import_from_bitwarden_json(&self, contents: String) -> ImportResult {
         let res = proton_authenticator::parse_bitwarden_json(&contents).map_err(ThirdPartyImportError::from)?;
         Ok(AuthenticatorImportResult::from(res))
@@ -111,17 +169,66 @@ impl AuthenticatorImporter {
         Ok(AuthenticatorImportResult::from(res))
     }
 
+    pub fn import_from_freeotp_plus_json(&self, contents: String) -> ImportResult {
+        let res = proton_authenticator::parse_freeotp_plus_json(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_freeotp_xml(&self, contents: String) -> ImportResult {
+        let res = proton_authenticator::parse_freeotp_xml(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_freeotp_backup(&self, contents: Vec<u8>, password: Option<String>) -> ImportResult {
+        let res =
+            proton_authenticator::parse_freeotp_backup(&contents, password).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
     pub fn import_from_google_qr(&self, contents: String) -> ImportResult {
         let res =
             proton_authenticator::parse_google_authenticator_totp(&contents).map_err(ThirdPartyImportError::from)?;
         Ok(AuthenticatorImportResult::from(res))
     }
 
+    pub fn import_from_qr_images(&self, images: Vec<Vec<u8>>) -> ImportResult {
+        let res = proton_authenticator::qr::import_from_qr_images(&images);
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_keepass_csv(&self, contents: String) -> ImportResult {
+        let res = proton_authenticator::parse_keepass_csv(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_keepass_kdbx(&self, contents: Vec<u8>, password: String) -> ImportResult {
+        let res =
+            proton_authenticator::parse_keepass_kdbx(&contents, &password).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
     pub fn import_from_lastpass_json(&self, contents: String) -> ImportResult {
         let res = proton_authenticator::parse_lastpass_json(&contents).map_err(ThirdPartyImportError::from)?;
         Ok(AuthenticatorImportResult::from(res))
     }
 
+    pub fn import_from_microsoft_authenticator_csv(&self, contents: String) -> ImportResult {
+        let res =
+            proton_authenticator::parse_microsoft_authenticator_csv(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_microsoft_authenticator_json(&self, contents: String) -> ImportResult {
+        let res =
+            proton_authenticator::parse_microsoft_authenticator_json(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_otpauth_db(&self, contents: Vec<u8>, password: Option<String>) -> ImportResult {
+        let res = proton_authenticator::parse_otpauth_db(&contents, password).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
     pub fn import_from_proton_authenticator(&self, contents: String) -> ImportResult {
         let res = proton_authenticator::parse_proton_authenticator_export(&contents)?;
         Ok(AuthenticatorImportResult::from(res))
@@ -132,6 +239,47 @@ impl AuthenticatorImporter {
         Ok(AuthenticatorImportResult::from(res))
     }
 
+    pub fn import_from_raivo_json(&self, contents: String) -> ImportResult {
+        let res = proton_authenticator::parse_raivo_json(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_raivo_zip(&self, contents: Vec<u8>, password: Option<String>) -> ImportResult {
+        let res = proton_authenticator::parse_raivo_zip(&contents, password).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    pub fn import_from_steam_mafile(&self, contents: String) -> ImportResult {
+        let res = proton_authenticator::parse_steam_mafile(&contents).map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    /// iv and salt are the encryption_iv and encryption_salt of the file in the manifest.json of the maFiles folder
+    pub fn import_from_steam_mafile_encrypted(
+        &self,
+        contents: String,
+        password: String,
+        iv: String,
+        salt: String,
+    ) -> ImportResult {
+        let res = proton_authenticator::parse_steam_mafile_encrypted(&contents, &password, &iv, &salt)
+            .map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
+    /// Imports the maFiles folder of Steam Desktop Authenticator from its manifest.json and the maFiles it contains
+    pub fn import_from_steam_mafiles(
+        &self,
+        manifest: String,
+        files: Vec<AuthenticatorSteamMaFile>,
+        password: Option<String>,
+    ) -> ImportResult {
+        let files: Vec<proton_authenticator::SteamMaFile> = files.into_iter().map(Into::into).collect();
+        let res = proton_authenticator::parse_steam_mafiles(&manifest, &files, password)
+            .map_err(ThirdPartyImportError::from)?;
+        Ok(AuthenticatorImportResult::from(res))
+    }
+
     pub fn import_from_2fas(&self, contents: String, password: Option<String>) -> ImportResult {
         let res = proton_authenticator::parse_2fas_file(&contents, password).map_err(ThirdPartyImportError::from)?;
         Ok(AuthenticatorImportResult::from(res))
@@ -0,0 +1,253 @@
+use crate::{AuthenticatorEntryModel, AuthenticatorError, AuthenticatorImportError, AuthenticatorImportResult};
+use proton_authenticator::import_planner::{
+    ImportConflict as CommonImportConflict, ImportConflictField as CommonImportConflictField,
+    ImportConflictResolution as CommonImportConflictResolution, ImportDecisions as CommonImportDecisions,
+    ImportDuplicate as CommonImportDuplicate, ImportMerge as CommonImportMerge, ImportPreview as CommonImportPreview,
+    apply_import, plan_import,
+};
+use proton_authenticator::{AuthenticatorEntry, ImportError, ImportResult};
+
+#[derive(uniffi::Enum)]
+pub enum ImportConflictField {
+    Type,
+    Name,
+    Issuer,
+    Note,
+    Algorithm,
+    Digits,
+    Period,
+}
+
+impl From<CommonImportConflictField> for ImportConflictField {
+    fn from(value: CommonImportConflictField) -> Self {
+        match value {
+            CommonImportConflictField::Type => ImportConflictField::Type,
+            CommonImportConflictField::Name => ImportConflictField::Name,
+            CommonImportConflictField::Issuer => ImportConflictField::Issuer,
+            CommonImportConflictField::Note => ImportConflictField::Note,
+            CommonImportConflictField::Algorithm => ImportConflictField::Algorithm,
+            CommonImportConflictField::Digits => ImportConflictField::Digits,
+            CommonImportConflictField::Period => ImportConflictField::Period,
+        }
+    }
+}
+
+impl From<ImportConflictField> for CommonImportConflictField {
+    fn from(value: ImportConflictField) -> Self {
+        match value {
+            ImportConflictField::Type => CommonImportConflictField::Type,
+            ImportConflictField::Name => CommonImportConflictField::Name,
+            ImportConflictField::Issuer => CommonImportConflictField::Issuer,
+            ImportConflictField::Note => CommonImportConflictField::Note,
+            ImportConflictField::Algorithm => CommonImportConflictField::Algorithm,
+            ImportConflictField::Digits => CommonImportConflictField::Digits,
+            ImportConflictField::Period => CommonImportConflictField::Period,
+        }
+    }
+}
+
+#[derive(uniffi::Enum)]
+pub enum ImportConflictResolution {
+    KeepExisting,
+    KeepImported,
+    KeepBoth,
+}
+
+impl From<ImportConflictResolution> for CommonImportConflictResolution {
+    fn from(value: ImportConflictResolution) -> Self {
+        match value {
+            ImportConflictResolution::KeepExisting => CommonImportConflictResolution::KeepExisting,
+            ImportConflictResolution::KeepImported => CommonImportConflictResolution::KeepImported,
+            ImportConflictResolution::KeepBoth => CommonImportConflictResolution::KeepBoth,
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct ImportDuplicate {
+    pub existing_id: String,
+    pub imported: AuthenticatorEntryModel,
+}
+
+#[derive(uniffi::Record)]
+pub struct ImportConflict {
+    pub existing: AuthenticatorEntryModel,
+    pub imported: AuthenticatorEntryModel,
+    pub fields: Vec<ImportConflictField>,
+}
+
+#[derive(uniffi::Record)]
+pub struct ImportMerge {
+    pub existing: AuthenticatorEntryModel,
+    pub imported: AuthenticatorEntryModel,
+    pub merged: AuthenticatorEntryModel,
+}
+
+#[derive(uniffi::Record)]
+pub struct ImportPreview {
+    pub new_entries: Vec<AuthenticatorEntryModel>,
+    pub duplicates: Vec<ImportDuplicate>,
+    pub conflicts: Vec<ImportConflict>,
+    pub merges: Vec<ImportMerge>,
+    pub errors: Vec<AuthenticatorImportError>,
+}
+
+#[derive(uniffi::Record)]
+pub struct ImportConflictDecision {
+    pub imported_id: String,
+    pub resolution: ImportConflictResolution,
+}
+
+#[derive(uniffi::Record)]
+pub struct ImportDecisions {
+    pub apply_merges: bool,
+    pub conflict_resolutions: Vec<ImportConflictDecision>,
+}
+
+impl From<CommonImportPreview> for ImportPreview {
+    fn from(value: CommonImportPreview) -> Self {
+        Self {
+            new_entries: value
+                .new_entries
+                .into_iter()
+                .map(AuthenticatorEntryModel::from)
+                .collect(),
+            duplicates: value
+                .duplicates
+                .into_iter()
+                .map(|d| ImportDuplicate {
+                    existing_id: d.existing_id,
+                    imported: AuthenticatorEntryModel::from(d.imported),
+                })
+                .collect(),
+            conflicts: value
+                .conflicts
+                .into_iter()
+                .map(|c| ImportConflict {
+                    existing: AuthenticatorEntryModel::from(c.existing),
+                    imported: AuthenticatorEntryModel::from(c.imported),
+                    fields: c.fields.into_iter().map(ImportConflictField::from).collect(),
+                })
+                .collect(),
+            merges: value
+                .merges
+                .into_iter()
+                .map(|m| ImportMerge {
+                    existing: AuthenticatorEntryModel::from(m.existing),
+                    imported: AuthenticatorEntryModel::from(m.imported),
+                    merged: AuthenticatorEntryModel::from(m.merged),
+                })
+                .collect(),
+            errors: value.errors.into_iter().map(AuthenticatorImportError::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<ImportPreview> for CommonImportPreview {
+    type Error = AuthenticatorError;
+
+    fn try_from(value: ImportPreview) -> Result<Self, Self::Error> {
+        let mut preview = CommonImportPreview {
+            new_entries: map_entries(value.new_entries)?,
+            ..Default::default()
+        };
+        for duplicate in value.duplicates {
+            preview.duplicates.push(CommonImportDuplicate {
+                existing_id: duplicate.existing_id,
+                imported: duplicate.imported.to_entry()?,
+            });
+        }
+        for conflict in value.conflicts {
+            preview.conflicts.push(CommonImportConflict {
+                existing: conflict.existing.to_entry()?,
+                imported: conflict.imported.to_entry()?,
+                fields: conflict
+                    .fields
+                    .into_iter()
+                    .map(CommonImportConflictField::from)
+                    .collect(),
+            });
+        }
+        for merge in value.merges {
+            preview.merges.push(CommonImportMerge {
+                existing: merge.existing.to_entry()?,
+                imported: merge.imported.to_entry()?,
+                merged: merge.merged.to_entry()?,
+            });
+        }
+        preview.errors = value
+            .errors
+            .into_iter()
+            .map(|e| ImportError {
+                context: e.context,
+                message: e.message,
+            })
+            .collect();
+        Ok(preview)
+    }
+}
+
+impl From<ImportDecisions> for CommonImportDecisions {
+    fn from(value: ImportDecisions) -> Self {
+        Self {
+            apply_merges: value.apply_merges,
+            conflict_resolutions: value
+                .conflict_resolutions
+                .into_iter()
+                .map(|d| (d.imported_id, CommonImportConflictResolution::from(d.resolution)))
+                .collect(),
+        }
+    }
+}
+
+fn map_entries(entries: Vec<AuthenticatorEntryModel>) -> Result<Vec<AuthenticatorEntry>, AuthenticatorError> {
+    let mut mapped = Vec::with_capacity(entries.len());
+    for entry in entries {
+        mapped.push(entry.to_entry()?);
+    }
+    Ok(mapped)
+}
+
+#[derive(uniffi::Object)]
+pub struct AuthenticatorImportPlanner;
+
+#[uniffi::export]
+impl AuthenticatorImportPlanner {
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn plan_import(
+        &self,
+        existing: Vec<AuthenticatorEntryModel>,
+        import: AuthenticatorImportResult,
+    ) -> Result<ImportPreview, AuthenticatorError> {
+        let existing = map_entries(existing)?;
+        let import = ImportResult {
+            entries: map_entries(import.entries)?,
+            errors: import
+                .errors
+                .into_iter()
+                .map(|e| ImportError {
+                    context: e.context,
+                    message: e.message,
+                })
+                .collect(),
+        };
+
+        Ok(ImportPreview::from(plan_import(&existing, import)))
+    }
+
+    pub fn apply_import(
+        &self,
+        existing: Vec<AuthenticatorEntryModel>,
+        preview: ImportPreview,
+        decisions: ImportDecisions,
+    ) -> Result<Vec<AuthenticatorEntryModel>, AuthenticatorError> {
+        let existing = map_entries(existing)?;
+        let preview = CommonImportPreview::try_from(preview)?;
+        let res = apply_import(existing, preview, &CommonImportDecisions::from(decisions));
+        Ok(res.into_iter().map(AuthenticatorEntryModel::from).collect())
+    }
+}
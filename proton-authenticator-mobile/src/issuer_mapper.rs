@@ -1,4 +1,29 @@
-use proton_authenticator::TOTPIssuerMapper;
+use proton_authenticator::{IconBundleError, IssuerIcon, IssuerMatchKind, TOTPIssuerMapper, warn};
+
+#[derive(Debug, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum AuthenticatorIconBundleError {
+    BadContent,
+    UnsupportedVersion,
+    Corrupted,
+}
+
+impl std::fmt::Display for AuthenticatorIconBundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<IconBundleError> for AuthenticatorIconBundleError {
+    fn from(e: IconBundleError) -> Self {
+        warn!("IconBundleError: {:?}", e);
+        match e {
+            IconBundleError::BadContent(_) => Self::BadContent,
+            IconBundleError::UnsupportedVersion(_) => Self::UnsupportedVersion,
+            IconBundleError::Corrupted(_) | IconBundleError::MissingIcon(_) => Self::Corrupted,
+        }
+    }
+}
 
 #[derive(uniffi::Object)]
 pub struct AuthenticatorIssuerMapper {
@@ -20,6 +45,58 @@ impl From<proton_authenticator::issuer_mapper::IssuerInfo> for IssuerInfo {
     }
 }
 
+#[derive(uniffi::Enum)]
+pub enum AuthenticatorIssuerMatchKind {
+    Exact,
+    Domain,
+    Normalized,
+    Fuzzy,
+}
+
+impl From<IssuerMatchKind> for AuthenticatorIssuerMatchKind {
+    fn from(value: IssuerMatchKind) -> Self {
+        match value {
+            IssuerMatchKind::Exact => Self::Exact,
+            IssuerMatchKind::Domain => Self::Domain,
+            IssuerMatchKind::Normalized => Self::Normalized,
+            IssuerMatchKind::Fuzzy => Self::Fuzzy,
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct AuthenticatorIssuerMatch {
+    pub info: IssuerInfo,
+    pub kind: AuthenticatorIssuerMatchKind,
+    /// From 0 to 1
+    pub confidence: f32,
+}
+
+impl From<proton_authenticator::IssuerMatch> for AuthenticatorIssuerMatch {
+    fn from(value: proton_authenticator::IssuerMatch) -> Self {
+        Self {
+            info: value.info.into(),
+            kind: value.kind.into(),
+            confidence: value.confidence,
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct AuthenticatorIssuerIcon {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl From<IssuerIcon> for AuthenticatorIssuerIcon {
+    fn from(value: IssuerIcon) -> Self {
+        Self {
+            mime_type: value.mime_type,
+            data: value.data,
+        }
+    }
+}
+
 #[uniffi::export]
 impl AuthenticatorIssuerMapper {
     #[uniffi::constructor]
@@ -29,7 +106,34 @@ impl AuthenticatorIssuerMapper {
         }
     }
 
+    /// Create a mapper that serves the issuer icons from the bundle generated by the icon fetcher
+    #[uniffi::constructor]
+    pub fn with_icon_bundle(bundle: Vec<u8>) -> Result<Self, AuthenticatorIconBundleError> {
+        Ok(Self {
+            inner: TOTPIssuerMapper::with_icon_bundle(&bundle)?,
+        })
+    }
+
     pub fn lookup(&self, issuer: String) -> Option<IssuerInfo> {
         self.inner.lookup(&issuer).map(IssuerInfo::from)
     }
+
+    /// Same as lookup, but also tells how the issuer was matched and how confident the match is
+    pub fn find(&self, issuer: String) -> Option<AuthenticatorIssuerMatch> {
+        self.inner.find(&issuer).map(AuthenticatorIssuerMatch::from)
+    }
+
+    /// Finds the issuer of a URL or domain
+    pub fn find_by_url(&self, url: String) -> Option<AuthenticatorIssuerMatch> {
+        self.inner.find_by_url(&url).map(AuthenticatorIssuerMatch::from)
+    }
+
+    /// Icon of the issuer from the icon bundle. Always None if the mapper was created without one
+    pub fn icon(&self, issuer: String) -> Option<AuthenticatorIssuerIcon> {
+        self.inner.icon(&issuer).map(AuthenticatorIssuerIcon::from)
+    }
+
+    pub fn icon_for_url(&self, url: String) -> Option<AuthenticatorIssuerIcon> {
+        self.inner.icon_for_url(&url).map(AuthenticatorIssuerIcon::from)
+    }
 }
@@ -1,16 +1,19 @@
 #![allow(clippy::new_without_default)]
 
 mod authenticator;
+mod backup;
 mod benchmark;
 mod crypto;
 mod entry;
 mod generator;
 mod import;
+mod import_planner;
 mod issuer_mapper;
 mod log;
 mod operations;
 mod ordering;
 mod qr;
+mod search;
 
 uniffi::setup_scaffolding!();
 
@@ -24,13 +27,16 @@ pub fn library_version() -> String {
 }
 
 pub use authenticator::*;
+pub use backup::*;
 pub use benchmark::*;
 pub use crypto::*;
 pub use entry::*;
 pub use generator::*;
 pub use import::*;
+pub use import_planner::*;
 pub use issuer_mapper::*;
 pub use log::*;
 pub use operations::*;
 pub use ordering::*;
 pub use qr::*;
+pub use search::*;
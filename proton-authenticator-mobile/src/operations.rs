@@ -1,7 +1,8 @@
 use crate::{AuthenticatorEntryModel, AuthenticatorError};
 use proton_authenticator::operations::{
     AuthenticatorEntryState as CommonEntryState, AuthenticatorOperation, EntryOperation as CommonEntryOperation,
-    LocalEntry as CommonLocalEntry, RemoteEntry as CommonRemoteEntry,
+    LocalEntry as CommonLocalEntry, LocalSyncEntry as CommonLocalSyncEntry, OrderUpdate as CommonOrderUpdate,
+    RemoteEntry as CommonRemoteEntry, RemoteSyncEntry as CommonRemoteSyncEntry, SyncPlan as CommonSyncPlan,
 };
 
 #[derive(uniffi::Enum)]
@@ -25,8 +26,10 @@ impl From<LocalEntryState> for CommonEntryState {
 pub struct LocalEntry {
     pub entry: AuthenticatorEntryModel,
     pub state: LocalEntryState,
+    pub remote_id: Option<String>,
     pub modify_time: i64,
     pub local_modify_time: Option<i64>,
+    pub base: Option<AuthenticatorEntryModel>,
 }
 
 impl TryFrom<LocalEntry> for CommonLocalEntry {
@@ -36,8 +39,13 @@ impl TryFrom<LocalEntry> for CommonLocalEntry {
         Ok(Self {
             entry: value.entry.to_entry()?,
             state: CommonEntryState::from(value.state),
+            remote_id: value.remote_id,
             modify_time: value.modify_time,
             local_modify_time: value.local_modify_time,
+            base: match value.base {
+                Some(base) => Some(base.to_entry()?),
+                None => None,
+            },
         })
     }
 }
@@ -48,6 +56,7 @@ pub struct RemoteEntry {
     pub revision: u32,
     pub entry: AuthenticatorEntryModel,
     pub modify_time: i64,
+    pub delete_time: Option<i64>,
 }
 
 impl TryFrom<RemoteEntry> for CommonRemoteEntry {
@@ -59,6 +68,7 @@ impl TryFrom<RemoteEntry> for CommonRemoteEntry {
             revision: value.revision,
             entry: value.entry.to_entry()?,
             modify_time: value.modify_time,
+            delete_time: value.delete_time,
         })
     }
 }
@@ -69,6 +79,10 @@ pub enum OperationType {
     DeleteLocal,
     DeleteLocalAndRemote,
     Push,
+    Merge,
+    Conflict,
+    RemoteDeletedLocalEdited,
+    LocalDeletedRemoteEdited,
 }
 
 impl From<AuthenticatorOperation> for OperationType {
@@ -78,6 +92,10 @@ impl From<AuthenticatorOperation> for OperationType {
             AuthenticatorOperation::DeleteLocal => OperationType::DeleteLocal,
             AuthenticatorOperation::DeleteLocalAndRemote => OperationType::DeleteLocalAndRemote,
             AuthenticatorOperation::Push => OperationType::Push,
+            AuthenticatorOperation::Merge => OperationType::Merge,
+            AuthenticatorOperation::Conflict => OperationType::Conflict,
+            AuthenticatorOperation::RemoteDeletedLocalEdited => OperationType::RemoteDeletedLocalEdited,
+            AuthenticatorOperation::LocalDeletedRemoteEdited => OperationType::LocalDeletedRemoteEdited,
         }
     }
 }
@@ -101,6 +119,80 @@ impl From<CommonEntryOperation> for EntryOperation {
     }
 }
 
+#[derive(uniffi::Record)]
+pub struct LocalSyncEntry {
+    pub local: LocalEntry,
+    pub order_key: String,
+    pub order_modify_time: i64,
+}
+
+impl TryFrom<LocalSyncEntry> for CommonLocalSyncEntry {
+    type Error = AuthenticatorError;
+
+    fn try_from(value: LocalSyncEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            local: CommonLocalEntry::try_from(value.local)?,
+            order_key: value.order_key,
+            order_modify_time: value.order_modify_time,
+        })
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct RemoteSyncEntry {
+    pub remote: RemoteEntry,
+    pub order_key: String,
+    pub order_modify_time: i64,
+}
+
+impl TryFrom<RemoteSyncEntry> for CommonRemoteSyncEntry {
+    type Error = AuthenticatorError;
+
+    fn try_from(value: RemoteSyncEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            remote: CommonRemoteEntry::try_from(value.remote)?,
+            order_key: value.order_key,
+            order_modify_time: value.order_modify_time,
+        })
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct OrderUpdate {
+    pub entry_id: String,
+    pub order_key: String,
+    pub update_local: bool,
+    pub update_remote: bool,
+}
+
+impl From<CommonOrderUpdate> for OrderUpdate {
+    fn from(value: CommonOrderUpdate) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            order_key: value.order_key,
+            update_local: value.update_local,
+            update_remote: value.update_remote,
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct SyncPlan {
+    pub operations: Vec<EntryOperation>,
+    pub order_updates: Vec<OrderUpdate>,
+    pub ordered_ids: Vec<String>,
+}
+
+impl From<CommonSyncPlan> for SyncPlan {
+    fn from(value: CommonSyncPlan) -> Self {
+        Self {
+            operations: value.operations.into_iter().map(EntryOperation::from).collect(),
+            order_updates: value.order_updates.into_iter().map(OrderUpdate::from).collect(),
+            ordered_ids: value.ordered_ids,
+        }
+    }
+}
+
 #[derive(uniffi::Object)]
 pub struct SyncOperationChecker;
 
@@ -116,22 +208,56 @@ impl SyncOperationChecker {
         remote: Vec<RemoteEntry>,
         local: Vec<LocalEntry>,
     ) -> Result<Vec<EntryOperation>, AuthenticatorError> {
+        let (remote_mapped, local_mapped) = map_entries(remote, local)?;
+        let ops = proton_authenticator::operations::calculate_operations_to_perform(remote_mapped, local_mapped);
+        Ok(ops.into_iter().map(EntryOperation::from).collect())
+    }
+
+    pub fn calculate_operations_for_partial_listing(
+        &self,
+        remote: Vec<RemoteEntry>,
+        local: Vec<LocalEntry>,
+    ) -> Result<Vec<EntryOperation>, AuthenticatorError> {
+        let (remote_mapped, local_mapped) = map_entries(remote, local)?;
+        let ops =
+            proton_authenticator::operations::calculate_operations_for_partial_listing(remote_mapped, local_mapped);
+        Ok(ops.into_iter().map(EntryOperation::from).collect())
+    }
+
+    pub fn plan_sync(
+        &self,
+        remote: Vec<RemoteSyncEntry>,
+        local: Vec<LocalSyncEntry>,
+        complete_listing: bool,
+    ) -> Result<SyncPlan, AuthenticatorError> {
         let mut remote_mapped = Vec::with_capacity(remote.len());
         for remote_entry in remote {
-            remote_mapped.push(CommonRemoteEntry::try_from(remote_entry)?);
+            remote_mapped.push(CommonRemoteSyncEntry::try_from(remote_entry)?);
         }
 
         let mut local_mapped = Vec::with_capacity(local.len());
         for local_entry in local {
-            local_mapped.push(CommonLocalEntry::try_from(local_entry)?);
+            local_mapped.push(CommonLocalSyncEntry::try_from(local_entry)?);
         }
 
-        let ops = proton_authenticator::operations::calculate_operations_to_perform(remote_mapped, local_mapped);
-        let mut result: Vec<EntryOperation> = Vec::new();
-        for op in ops {
-            result.push(EntryOperation::from(op));
-        }
+        let plan = proton_authenticator::operations::plan_sync(remote_mapped, local_mapped, complete_listing);
+        Ok(SyncPlan::from(plan))
+    }
+}
 
-        Ok(result)
+fn map_entries(
+    remote: Vec<RemoteEntry>,
+    local: Vec<LocalEntry>,
+) -> Result<(Vec<CommonRemoteEntry>, Vec<CommonLocalEntry>), AuthenticatorError> {
+    let mut remote_mapped = Vec::with_capacity(remote.len());
+    for remote_entry in remote {
+        remote_mapped.push(CommonRemoteEntry::try_from(remote_entry)?);
     }
+
+    let mut local_mapped = Vec::with_capacity(local.len());
+    for local_entry in local {
+        local_mapped.push(CommonLocalEntry::try_from(local_entry)?);
+    }
+
+    Ok((remote_mapped, local_mapped))
 }
@@ -1,5 +1,8 @@
 use crate::{AuthenticatorEntryModel, AuthenticatorError};
-use proton_authenticator::ordering::{EntryWithOrder, reorder_items};
+use proton_authenticator::EntryUsage;
+use proton_authenticator::ordering::{
+    EntrySortMode, EntryWithOrder, initial_keys, key_between, reorder_items, sort_entries,
+};
 
 #[derive(uniffi::Record)]
 pub struct AuthenticatorEntryWithOrder {
@@ -31,6 +34,53 @@ impl From<EntryWithOrder> for AuthenticatorEntryWithOrder {
     }
 }
 
+#[derive(uniffi::Record)]
+pub struct AuthenticatorEntryUsage {
+    pub entry_id: String,
+    /// Unix timestamp in seconds
+    pub last_used: Option<i64>,
+    pub use_count: u32,
+}
+
+impl From<AuthenticatorEntryUsage> for EntryUsage {
+    fn from(value: AuthenticatorEntryUsage) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            last_used: value.last_used,
+            use_count: value.use_count,
+        }
+    }
+}
+
+impl From<EntryUsage> for AuthenticatorEntryUsage {
+    fn from(value: EntryUsage) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            last_used: value.last_used,
+            use_count: value.use_count,
+        }
+    }
+}
+
+#[derive(uniffi::Enum)]
+pub enum AuthenticatorEntrySortMode {
+    Manual,
+    RecentlyUsed,
+    Alphabetical,
+    Issuer,
+}
+
+impl From<AuthenticatorEntrySortMode> for EntrySortMode {
+    fn from(value: AuthenticatorEntrySortMode) -> Self {
+        match value {
+            AuthenticatorEntrySortMode::Manual => Self::Manual,
+            AuthenticatorEntrySortMode::RecentlyUsed => Self::RecentlyUsed,
+            AuthenticatorEntrySortMode::Alphabetical => Self::Alphabetical,
+            AuthenticatorEntrySortMode::Issuer => Self::Issuer,
+        }
+    }
+}
+
 #[derive(uniffi::Object)]
 pub struct AuthenticatorEntrySorter;
 
@@ -64,4 +114,61 @@ impl AuthenticatorEntrySorter {
 
         Ok(res_mapped)
     }
+
+    /// Generates an order key that sorts between `before` and `after`. `None` means the start or the
+    /// end of the list
+    pub fn order_key_between(
+        &self,
+        before: Option<String>,
+        after: Option<String>,
+    ) -> Result<String, AuthenticatorError> {
+        Ok(key_between(before.as_deref(), after.as_deref())?)
+    }
+
+    /// Generates `count` sorted order keys, used to migrate a list sorted by numeric order
+    pub fn initial_order_keys(&self, count: u32) -> Vec<String> {
+        initial_keys(count as usize)
+    }
+
+    /// Sorts the entries for display without modifying their order
+    pub fn sort_for_display(
+        &self,
+        entries: Vec<AuthenticatorEntryWithOrder>,
+        usage: Vec<AuthenticatorEntryUsage>,
+        mode: AuthenticatorEntrySortMode,
+    ) -> Result<Vec<AuthenticatorEntryWithOrder>, AuthenticatorError> {
+        let mut mapped = vec![];
+        for entry in entries {
+            mapped.push(EntryWithOrder::try_from(entry)?);
+        }
+        let usage: Vec<EntryUsage> = usage.into_iter().map(EntryUsage::from).collect();
+
+        let res = sort_entries(&mapped, &usage, mode.into());
+        Ok(res.into_iter().map(AuthenticatorEntryWithOrder::from).collect())
+    }
+
+    /// Marks the entry as used now, creating its usage record if needed
+    pub fn record_use(
+        &self,
+        usage: Option<AuthenticatorEntryUsage>,
+        entry_id: String,
+        now: i64,
+    ) -> AuthenticatorEntryUsage {
+        let mut usage = usage.map(EntryUsage::from).unwrap_or_else(|| EntryUsage::new(entry_id));
+        usage.record_use(now);
+        usage.into()
+    }
+
+    pub fn merge_usage(
+        &self,
+        local: Vec<AuthenticatorEntryUsage>,
+        remote: Vec<AuthenticatorEntryUsage>,
+    ) -> Vec<AuthenticatorEntryUsage> {
+        let local: Vec<EntryUsage> = local.into_iter().map(EntryUsage::from).collect();
+        let remote: Vec<EntryUsage> = remote.into_iter().map(EntryUsage::from).collect();
+        proton_authenticator::merge_usage(&local, &remote)
+            .into_iter()
+            .map(AuthenticatorEntryUsage::from)
+            .collect()
+    }
 }
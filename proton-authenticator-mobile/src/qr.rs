@@ -11,4 +11,8 @@ impl QrCodeScanner {
     pub fn scan_qr_code(&self, image: Vec<u8>) -> Option<String> {
         proton_authenticator::qr::parse_qr_code(&image)
     }
+
+    pub fn scan_qr_codes(&self, image: Vec<u8>) -> Vec<String> {
+        proton_authenticator::qr::parse_qr_codes(&image).unwrap_or_default()
+    }
 }
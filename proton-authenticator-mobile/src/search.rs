@@ -0,0 +1,66 @@
+use crate::{AuthenticatorEntryModel, AuthenticatorError};
+use proton_authenticator::EntrySearchIndex;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(uniffi::Record)]
+pub struct AuthenticatorSearchResult {
+    pub entry_id: String,
+    pub score: u32,
+}
+
+impl From<proton_authenticator::SearchResult> for AuthenticatorSearchResult {
+    fn from(value: proton_authenticator::SearchResult) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            score: value.score,
+        }
+    }
+}
+
+#[derive(uniffi::Object)]
+pub struct AuthenticatorSearchIndex {
+    inner: Mutex<EntrySearchIndex>,
+}
+
+#[uniffi::export]
+impl AuthenticatorSearchIndex {
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(EntrySearchIndex::new()),
+        }
+    }
+
+    pub fn set_entries(&self, entries: Vec<AuthenticatorEntryModel>) -> Result<(), AuthenticatorError> {
+        let mut mapped = Vec::with_capacity(entries.len());
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        self.index().set_entries(&mapped);
+        Ok(())
+    }
+
+    pub fn upsert(&self, entry: AuthenticatorEntryModel) -> Result<(), AuthenticatorError> {
+        self.index().upsert(&entry.to_entry()?);
+        Ok(())
+    }
+
+    pub fn remove(&self, entry_id: String) -> bool {
+        self.index().remove(&entry_id)
+    }
+
+    pub fn search(&self, query: String) -> Vec<AuthenticatorSearchResult> {
+        self.index()
+            .search(&query)
+            .into_iter()
+            .map(AuthenticatorSearchResult::from)
+            .collect()
+    }
+}
+
+impl AuthenticatorSearchIndex {
+    fn index(&self) -> MutexGuard<'_, EntrySearchIndex> {
+        // A panic while holding the lock cannot leave the index in an inconsistent state
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
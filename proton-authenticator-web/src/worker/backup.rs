@@ -0,0 +1,159 @@
+use super::JsResult;
+use super::import::AuthenticatorImportResult;
+use crate::entry::WasmAuthenticatorEntryModel;
+use proton_authenticator::{BackupError, BackupProtection, BackupProtectionInfo, BackupRetentionPolicy};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+fn convert_backup_error(err: BackupError) -> JsError {
+    JsError::new(&format!("{err:?}"))
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(tag = "type")]
+pub enum WasmBackupProtection {
+    Password { password: String },
+    Key { key: Vec<u8> },
+}
+
+impl From<WasmBackupProtection> for BackupProtection {
+    fn from(value: WasmBackupProtection) -> Self {
+        match value {
+            WasmBackupProtection::Password { password } => Self::Password(password),
+            WasmBackupProtection::Key { key } => Self::Key(key),
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum WasmBackupProtectionType {
+    Password,
+    Key,
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmBackupManifest {
+    pub version: u8,
+    /// Unix timestamp in seconds
+    pub created_at: i64,
+    pub app_version: String,
+    pub entry_count: u32,
+    pub content_hash: String,
+    pub protection: WasmBackupProtectionType,
+}
+
+impl From<proton_authenticator::BackupManifest> for WasmBackupManifest {
+    fn from(value: proton_authenticator::BackupManifest) -> Self {
+        Self {
+            version: value.version,
+            created_at: value.created_at,
+            app_version: value.app_version,
+            entry_count: value.entry_count,
+            content_hash: value.content_hash,
+            protection: match value.protection {
+                BackupProtectionInfo::Password { .. } => WasmBackupProtectionType::Password,
+                BackupProtectionInfo::Key { .. } => WasmBackupProtectionType::Key,
+            },
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmBackupSnapshot {
+    pub file_name: String,
+    pub manifest: WasmBackupManifest,
+    pub contents: String,
+}
+
+impl From<proton_authenticator::BackupSnapshot> for WasmBackupSnapshot {
+    fn from(value: proton_authenticator::BackupSnapshot) -> Self {
+        Self {
+            file_name: value.file_name,
+            manifest: value.manifest.into(),
+            contents: value.contents,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmBackupFileInfo {
+    pub file_name: String,
+    pub created_at: i64,
+}
+
+impl From<proton_authenticator::BackupFileInfo> for WasmBackupFileInfo {
+    fn from(value: proton_authenticator::BackupFileInfo) -> Self {
+        Self {
+            file_name: value.file_name,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmBackupRetentionPolicy {
+    pub keep_last: u32,
+    pub keep_daily: u32,
+}
+
+impl From<WasmBackupRetentionPolicy> for BackupRetentionPolicy {
+    fn from(value: WasmBackupRetentionPolicy) -> Self {
+        Self {
+            keep_last: value.keep_last,
+            keep_daily: value.keep_daily,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn create_backup(
+    models: Vec<WasmAuthenticatorEntryModel>,
+    protection: WasmBackupProtection,
+) -> JsResult<WasmBackupSnapshot> {
+    let mut mapped = Vec::with_capacity(models.len());
+    for model in models {
+        mapped.push(model.to_entry()?);
+    }
+    let now = (js_sys::Date::now() / 1000.0) as i64;
+    let snapshot =
+        proton_authenticator::create_backup(mapped, &protection.into(), now).map_err(convert_backup_error)?;
+    Ok(snapshot.into())
+}
+
+#[wasm_bindgen]
+pub fn read_backup_manifest(contents: String) -> JsResult<WasmBackupManifest> {
+    let manifest = proton_authenticator::read_backup_manifest(&contents).map_err(convert_backup_error)?;
+    Ok(manifest.into())
+}
+
+#[wasm_bindgen]
+pub fn verify_backup(contents: String, protection: WasmBackupProtection) -> JsResult<WasmBackupManifest> {
+    let manifest = proton_authenticator::verify_backup(&contents, &protection.into()).map_err(convert_backup_error)?;
+    Ok(manifest.into())
+}
+
+#[wasm_bindgen]
+pub fn restore_backup(contents: String, protection: WasmBackupProtection) -> JsResult<AuthenticatorImportResult> {
+    let result = proton_authenticator::restore_backup(&contents, &protection.into()).map_err(convert_backup_error)?;
+    Ok(result.into())
+}
+
+#[wasm_bindgen]
+pub fn list_backups(file_names: Vec<String>) -> Vec<WasmBackupFileInfo> {
+    proton_authenticator::list_backups(&file_names)
+        .into_iter()
+        .map(WasmBackupFileInfo::from)
+        .collect()
+}
+
+#[wasm_bindgen]
+pub fn backups_to_prune(file_names: Vec<String>, policy: WasmBackupRetentionPolicy) -> Vec<String> {
+    proton_authenticator::backups_to_prune(&file_names, &policy.into())
+}
@@ -1,4 +1,5 @@
 use super::JsResult;
+use super::issuer::WasmIssuerIcon;
 use crate::common::vec_to_uint8_array;
 use crate::entry::*;
 use js_sys::Uint8Array;
@@ -6,8 +7,9 @@ use proton_authenticator::{
     Algorithm, AuthenticatorClient, AuthenticatorCodeResponse, AuthenticatorEntry,
     AuthenticatorEntrySteamCreateParameters as CommonSteamCreateParameters,
     AuthenticatorEntryTotpCreateParameters as CommonTotpCreateParameters, AuthenticatorEntryTotpParameters,
-    AuthenticatorEntryUpdateContents as CommonUpdateContents,
+    AuthenticatorEntryUpdateContents as CommonUpdateContents, KdfAlgorithm, KdfParams, KdfProfile,
 };
+use proton_pass_common::qr::QrLogo;
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
@@ -254,3 +256,176 @@ pub fn export_entries_with_password(models: Vec<WasmAuthenticatorEntryModel>, pa
     let client = AuthenticatorClient::new();
     Ok(client.export_entries_with_password(mapped, &password)?)
 }
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum WasmKdfProfile {
+    Standard,
+    Strong,
+    Paranoid,
+}
+
+impl From<WasmKdfProfile> for KdfProfile {
+    fn from(value: WasmKdfProfile) -> Self {
+        match value {
+            WasmKdfProfile::Standard => Self::Standard,
+            WasmKdfProfile::Strong => Self::Strong,
+            WasmKdfProfile::Paranoid => Self::Paranoid,
+        }
+    }
+}
+
+/// Argon2id parameters used to derive the key of a password protected export
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmKdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl From<KdfParams> for WasmKdfParams {
+    fn from(value: KdfParams) -> Self {
+        Self {
+            memory_kib: value.memory_kib,
+            iterations: value.iterations,
+            parallelism: value.parallelism,
+        }
+    }
+}
+
+impl From<WasmKdfParams> for KdfParams {
+    fn from(value: WasmKdfParams) -> Self {
+        Self {
+            algorithm: KdfAlgorithm::Argon2id,
+            memory_kib: value.memory_kib,
+            iterations: value.iterations,
+            parallelism: value.parallelism,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn export_entries_with_password_and_params(
+    models: Vec<WasmAuthenticatorEntryModel>,
+    password: String,
+    params: WasmKdfParams,
+) -> JsResult<String> {
+    let mut mapped = vec![];
+    for entry in models {
+        mapped.push(entry.to_entry()?);
+    }
+    let client = AuthenticatorClient::new();
+    Ok(client.export_entries_with_password_and_params(mapped, &password, params.into())?)
+}
+
+#[wasm_bindgen]
+pub fn kdf_params_for_profile(profile: WasmKdfProfile) -> WasmKdfParams {
+    KdfParams::for_profile(profile.into()).into()
+}
+
+/// Benchmarks the device to find the strongest KDF parameters that take at most `target_ms`
+#[wasm_bindgen]
+pub fn calibrate_kdf_params(target_ms: u64) -> JsResult<WasmKdfParams> {
+    let params = proton_authenticator::calibrate_kdf_params(target_ms, || js_sys::Date::now() as u64)?;
+    Ok(params.into())
+}
+
+#[wasm_bindgen]
+pub fn export_entries_to_aegis(models: Vec<WasmAuthenticatorEntryModel>, password: Option<String>) -> JsResult<String> {
+    let mut mapped = vec![];
+    for entry in models {
+        mapped.push(entry.to_entry()?);
+    }
+    let client = AuthenticatorClient::new();
+    Ok(client.export_entries_to_aegis(mapped, password.as_deref())?)
+}
+
+#[wasm_bindgen]
+pub fn export_entries_to_2fas(models: Vec<WasmAuthenticatorEntryModel>) -> JsResult<String> {
+    let mut mapped = vec![];
+    for entry in models {
+        mapped.push(entry.to_entry()?);
+    }
+    let client = AuthenticatorClient::new();
+    Ok(client.export_entries_to_2fas(mapped)?)
+}
+
+#[wasm_bindgen]
+pub fn export_entries_to_bitwarden(models: Vec<WasmAuthenticatorEntryModel>) -> JsResult<String> {
+    let mut mapped = vec![];
+    for entry in models {
+        mapped.push(entry.to_entry()?);
+    }
+    let client = AuthenticatorClient::new();
+    Ok(client.export_entries_to_bitwarden(mapped)?)
+}
+
+#[wasm_bindgen]
+pub fn export_entries_to_otpauth_list(models: Vec<WasmAuthenticatorEntryModel>) -> JsResult<String> {
+    let mut mapped = vec![];
+    for entry in models {
+        mapped.push(entry.to_entry()?);
+    }
+    let client = AuthenticatorClient::new();
+    Ok(client.export_entries_to_otpauth_list(mapped))
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmGoogleMigrationQrExport {
+    pub qr_codes: Vec<String>,
+    pub skipped_entry_ids: Vec<String>,
+}
+
+#[wasm_bindgen]
+pub fn export_entries_to_google_migration_qr(
+    models: Vec<WasmAuthenticatorEntryModel>,
+) -> JsResult<WasmGoogleMigrationQrExport> {
+    let mut mapped = vec![];
+    for entry in models {
+        mapped.push(entry.to_entry()?);
+    }
+    let client = AuthenticatorClient::new();
+    let export = client.export_entries_to_google_migration(mapped)?;
+
+    let mut qr_codes = vec![];
+    for payload in export.payloads {
+        let svg = proton_pass_common::qr::generate_svg_qr_code(&payload)
+            .map_err(|e| JsError::new(&format!("Error generating QR code: {e:?}")))?;
+        qr_codes.push(svg);
+    }
+
+    Ok(WasmGoogleMigrationQrExport {
+        qr_codes,
+        skipped_entry_ids: export.skipped,
+    })
+}
+
+/// Renders the entry as a QR code SVG that can be scanned by another device, with the issuer icon in the center if
+/// provided. The icon must be a PNG, JPEG or SVG image
+#[wasm_bindgen]
+pub fn export_entry_to_svg_qr(model: WasmAuthenticatorEntryModel, icon: Option<WasmIssuerIcon>) -> JsResult<String> {
+    let uri = model.to_entry()?.uri();
+    let logo = icon.as_ref().map(|icon| QrLogo {
+        data: &icon.data,
+        mime_type: &icon.mime_type,
+    });
+    proton_pass_common::qr::generate_svg_qr_code_with_logo(&uri, logo.as_ref())
+        .map_err(|e| JsError::new(&format!("Error generating QR code: {e:?}")))
+}
+
+/// Renders the entry as a PNG QR code of at least `size` pixels, with the issuer icon in the center if provided.
+/// Sizes above 2048 pixels are rejected
+#[wasm_bindgen]
+pub fn export_entry_to_png_qr(
+    model: WasmAuthenticatorEntryModel,
+    size: u32,
+    icon: Option<WasmIssuerIcon>,
+) -> JsResult<Uint8Array> {
+    let uri = model.to_entry()?.uri();
+    let logo = icon.as_ref().map(|icon| icon.data.as_slice());
+    let png = proton_pass_common::qr::generate_png_qr_code(&uri, size, logo)
+        .map_err(|e| JsError::new(&format!("Error generating QR code: {e:?}")))?;
+    Ok(vec_to_uint8_array(png))
+}
@@ -1,4 +1,5 @@
 use super::JsResult;
+use super::ordering::WasmEntryUsage;
 use crate::common::*;
 use crate::entry::*;
 use js_sys::Uint8Array;
@@ -50,3 +51,33 @@ pub fn decrypt_entries(
 
     Ok(mapped_entries)
 }
+
+#[wasm_bindgen]
+pub fn reencrypt_entries(
+    encrypted_entries: Vec<Uint8Array>,
+    old_key: Uint8Array,
+    new_key: Uint8Array,
+) -> JsResult<Vec<Uint8Array>> {
+    let entries: Vec<Vec<u8>> = encrypted_entries.iter().map(|entry| entry.to_vec()).collect();
+    let reencrypted = proton_authenticator::reencrypt_entries(entries, old_key.to_vec(), new_key.to_vec())?;
+    Ok(reencrypted.into_iter().map(vec_to_uint8_array).collect())
+}
+
+#[wasm_bindgen]
+pub fn find_entry_key(encrypted_entry: Uint8Array, keys: Vec<Uint8Array>) -> Option<u32> {
+    let keys: Vec<Vec<u8>> = keys.iter().map(|key| key.to_vec()).collect();
+    proton_authenticator::find_entry_key(&encrypted_entry.to_vec(), &keys).map(|idx| idx as u32)
+}
+
+#[wasm_bindgen]
+pub fn encrypt_usage(usage: Vec<WasmEntryUsage>, key: Uint8Array) -> JsResult<Uint8Array> {
+    let usage: Vec<proton_authenticator::EntryUsage> = usage.into_iter().map(Into::into).collect();
+    let encrypted = proton_authenticator::encrypt_usage(&usage, key.to_vec())?;
+    Ok(vec_to_uint8_array(encrypted))
+}
+
+#[wasm_bindgen]
+pub fn decrypt_usage(encrypted_usage: Uint8Array, key: Uint8Array) -> JsResult<Vec<WasmEntryUsage>> {
+    let usage = proton_authenticator::decrypt_usage(&encrypted_usage.to_vec(), key.to_vec())?;
+    Ok(usage.into_iter().map(WasmEntryUsage::from).collect())
+}
@@ -4,7 +4,7 @@ use proton_authenticator::generator::{
     GeneratorCurrentTimeProvider, TotpGenerationHandle, TotpGenerator as CoreTotpGenerator, TotpGeneratorCallback,
     TotpGeneratorDependencies,
 };
-use proton_authenticator::{AuthenticatorCodeResponse, LogLevel, emit_log_message};
+use proton_authenticator::{AuthenticatorCodeResponse, AuthenticatorEntry, LogLevel, emit_log_message};
 use std::sync::Arc;
 use wasm_bindgen::prelude::*;
 
@@ -15,14 +15,15 @@ pub struct WebTotpGenerator {
 
 pub struct WebCurrentTimeProvider {
     inner: js_sys::Function,
+    inner_ms: Option<js_sys::Function>,
 }
 
 unsafe impl Send for WebCurrentTimeProvider {}
 unsafe impl Sync for WebCurrentTimeProvider {}
 
-impl GeneratorCurrentTimeProvider for WebCurrentTimeProvider {
-    fn now(&self) -> u64 {
-        match self.inner.call0(&JsValue::NULL) {
+impl WebCurrentTimeProvider {
+    fn call(function: &js_sys::Function) -> u64 {
+        match function.call0(&JsValue::NULL) {
             Ok(v) => {
                 let value_as_debug = format!("{v:?}");
                 u64::try_from(v).unwrap_or_else(|e| {
@@ -44,19 +45,41 @@ impl GeneratorCurrentTimeProvider for WebCurrentTimeProvider {
     }
 }
 
+impl GeneratorCurrentTimeProvider for WebCurrentTimeProvider {
+    fn now(&self) -> u64 {
+        Self::call(&self.inner)
+    }
+
+    fn now_ms(&self) -> u64 {
+        match &self.inner_ms {
+            Some(inner_ms) => Self::call(inner_ms),
+            None => self.now().saturating_mul(1000),
+        }
+    }
+}
+
 /// Callback-based TOTP generator that allows the caller to subscribe to TOTP code changes, and
 /// to get notified of changes in a configurable manner
 #[wasm_bindgen]
 impl WebTotpGenerator {
     /// Create a new instance of the TOTP generator
-    /// - period_ms: how often the generator should check if the codes have changed. Time in ms
-    /// - only_on_code_change: if true, only invoke the callback if the codes have changed. If false, it will always be called
-    /// - current_time_provider: callback that will be invoked to get the current time
+    /// - period_ms: how often the callback is invoked when only_on_code_change is false. Time in ms
+    /// - only_on_code_change: if true, the generator sleeps until the next code change and only invokes the callback
+    ///   with the entries whose code changed. If false, it will be called with all the codes every period_ms
+    /// - current_time_provider: callback that will be invoked to get the current time in seconds
+    /// - current_time_ms_provider: optional callback that will be invoked to get the current time in milliseconds.
+    ///   If missing, the time in seconds is used and the wake-ups on code change can be up to a second late
     #[wasm_bindgen(constructor)]
-    pub fn new(period_ms: u32, only_on_code_change: bool, current_time_provider: js_sys::Function) -> Self {
+    pub fn new(
+        period_ms: u32,
+        only_on_code_change: bool,
+        current_time_provider: js_sys::Function,
+        current_time_ms_provider: Option<js_sys::Function>,
+    ) -> Self {
         let dependencies = TotpGeneratorDependencies {
             current_time_provider: Arc::new(WebCurrentTimeProvider {
                 inner: current_time_provider,
+                inner_ms: current_time_ms_provider,
             }),
         };
         Self {
@@ -73,10 +96,7 @@ impl WebTotpGenerator {
         entries: Vec<WasmAuthenticatorEntryModel>,
         callback: js_sys::Function,
     ) -> Result<WebTotpGenerationHandle, JsError> {
-        let mut as_entries = vec![];
-        for entry in entries {
-            as_entries.push(entry.to_entry()?);
-        }
+        let as_entries = to_entries(entries)?;
         let cb = WasmCallback { callback };
         let handle = self.inner.start_async(as_entries, cb).await;
         Ok(WebTotpGenerationHandle { inner: handle })
@@ -116,4 +136,30 @@ impl WebTotpGenerationHandle {
     pub fn cancel(&mut self) {
         self.inner.cancel();
     }
+
+    /// Adds entries to the running generator. Entries with the id of an existing one replace it
+    #[wasm_bindgen]
+    pub fn add_entries(&self, entries: Vec<WasmAuthenticatorEntryModel>) -> Result<(), JsError> {
+        self.inner.add_entries(to_entries(entries)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn remove_entries(&self, entry_ids: Vec<String>) {
+        self.inner.remove_entries(&entry_ids);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_entries(&self, entries: Vec<WasmAuthenticatorEntryModel>) -> Result<(), JsError> {
+        self.inner.set_entries(to_entries(entries)?);
+        Ok(())
+    }
+}
+
+fn to_entries(entries: Vec<WasmAuthenticatorEntryModel>) -> Result<Vec<AuthenticatorEntry>, JsError> {
+    let mut as_entries = vec![];
+    for entry in entries {
+        as_entries.push(entry.to_entry()?);
+    }
+    Ok(as_entries)
 }
@@ -1,6 +1,6 @@
 use crate::entry::WasmAuthenticatorEntryModel;
 use js_sys::Uint8Array;
-use proton_authenticator::ThirdPartyImportError;
+use proton_authenticator::{DetectedFormat, ImportFormat, ThirdPartyImportError};
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
@@ -47,6 +47,48 @@ impl From<proton_authenticator::ImportResult> for AuthenticatorImportResult {
     }
 }
 
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct AuthenticatorDetectedImportResult {
+    pub format: ImportFormat,
+    pub result: AuthenticatorImportResult,
+}
+
+/// A file of the maFiles folder of Steam Desktop Authenticator
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmSteamMaFile {
+    pub file_name: String,
+    pub contents: String,
+}
+
+impl From<WasmSteamMaFile> for proton_authenticator::SteamMaFile {
+    fn from(file: WasmSteamMaFile) -> Self {
+        Self {
+            file_name: file.file_name,
+            contents: file.contents,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn detect_import_format(contents: Uint8Array) -> Option<DetectedFormat> {
+    proton_authenticator::detect_import_format(&contents.to_vec())
+}
+
+#[wasm_bindgen]
+pub fn import_with_detected_format(
+    contents: Uint8Array,
+    password: Option<String>,
+) -> Result<AuthenticatorDetectedImportResult, JsError> {
+    let res = proton_authenticator::detect_and_parse(&contents.to_vec(), password)
+        .map_err(|e| JsError::new(&format!("{e:?}")))?;
+    Ok(AuthenticatorDetectedImportResult {
+        format: res.format,
+        result: AuthenticatorImportResult::from(res.result),
+    })
+}
+
 #[wasm_bindgen]
 pub fn import_from_aegis_json(contents: String, password: Option<String>) -> ImportResult {
     let res = proton_authenticator::parse_aegis_json(&contents, password)
@@ -61,6 +103,21 @@ pub fn import_from_aegis_txt(contents: String) -> ImportResult {
     Ok(AuthenticatorImportResult::from(res))
 }
 
+#[wasm_bindgen]
+pub fn import_from_andotp(contents: Uint8Array, password: Option<String>) -> ImportResult {
+    let contents_as_array = contents.to_vec();
+    let res = proton_authenticator::parse_andotp_file(&contents_as_array, password)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_authy_json(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_authy_json(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
 #[wasm_bindgen]
 pub fn import_from_bitwarden_json(contents: String) -> ImportResult {
     let res = proton_authenticator::parse_bitwarden_json(&contents)
@@ -89,6 +146,28 @@ pub fn import_from_ente_encrypted(contents: String, password: String) -> ImportR
     Ok(AuthenticatorImportResult::from(res))
 }
 
+#[wasm_bindgen]
+pub fn import_from_freeotp_plus_json(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_freeotp_plus_json(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_freeotp_xml(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_freeotp_xml(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_freeotp_backup(contents: Uint8Array, password: Option<String>) -> ImportResult {
+    let contents_as_array = contents.to_vec();
+    let res = proton_authenticator::parse_freeotp_backup(&contents_as_array, password)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
 #[wasm_bindgen]
 pub fn import_from_google_qr(contents: String) -> ImportResult {
     let res = proton_authenticator::parse_google_authenticator_totp(&contents)
@@ -96,6 +175,21 @@ pub fn import_from_google_qr(contents: String) -> ImportResult {
     Ok(AuthenticatorImportResult::from(res))
 }
 
+#[wasm_bindgen]
+pub fn import_from_keepass_csv(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_keepass_csv(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_keepass_kdbx(contents: Uint8Array, password: String) -> ImportResult {
+    let contents_as_array = contents.to_vec();
+    let res = proton_authenticator::parse_keepass_kdbx(&contents_as_array, &password)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
 #[wasm_bindgen]
 pub fn import_from_lastpass_json(contents: String) -> ImportResult {
     let res = proton_authenticator::parse_lastpass_json(&contents)
@@ -103,6 +197,28 @@ pub fn import_from_lastpass_json(contents: String) -> ImportResult {
     Ok(AuthenticatorImportResult::from(res))
 }
 
+#[wasm_bindgen]
+pub fn import_from_microsoft_authenticator_csv(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_microsoft_authenticator_csv(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_microsoft_authenticator_json(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_microsoft_authenticator_json(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_otpauth_db(contents: Uint8Array, password: Option<String>) -> ImportResult {
+    let contents_as_array = contents.to_vec();
+    let res = proton_authenticator::parse_otpauth_db(&contents_as_array, password)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
 #[wasm_bindgen]
 pub fn import_from_proton_authenticator(contents: String) -> ImportResult {
     let res = proton_authenticator::parse_proton_authenticator_export(&contents)
@@ -117,6 +233,54 @@ pub fn import_from_proton_authenticator_with_password(contents: String, password
     Ok(AuthenticatorImportResult::from(res))
 }
 
+#[wasm_bindgen]
+pub fn import_from_raivo_json(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_raivo_json(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_raivo_zip(contents: Uint8Array, password: Option<String>) -> ImportResult {
+    let contents_as_array = contents.to_vec();
+    let res = proton_authenticator::parse_raivo_zip(&contents_as_array, password)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+#[wasm_bindgen]
+pub fn import_from_steam_mafile(contents: String) -> ImportResult {
+    let res = proton_authenticator::parse_steam_mafile(&contents)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+/// iv and salt are the encryption_iv and encryption_salt of the file in the manifest.json of the maFiles folder
+#[wasm_bindgen]
+pub fn import_from_steam_mafile_encrypted(
+    contents: String,
+    password: String,
+    iv: String,
+    salt: String,
+) -> ImportResult {
+    let res = proton_authenticator::parse_steam_mafile_encrypted(&contents, &password, &iv, &salt)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
+/// Imports the maFiles folder of Steam Desktop Authenticator from its manifest.json and the maFiles it contains
+#[wasm_bindgen]
+pub fn import_from_steam_mafiles(
+    manifest: String,
+    files: Vec<WasmSteamMaFile>,
+    password: Option<String>,
+) -> ImportResult {
+    let files: Vec<proton_authenticator::SteamMaFile> = files.into_iter().map(Into::into).collect();
+    let res = proton_authenticator::parse_steam_mafiles(&manifest, &files, password)
+        .map_err(|e| convert_import_error(ThirdPartyImportError::from(e)))?;
+    Ok(AuthenticatorImportResult::from(res))
+}
+
 #[wasm_bindgen]
 pub fn import_from_2fas(contents: String, password: Option<String>) -> ImportResult {
     let res = proton_authenticator::parse_2fas_file(&contents, password)
@@ -153,3 +317,11 @@ pub fn import_from_google_authenticator_qr(
         }
     }
 }
+
+#[cfg(feature = "qr")]
+#[wasm_bindgen]
+pub fn import_from_qr_images(images: Vec<Uint8Array>) -> ImportResult {
+    let images: Vec<Vec<u8>> = images.iter().map(|image| image.to_vec()).collect();
+    let res = proton_authenticator::qr::import_from_qr_images(&images);
+    Ok(AuthenticatorImportResult::from(res))
+}
@@ -0,0 +1,256 @@
+use crate::entry::WasmAuthenticatorEntryModel;
+use crate::worker::JsResult;
+use crate::worker::import::{AuthenticatorImportError, AuthenticatorImportResult};
+use proton_authenticator::import_planner::{
+    ImportConflict as CommonImportConflict, ImportConflictField as CommonImportConflictField,
+    ImportConflictResolution as CommonImportConflictResolution, ImportDecisions as CommonImportDecisions,
+    ImportDuplicate as CommonImportDuplicate, ImportMerge as CommonImportMerge, ImportPreview as CommonImportPreview,
+    apply_import, plan_import,
+};
+use proton_authenticator::{AuthenticatorEntry, ImportError, ImportResult};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::JsError;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum WasmImportConflictField {
+    Type,
+    Name,
+    Issuer,
+    Note,
+    Algorithm,
+    Digits,
+    Period,
+}
+
+impl From<CommonImportConflictField> for WasmImportConflictField {
+    fn from(value: CommonImportConflictField) -> Self {
+        match value {
+            CommonImportConflictField::Type => WasmImportConflictField::Type,
+            CommonImportConflictField::Name => WasmImportConflictField::Name,
+            CommonImportConflictField::Issuer => WasmImportConflictField::Issuer,
+            CommonImportConflictField::Note => WasmImportConflictField::Note,
+            CommonImportConflictField::Algorithm => WasmImportConflictField::Algorithm,
+            CommonImportConflictField::Digits => WasmImportConflictField::Digits,
+            CommonImportConflictField::Period => WasmImportConflictField::Period,
+        }
+    }
+}
+
+impl From<WasmImportConflictField> for CommonImportConflictField {
+    fn from(value: WasmImportConflictField) -> Self {
+        match value {
+            WasmImportConflictField::Type => CommonImportConflictField::Type,
+            WasmImportConflictField::Name => CommonImportConflictField::Name,
+            WasmImportConflictField::Issuer => CommonImportConflictField::Issuer,
+            WasmImportConflictField::Note => CommonImportConflictField::Note,
+            WasmImportConflictField::Algorithm => CommonImportConflictField::Algorithm,
+            WasmImportConflictField::Digits => CommonImportConflictField::Digits,
+            WasmImportConflictField::Period => CommonImportConflictField::Period,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum WasmImportConflictResolution {
+    KeepExisting,
+    KeepImported,
+    KeepBoth,
+}
+
+impl From<WasmImportConflictResolution> for CommonImportConflictResolution {
+    fn from(value: WasmImportConflictResolution) -> Self {
+        match value {
+            WasmImportConflictResolution::KeepExisting => CommonImportConflictResolution::KeepExisting,
+            WasmImportConflictResolution::KeepImported => CommonImportConflictResolution::KeepImported,
+            WasmImportConflictResolution::KeepBoth => CommonImportConflictResolution::KeepBoth,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmImportDuplicate {
+    pub existing_id: String,
+    pub imported: WasmAuthenticatorEntryModel,
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmImportConflict {
+    pub existing: WasmAuthenticatorEntryModel,
+    pub imported: WasmAuthenticatorEntryModel,
+    pub fields: Vec<WasmImportConflictField>,
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmImportMerge {
+    pub existing: WasmAuthenticatorEntryModel,
+    pub imported: WasmAuthenticatorEntryModel,
+    pub merged: WasmAuthenticatorEntryModel,
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmImportPreview {
+    pub new_entries: Vec<WasmAuthenticatorEntryModel>,
+    pub duplicates: Vec<WasmImportDuplicate>,
+    pub conflicts: Vec<WasmImportConflict>,
+    pub merges: Vec<WasmImportMerge>,
+    pub errors: Vec<AuthenticatorImportError>,
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmImportConflictDecision {
+    pub imported_id: String,
+    pub resolution: WasmImportConflictResolution,
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmImportDecisions {
+    pub apply_merges: bool,
+    pub conflict_resolutions: Vec<WasmImportConflictDecision>,
+}
+
+impl From<CommonImportPreview> for WasmImportPreview {
+    fn from(value: CommonImportPreview) -> Self {
+        Self {
+            new_entries: value
+                .new_entries
+                .into_iter()
+                .map(WasmAuthenticatorEntryModel::from)
+                .collect(),
+            duplicates: value
+                .duplicates
+                .into_iter()
+                .map(|d| WasmImportDuplicate {
+                    existing_id: d.existing_id,
+                    imported: WasmAuthenticatorEntryModel::from(d.imported),
+                })
+                .collect(),
+            conflicts: value
+                .conflicts
+                .into_iter()
+                .map(|c| WasmImportConflict {
+                    existing: WasmAuthenticatorEntryModel::from(c.existing),
+                    imported: WasmAuthenticatorEntryModel::from(c.imported),
+                    fields: c.fields.into_iter().map(WasmImportConflictField::from).collect(),
+                })
+                .collect(),
+            merges: value
+                .merges
+                .into_iter()
+                .map(|m| WasmImportMerge {
+                    existing: WasmAuthenticatorEntryModel::from(m.existing),
+                    imported: WasmAuthenticatorEntryModel::from(m.imported),
+                    merged: WasmAuthenticatorEntryModel::from(m.merged),
+                })
+                .collect(),
+            errors: value.errors.into_iter().map(AuthenticatorImportError::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<WasmImportPreview> for CommonImportPreview {
+    type Error = JsError;
+
+    fn try_from(value: WasmImportPreview) -> Result<Self, Self::Error> {
+        let mut preview = CommonImportPreview {
+            new_entries: map_entries(value.new_entries)?,
+            ..Default::default()
+        };
+        for duplicate in value.duplicates {
+            preview.duplicates.push(CommonImportDuplicate {
+                existing_id: duplicate.existing_id,
+                imported: duplicate.imported.to_entry()?,
+            });
+        }
+        for conflict in value.conflicts {
+            preview.conflicts.push(CommonImportConflict {
+                existing: conflict.existing.to_entry()?,
+                imported: conflict.imported.to_entry()?,
+                fields: conflict
+                    .fields
+                    .into_iter()
+                    .map(CommonImportConflictField::from)
+                    .collect(),
+            });
+        }
+        for merge in value.merges {
+            preview.merges.push(CommonImportMerge {
+                existing: merge.existing.to_entry()?,
+                imported: merge.imported.to_entry()?,
+                merged: merge.merged.to_entry()?,
+            });
+        }
+        preview.errors = value
+            .errors
+            .into_iter()
+            .map(|e| ImportError {
+                context: e.context,
+                message: e.message,
+            })
+            .collect();
+        Ok(preview)
+    }
+}
+
+impl From<WasmImportDecisions> for CommonImportDecisions {
+    fn from(value: WasmImportDecisions) -> Self {
+        Self {
+            apply_merges: value.apply_merges,
+            conflict_resolutions: value
+                .conflict_resolutions
+                .into_iter()
+                .map(|d| (d.imported_id, CommonImportConflictResolution::from(d.resolution)))
+                .collect(),
+        }
+    }
+}
+
+fn map_entries(entries: Vec<WasmAuthenticatorEntryModel>) -> JsResult<Vec<AuthenticatorEntry>> {
+    let mut mapped = Vec::with_capacity(entries.len());
+    for entry in entries {
+        mapped.push(entry.to_entry()?);
+    }
+    Ok(mapped)
+}
+
+#[wasm_bindgen]
+pub fn plan_entries_import(
+    existing: Vec<WasmAuthenticatorEntryModel>,
+    import: AuthenticatorImportResult,
+) -> JsResult<WasmImportPreview> {
+    let existing = map_entries(existing)?;
+    let import = ImportResult {
+        entries: map_entries(import.entries)?,
+        errors: import
+            .errors
+            .into_iter()
+            .map(|e| ImportError {
+                context: e.context,
+                message: e.message,
+            })
+            .collect(),
+    };
+
+    Ok(WasmImportPreview::from(plan_import(&existing, import)))
+}
+
+#[wasm_bindgen]
+pub fn apply_entries_import(
+    existing: Vec<WasmAuthenticatorEntryModel>,
+    preview: WasmImportPreview,
+    decisions: WasmImportDecisions,
+) -> JsResult<Vec<WasmAuthenticatorEntryModel>> {
+    let existing = map_entries(existing)?;
+    let preview = CommonImportPreview::try_from(preview)?;
+    let res = apply_import(existing, preview, &CommonImportDecisions::from(decisions));
+    Ok(res.into_iter().map(WasmAuthenticatorEntryModel::from).collect())
+}
@@ -1,4 +1,5 @@
-use proton_authenticator::{IssuerInfo, TOTPIssuerMapper};
+use super::JsResult;
+use proton_authenticator::{IssuerIcon, IssuerInfo, IssuerMatch, IssuerMatchKind, TOTPIssuerMapper};
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
@@ -19,6 +20,61 @@ impl From<IssuerInfo> for WasmIssuerInfo {
     }
 }
 
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum WasmIssuerMatchKind {
+    Exact,
+    Domain,
+    Normalized,
+    Fuzzy,
+}
+
+impl From<IssuerMatchKind> for WasmIssuerMatchKind {
+    fn from(value: IssuerMatchKind) -> Self {
+        match value {
+            IssuerMatchKind::Exact => Self::Exact,
+            IssuerMatchKind::Domain => Self::Domain,
+            IssuerMatchKind::Normalized => Self::Normalized,
+            IssuerMatchKind::Fuzzy => Self::Fuzzy,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmIssuerMatch {
+    pub info: WasmIssuerInfo,
+    pub kind: WasmIssuerMatchKind,
+    /// From 0 to 1
+    pub confidence: f32,
+}
+
+impl From<IssuerMatch> for WasmIssuerMatch {
+    fn from(value: IssuerMatch) -> Self {
+        Self {
+            info: value.info.into(),
+            kind: value.kind.into(),
+            confidence: value.confidence,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmIssuerIcon {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl From<IssuerIcon> for WasmIssuerIcon {
+    fn from(value: IssuerIcon) -> Self {
+        Self {
+            mime_type: value.mime_type,
+            data: value.data,
+        }
+    }
+}
+
 #[wasm_bindgen]
 pub struct WasmIssuerMapper {
     inner: TOTPIssuerMapper,
@@ -39,8 +95,35 @@ impl WasmIssuerMapper {
         }
     }
 
+    /// Creates a mapper that serves the issuer icons from the bundle generated by the icon fetcher
+    #[wasm_bindgen]
+    pub fn with_icon_bundle(bundle: Vec<u8>) -> JsResult<WasmIssuerMapper> {
+        let inner = TOTPIssuerMapper::with_icon_bundle(&bundle).map_err(|e| JsError::new(&format!("{e:?}")))?;
+        Ok(Self { inner })
+    }
+
     #[wasm_bindgen]
     pub fn get_issuer_info(&self, issuer: String) -> Option<WasmIssuerInfo> {
         self.inner.lookup(&issuer).map(WasmIssuerInfo::from)
     }
+
+    #[wasm_bindgen]
+    pub fn find_issuer(&self, issuer: String) -> Option<WasmIssuerMatch> {
+        self.inner.find(&issuer).map(WasmIssuerMatch::from)
+    }
+
+    #[wasm_bindgen]
+    pub fn find_issuer_by_url(&self, url: String) -> Option<WasmIssuerMatch> {
+        self.inner.find_by_url(&url).map(WasmIssuerMatch::from)
+    }
+
+    #[wasm_bindgen]
+    pub fn get_issuer_icon(&self, issuer: String) -> Option<WasmIssuerIcon> {
+        self.inner.icon(&issuer).map(WasmIssuerIcon::from)
+    }
+
+    #[wasm_bindgen]
+    pub fn get_issuer_icon_by_url(&self, url: String) -> Option<WasmIssuerIcon> {
+        self.inner.icon_for_url(&url).map(WasmIssuerIcon::from)
+    }
 }
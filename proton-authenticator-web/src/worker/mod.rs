@@ -1,11 +1,14 @@
 use wasm_bindgen::JsError;
 
+mod backup;
 mod client;
 mod crypto;
 mod generator;
 mod import;
+mod import_planner;
 mod issuer;
 mod operations;
 mod ordering;
+mod search;
 
 pub type JsResult<T> = Result<T, JsError>;
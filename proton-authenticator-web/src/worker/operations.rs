@@ -2,7 +2,8 @@ use crate::entry::WasmAuthenticatorEntryModel;
 use crate::worker::JsResult;
 use proton_authenticator::operations::{
     AuthenticatorEntryState as CommonEntryState, AuthenticatorOperation, EntryOperation as CommonEntryOperation,
-    LocalEntry as CommonLocalEntry, RemoteEntry as CommonRemoteEntry,
+    LocalEntry as CommonLocalEntry, LocalSyncEntry as CommonLocalSyncEntry, OrderUpdate as CommonOrderUpdate,
+    RemoteEntry as CommonRemoteEntry, RemoteSyncEntry as CommonRemoteSyncEntry, SyncPlan as CommonSyncPlan,
 };
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
@@ -32,8 +33,10 @@ impl From<WasmLocalEntryState> for CommonEntryState {
 pub struct WasmLocalEntry {
     pub entry: WasmAuthenticatorEntryModel,
     pub state: WasmLocalEntryState,
+    pub remote_id: Option<String>,
     pub modify_time: i64,
     pub local_modify_time: Option<i64>,
+    pub base: Option<WasmAuthenticatorEntryModel>,
 }
 
 impl TryFrom<WasmLocalEntry> for CommonLocalEntry {
@@ -43,8 +46,13 @@ impl TryFrom<WasmLocalEntry> for CommonLocalEntry {
         Ok(Self {
             entry: value.entry.to_entry()?,
             state: CommonEntryState::from(value.state),
+            remote_id: value.remote_id,
             modify_time: value.modify_time,
             local_modify_time: value.local_modify_time,
+            base: match value.base {
+                Some(base) => Some(base.to_entry()?),
+                None => None,
+            },
         })
     }
 }
@@ -56,6 +64,7 @@ pub struct WasmRemoteEntry {
     pub revision: u32,
     pub entry: WasmAuthenticatorEntryModel,
     pub modify_time: i64,
+    pub delete_time: Option<i64>,
 }
 
 impl TryFrom<WasmRemoteEntry> for CommonRemoteEntry {
@@ -67,6 +76,7 @@ impl TryFrom<WasmRemoteEntry> for CommonRemoteEntry {
             revision: value.revision,
             entry: value.entry.to_entry()?,
             modify_time: value.modify_time,
+            delete_time: value.delete_time,
         })
     }
 }
@@ -78,6 +88,10 @@ pub enum WasmOperationType {
     DeleteLocal,
     DeleteLocalAndRemote,
     Push,
+    Merge,
+    Conflict,
+    RemoteDeletedLocalEdited,
+    LocalDeletedRemoteEdited,
 }
 
 impl From<AuthenticatorOperation> for WasmOperationType {
@@ -87,6 +101,10 @@ impl From<AuthenticatorOperation> for WasmOperationType {
             AuthenticatorOperation::DeleteLocal => WasmOperationType::DeleteLocal,
             AuthenticatorOperation::DeleteLocalAndRemote => WasmOperationType::DeleteLocalAndRemote,
             AuthenticatorOperation::Push => WasmOperationType::Push,
+            AuthenticatorOperation::Merge => WasmOperationType::Merge,
+            AuthenticatorOperation::Conflict => WasmOperationType::Conflict,
+            AuthenticatorOperation::RemoteDeletedLocalEdited => WasmOperationType::RemoteDeletedLocalEdited,
+            AuthenticatorOperation::LocalDeletedRemoteEdited => WasmOperationType::LocalDeletedRemoteEdited,
         }
     }
 }
@@ -111,26 +129,137 @@ impl From<CommonEntryOperation> for WasmEntryOperation {
     }
 }
 
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmLocalSyncEntry {
+    pub local: WasmLocalEntry,
+    pub order_key: String,
+    pub order_modify_time: i64,
+}
+
+impl TryFrom<WasmLocalSyncEntry> for CommonLocalSyncEntry {
+    type Error = JsError;
+
+    fn try_from(value: WasmLocalSyncEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            local: CommonLocalEntry::try_from(value.local)?,
+            order_key: value.order_key,
+            order_modify_time: value.order_modify_time,
+        })
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmRemoteSyncEntry {
+    pub remote: WasmRemoteEntry,
+    pub order_key: String,
+    pub order_modify_time: i64,
+}
+
+impl TryFrom<WasmRemoteSyncEntry> for CommonRemoteSyncEntry {
+    type Error = JsError;
+
+    fn try_from(value: WasmRemoteSyncEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            remote: CommonRemoteEntry::try_from(value.remote)?,
+            order_key: value.order_key,
+            order_modify_time: value.order_modify_time,
+        })
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmOrderUpdate {
+    pub entry_id: String,
+    pub order_key: String,
+    pub update_local: bool,
+    pub update_remote: bool,
+}
+
+impl From<CommonOrderUpdate> for WasmOrderUpdate {
+    fn from(value: CommonOrderUpdate) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            order_key: value.order_key,
+            update_local: value.update_local,
+            update_remote: value.update_remote,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmSyncPlan {
+    pub operations: Vec<WasmEntryOperation>,
+    pub order_updates: Vec<WasmOrderUpdate>,
+    pub ordered_ids: Vec<String>,
+}
+
+impl From<CommonSyncPlan> for WasmSyncPlan {
+    fn from(value: CommonSyncPlan) -> Self {
+        Self {
+            operations: value.operations.into_iter().map(WasmEntryOperation::from).collect(),
+            order_updates: value.order_updates.into_iter().map(WasmOrderUpdate::from).collect(),
+            ordered_ids: value.ordered_ids,
+        }
+    }
+}
+
 #[wasm_bindgen]
 pub fn calculate_operations(
     remote: Vec<WasmRemoteEntry>,
     local: Vec<WasmLocalEntry>,
 ) -> JsResult<Vec<WasmEntryOperation>> {
+    let (remote_mapped, local_mapped) = map_entries(remote, local)?;
+    let ops = proton_authenticator::operations::calculate_operations_to_perform(remote_mapped, local_mapped);
+    Ok(ops.into_iter().map(WasmEntryOperation::from).collect())
+}
+
+#[wasm_bindgen]
+pub fn calculate_operations_for_partial_listing(
+    remote: Vec<WasmRemoteEntry>,
+    local: Vec<WasmLocalEntry>,
+) -> JsResult<Vec<WasmEntryOperation>> {
+    let (remote_mapped, local_mapped) = map_entries(remote, local)?;
+    let ops = proton_authenticator::operations::calculate_operations_for_partial_listing(remote_mapped, local_mapped);
+    Ok(ops.into_iter().map(WasmEntryOperation::from).collect())
+}
+
+#[wasm_bindgen]
+pub fn plan_sync(
+    remote: Vec<WasmRemoteSyncEntry>,
+    local: Vec<WasmLocalSyncEntry>,
+    complete_listing: bool,
+) -> JsResult<WasmSyncPlan> {
     let mut remote_mapped = Vec::with_capacity(remote.len());
     for remote_entry in remote {
-        remote_mapped.push(CommonRemoteEntry::try_from(remote_entry)?);
+        remote_mapped.push(CommonRemoteSyncEntry::try_from(remote_entry)?);
     }
 
     let mut local_mapped = Vec::with_capacity(local.len());
     for local_entry in local {
-        local_mapped.push(CommonLocalEntry::try_from(local_entry)?);
+        local_mapped.push(CommonLocalSyncEntry::try_from(local_entry)?);
     }
 
-    let ops = proton_authenticator::operations::calculate_operations_to_perform(remote_mapped, local_mapped);
-    let mut result: Vec<WasmEntryOperation> = Vec::new();
-    for op in ops {
-        result.push(WasmEntryOperation::from(op));
+    let plan = proton_authenticator::operations::plan_sync(remote_mapped, local_mapped, complete_listing);
+    Ok(WasmSyncPlan::from(plan))
+}
+
+fn map_entries(
+    remote: Vec<WasmRemoteEntry>,
+    local: Vec<WasmLocalEntry>,
+) -> JsResult<(Vec<CommonRemoteEntry>, Vec<CommonLocalEntry>)> {
+    let mut remote_mapped = Vec::with_capacity(remote.len());
+    for remote_entry in remote {
+        remote_mapped.push(CommonRemoteEntry::try_from(remote_entry)?);
+    }
+
+    let mut local_mapped = Vec::with_capacity(local.len());
+    for local_entry in local {
+        local_mapped.push(CommonLocalEntry::try_from(local_entry)?);
     }
 
-    Ok(result)
+    Ok((remote_mapped, local_mapped))
 }
@@ -1,5 +1,8 @@
 use crate::entry::WasmAuthenticatorEntryModel;
-use proton_authenticator::ordering::{EntryWithOrder, reorder_items};
+use proton_authenticator::EntryUsage;
+use proton_authenticator::ordering::{
+    EntrySortMode, EntryWithOrder, initial_keys, key_between, reorder_items, sort_entries,
+};
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::JsError;
@@ -59,3 +62,96 @@ pub fn sort_entries(
 
     Ok(res_mapped)
 }
+
+#[wasm_bindgen]
+pub fn order_key_between(before: Option<String>, after: Option<String>) -> Result<String, JsError> {
+    key_between(before.as_deref(), after.as_deref()).map_err(|e| JsError::new(&format!("{e:?}")))
+}
+
+#[wasm_bindgen]
+pub fn initial_order_keys(count: u32) -> Vec<String> {
+    initial_keys(count as usize)
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmEntryUsage {
+    pub entry_id: String,
+    /// Unix timestamp in seconds
+    pub last_used: Option<i64>,
+    pub use_count: u32,
+}
+
+impl From<WasmEntryUsage> for EntryUsage {
+    fn from(value: WasmEntryUsage) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            last_used: value.last_used,
+            use_count: value.use_count,
+        }
+    }
+}
+
+impl From<EntryUsage> for WasmEntryUsage {
+    fn from(value: EntryUsage) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            last_used: value.last_used,
+            use_count: value.use_count,
+        }
+    }
+}
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum WasmEntrySortMode {
+    Manual,
+    RecentlyUsed,
+    Alphabetical,
+    Issuer,
+}
+
+impl From<WasmEntrySortMode> for EntrySortMode {
+    fn from(value: WasmEntrySortMode) -> Self {
+        match value {
+            WasmEntrySortMode::Manual => Self::Manual,
+            WasmEntrySortMode::RecentlyUsed => Self::RecentlyUsed,
+            WasmEntrySortMode::Alphabetical => Self::Alphabetical,
+            WasmEntrySortMode::Issuer => Self::Issuer,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn sort_entries_for_display(
+    entries: Vec<AuthenticatorEntryWithOrder>,
+    usage: Vec<WasmEntryUsage>,
+    mode: WasmEntrySortMode,
+) -> Result<Vec<AuthenticatorEntryWithOrder>, JsError> {
+    let mut mapped = vec![];
+    for entry in entries {
+        mapped.push(EntryWithOrder::try_from(entry)?);
+    }
+    let usage: Vec<EntryUsage> = usage.into_iter().map(EntryUsage::from).collect();
+
+    let res = sort_entries(&mapped, &usage, mode.into());
+    Ok(res.into_iter().map(AuthenticatorEntryWithOrder::from).collect())
+}
+
+#[wasm_bindgen]
+pub fn record_entry_use(usage: Option<WasmEntryUsage>, entry_id: String) -> WasmEntryUsage {
+    let now = (js_sys::Date::now() / 1000.0) as i64;
+    let mut usage = usage.map(EntryUsage::from).unwrap_or_else(|| EntryUsage::new(entry_id));
+    usage.record_use(now);
+    usage.into()
+}
+
+#[wasm_bindgen]
+pub fn merge_entry_usage(local: Vec<WasmEntryUsage>, remote: Vec<WasmEntryUsage>) -> Vec<WasmEntryUsage> {
+    let local: Vec<EntryUsage> = local.into_iter().map(EntryUsage::from).collect();
+    let remote: Vec<EntryUsage> = remote.into_iter().map(EntryUsage::from).collect();
+    proton_authenticator::merge_usage(&local, &remote)
+        .into_iter()
+        .map(WasmEntryUsage::from)
+        .collect()
+}
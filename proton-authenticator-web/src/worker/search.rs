@@ -0,0 +1,73 @@
+use crate::entry::WasmAuthenticatorEntryModel;
+use proton_authenticator::{EntrySearchIndex, SearchResult};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::JsError;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Tsify, Deserialize, Serialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WasmSearchResult {
+    pub entry_id: String,
+    pub score: u32,
+}
+
+impl From<SearchResult> for WasmSearchResult {
+    fn from(value: SearchResult) -> Self {
+        Self {
+            entry_id: value.entry_id,
+            score: value.score,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct WasmEntrySearchIndex {
+    inner: EntrySearchIndex,
+}
+
+impl Default for WasmEntrySearchIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmEntrySearchIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            inner: EntrySearchIndex::new(),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_entries(&mut self, entries: Vec<WasmAuthenticatorEntryModel>) -> Result<(), JsError> {
+        let mut mapped = Vec::with_capacity(entries.len());
+        for entry in entries {
+            mapped.push(entry.to_entry()?);
+        }
+        self.inner.set_entries(&mapped);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn upsert(&mut self, entry: WasmAuthenticatorEntryModel) -> Result<(), JsError> {
+        self.inner.upsert(&entry.to_entry()?);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn remove(&mut self, entry_id: String) -> bool {
+        self.inner.remove(&entry_id)
+    }
+
+    #[wasm_bindgen]
+    pub fn search(&self, query: String) -> Vec<WasmSearchResult> {
+        self.inner
+            .search(&query)
+            .into_iter()
+            .map(WasmSearchResult::from)
+            .collect()
+    }
+}
@@ -0,0 +1,546 @@
+use crate::crypto::{self, EncryptionTag};
+use crate::entry::{KdfParams, derive_password_key, export_entries, import_authenticator_entries};
+use crate::{AuthenticatorEntry, AuthenticatorError, ImportResult, ThirdPartyImportError};
+use base64::prelude::*;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use zeroize::Zeroizing;
+
+const BACKUP_VERSION: u8 = 1;
+const FILE_NAME_PREFIX: &str = "proton_authenticator_backup_";
+const FILE_NAME_SUFFIX: &str = ".json";
+const MAC_KEY_CONTEXT: &[u8] = b"proton.authenticator.backup.mac.v1";
+const ENCRYPTION_KEY_CONTEXT: &[u8] = b"proton.authenticator.backup.encryption.v1";
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Clone, Debug, proton_pass_derive::Error)]
+pub enum BackupError {
+    BadContent,
+    UnsupportedVersion(u8),
+    /// The content does not match the hash in the manifest
+    Corrupted,
+    /// The manifest MAC does not match. Either the credentials are wrong or the backup was tampered with
+    AuthenticationFailed,
+    /// The backup was protected in a different way than the one provided
+    WrongProtection,
+    InvalidKey,
+    Authenticator(AuthenticatorError),
+}
+
+impl From<AuthenticatorError> for BackupError {
+    fn from(e: AuthenticatorError) -> Self {
+        Self::Authenticator(e)
+    }
+}
+
+/// How the backup contents are protected
+pub enum BackupProtection {
+    Password(String),
+    Key(Vec<u8>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackupProtectionInfo {
+    /// The encryption and MAC keys are derived from the password with the given parameters and salt
+    Password { kdf: KdfParams, salt: String },
+    /// Id of the key the backup was encrypted with
+    Key { key_id: String },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub version: u8,
+    /// Unix timestamp in seconds
+    pub created_at: i64,
+    pub app_version: String,
+    pub entry_count: u32,
+    /// Hex encoded SHA-256 of the payload
+    pub content_hash: String,
+    pub protection: BackupProtectionInfo,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackupFile {
+    manifest: BackupManifest,
+    mac: String,
+    payload: String,
+}
+
+pub struct BackupSnapshot {
+    pub file_name: String,
+    pub manifest: BackupManifest,
+    pub contents: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackupFileInfo {
+    pub file_name: String,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct BackupRetentionPolicy {
+    /// Number of most recent backups to keep. The newest backup is always kept, even if this is 0
+    pub keep_last: u32,
+    /// Number of days for which the most recent backup of the day is kept, counting from the newest backup
+    pub keep_daily: u32,
+}
+
+/// Creates an encrypted snapshot of the entries. `now` is a unix timestamp in seconds.
+pub fn create_backup(
+    entries: Vec<AuthenticatorEntry>,
+    protection: &BackupProtection,
+    now: i64,
+) -> Result<BackupSnapshot, BackupError> {
+    let entry_count = entries.len() as u32;
+    let protection_info = match protection {
+        BackupProtection::Password(_) => {
+            let mut salt = [0u8; 16];
+            rand::thread_rng().fill_bytes(&mut salt);
+            BackupProtectionInfo::Password {
+                kdf: KdfParams::default(),
+                salt: BASE64_STANDARD.encode(salt),
+            }
+        }
+        BackupProtection::Key(key) => {
+            check_key(key)?;
+            BackupProtectionInfo::Key {
+                key_id: hex::encode(crypto::key_id(key)),
+            }
+        }
+    };
+    let keys = backup_keys(protection, &protection_info)?;
+
+    let exported = Zeroizing::new(export_entries(entries)?);
+    let encrypted = crypto::encrypt(exported.as_bytes(), &keys.encryption, EncryptionTag::Backup)
+        .map_err(|e| AuthenticatorError::Unknown(format!("failed to encrypt backup: {e:?}")))?;
+    let payload = BASE64_STANDARD.encode(encrypted);
+
+    let manifest = BackupManifest {
+        version: BACKUP_VERSION,
+        created_at: now,
+        app_version: crate::library_version(),
+        entry_count,
+        content_hash: content_hash(&payload),
+        protection: protection_info,
+    };
+    let mac = compute_mac(&keys.mac, &manifest, &payload)?;
+    let file = BackupFile {
+        manifest: manifest.clone(),
+        mac: BASE64_STANDARD.encode(mac),
+        payload,
+    };
+    let contents = serde_json::to_string(&file).map_err(|e| {
+        warn!("Error serializing backup: {e:?}");
+        BackupError::BadContent
+    })?;
+
+    Ok(BackupSnapshot {
+        file_name: backup_file_name(now),
+        manifest,
+        contents,
+    })
+}
+
+/// Reads the manifest and checks that the content matches its hash. Does not need the backup credentials,
+/// so it only detects accidental corruption. Use [`verify_backup`] to also authenticate the manifest.
+pub fn read_backup_manifest(contents: &str) -> Result<BackupManifest, BackupError> {
+    let file = parse_backup(contents)?;
+    Ok(file.manifest)
+}
+
+/// Checks the integrity and authenticity of a backup without importing its entries
+pub fn verify_backup(contents: &str, protection: &BackupProtection) -> Result<BackupManifest, BackupError> {
+    let file = parse_backup(contents)?;
+    verify_file(&file, protection)?;
+    Ok(file.manifest)
+}
+
+/// Verifies the backup and imports its entries
+pub fn restore_backup(contents: &str, protection: &BackupProtection) -> Result<ImportResult, BackupError> {
+    let file = parse_backup(contents)?;
+    let keys = verify_file(&file, protection)?;
+
+    let encrypted = BASE64_STANDARD
+        .decode(&file.payload)
+        .map_err(|_| BackupError::BadContent)?;
+    let decrypted = crypto::decrypt(&encrypted, &keys.encryption, EncryptionTag::Backup)
+        .map(Zeroizing::new)
+        .map_err(|_| AuthenticatorError::Import(ThirdPartyImportError::DecryptionFailed))?;
+    let as_str = std::str::from_utf8(&decrypted).map_err(|_| BackupError::BadContent)?;
+    Ok(import_authenticator_entries(as_str)?)
+}
+
+pub fn backup_file_name(created_at: i64) -> String {
+    format!("{FILE_NAME_PREFIX}{created_at}{FILE_NAME_SUFFIX}")
+}
+
+/// Returns the backups among the given file names, newest first. Other files are ignored.
+pub fn list_backups(file_names: &[String]) -> Vec<BackupFileInfo> {
+    let mut backups: Vec<BackupFileInfo> = file_names
+        .iter()
+        .filter_map(|name| {
+            let created_at = name
+                .strip_prefix(FILE_NAME_PREFIX)?
+                .strip_suffix(FILE_NAME_SUFFIX)?
+                .parse()
+                .ok()?;
+            Some(BackupFileInfo {
+                file_name: name.to_string(),
+                created_at,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.file_name.cmp(&b.file_name)));
+    backups
+}
+
+/// Returns the file names of the backups that should be deleted according to the policy.
+/// The newest backup is never returned, so pruning can't leave the user without any backup.
+pub fn backups_to_prune(file_names: &[String], policy: &BackupRetentionPolicy) -> Vec<String> {
+    let backups = list_backups(file_names);
+    let Some(newest) = backups.first() else {
+        return Vec::new();
+    };
+    let newest_day = newest.created_at.div_euclid(SECONDS_PER_DAY);
+
+    let mut kept_days = HashSet::new();
+    backups
+        .iter()
+        .enumerate()
+        .filter(|(idx, backup)| {
+            let day = backup.created_at.div_euclid(SECONDS_PER_DAY);
+            let in_daily_window = newest_day - day < policy.keep_daily as i64;
+            // Backups are sorted newest first, so the first one seen for a day is the most recent of that day
+            let kept_as_daily = in_daily_window && kept_days.insert(day);
+            let kept_as_last = *idx < policy.keep_last.max(1) as usize;
+            !(kept_as_last || kept_as_daily)
+        })
+        .map(|(_, backup)| backup.file_name.clone())
+        .collect()
+}
+
+fn check_key(key: &[u8]) -> Result<(), BackupError> {
+    if key.len() != crypto::KEY_LENGTH {
+        return Err(BackupError::InvalidKey);
+    }
+    Ok(())
+}
+
+fn parse_backup(contents: &str) -> Result<BackupFile, BackupError> {
+    let file: BackupFile = serde_json::from_str(contents).map_err(|e| {
+        warn!("Error parsing backup: {e:?}");
+        BackupError::BadContent
+    })?;
+    if file.manifest.version != BACKUP_VERSION {
+        return Err(BackupError::UnsupportedVersion(file.manifest.version));
+    }
+    if content_hash(&file.payload) != file.manifest.content_hash {
+        return Err(BackupError::Corrupted);
+    }
+    Ok(file)
+}
+
+fn content_hash(payload: &str) -> String {
+    hex::encode(Sha256::digest(payload.as_bytes()))
+}
+
+struct BackupKeys {
+    encryption: Zeroizing<Vec<u8>>,
+    mac: Zeroizing<Vec<u8>>,
+}
+
+// Checks the MAC, returning the keys so the payload can be decrypted without deriving them again
+fn verify_file(file: &BackupFile, protection: &BackupProtection) -> Result<BackupKeys, BackupError> {
+    let keys = backup_keys(protection, &file.manifest.protection)?;
+
+    let mac = BASE64_STANDARD.decode(&file.mac).map_err(|_| BackupError::BadContent)?;
+    let mut expected = new_mac(&keys.mac)?;
+    update_mac(&mut expected, &file.manifest, &file.payload)?;
+    expected
+        .verify_slice(&mac)
+        .map_err(|_| BackupError::AuthenticationFailed)?;
+
+    Ok(keys)
+}
+
+fn backup_keys(protection: &BackupProtection, info: &BackupProtectionInfo) -> Result<BackupKeys, BackupError> {
+    match (protection, info) {
+        (BackupProtection::Password(password), BackupProtectionInfo::Password { kdf, salt }) => {
+            // The parameters come from the manifest, which is not authenticated yet
+            kdf.validate()?;
+            let salt: [u8; 16] = BASE64_STANDARD
+                .decode(salt)
+                .ok()
+                .and_then(|salt| salt.try_into().ok())
+                .ok_or(BackupError::BadContent)?;
+            // A single derivation for both keys, as it is the expensive part of creating and restoring a backup
+            let master_key = derive_password_key(password, &salt, kdf)
+                .map_err(|e| AuthenticatorError::SerializationError(format!("Error deriving backup key: {e:?}")))?;
+            Ok(BackupKeys {
+                encryption: derive_subkey(master_key.as_slice(), ENCRYPTION_KEY_CONTEXT),
+                mac: derive_subkey(master_key.as_slice(), MAC_KEY_CONTEXT),
+            })
+        }
+        (BackupProtection::Key(key), BackupProtectionInfo::Key { .. }) => {
+            check_key(key)?;
+            Ok(BackupKeys {
+                encryption: Zeroizing::new(key.clone()),
+                mac: derive_subkey(key, MAC_KEY_CONTEXT),
+            })
+        }
+        _ => Err(BackupError::WrongProtection),
+    }
+}
+
+// Separate keys for each purpose, so no key is used for both encryption and MAC
+fn derive_subkey(key: &[u8], context: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(context);
+    Zeroizing::new(mac.finalize().into_bytes().to_vec())
+}
+
+fn new_mac(key: &[u8]) -> Result<Hmac<Sha256>, BackupError> {
+    <Hmac<Sha256> as Mac>::new_from_slice(key)
+        .map_err(|e| BackupError::Authenticator(AuthenticatorError::Unknown(format!("Error creating MAC: {e:?}"))))
+}
+
+fn update_mac(mac: &mut Hmac<Sha256>, manifest: &BackupManifest, payload: &str) -> Result<(), BackupError> {
+    let manifest_bytes = serde_json::to_vec(manifest).map_err(|_| BackupError::BadContent)?;
+    mac.update(&(manifest_bytes.len() as u64).to_be_bytes());
+    mac.update(&manifest_bytes);
+    mac.update(payload.as_bytes());
+    Ok(())
+}
+
+fn compute_mac(key: &[u8], manifest: &BackupManifest, payload: &str) -> Result<Vec<u8>, BackupError> {
+    let mut mac = new_mac(key)?;
+    update_mac(&mut mac, manifest, payload)?;
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::generate_encryption_key;
+
+    const NOW: i64 = 1_750_000_000;
+
+    fn entries() -> Vec<AuthenticatorEntry> {
+        vec![
+            AuthenticatorEntry::from_uri(
+                "otpauth://totp/label1?secret=MYSECRET&issuer=issuer1&algorithm=SHA1&digits=6&period=30",
+                None,
+            )
+            .unwrap(),
+            AuthenticatorEntry::from_uri("steam://STEAMKEY", Some("note".to_string())).unwrap(),
+        ]
+    }
+
+    fn tamper_manifest(contents: &str, f: impl FnOnce(&mut BackupManifest)) -> String {
+        let mut file: BackupFile = serde_json::from_str(contents).unwrap();
+        f(&mut file.manifest);
+        serde_json::to_string(&file).unwrap()
+    }
+
+    #[test]
+    fn can_create_verify_and_restore_key_backup() {
+        let key = generate_encryption_key();
+        let protection = BackupProtection::Key(key.clone());
+        let snapshot = create_backup(entries(), &protection, NOW).unwrap();
+
+        assert_eq!(backup_file_name(NOW), snapshot.file_name);
+        assert_eq!(2, snapshot.manifest.entry_count);
+        assert_eq!(NOW, snapshot.manifest.created_at);
+        assert_eq!(crate::library_version(), snapshot.manifest.app_version);
+        assert_eq!(
+            BackupProtectionInfo::Key {
+                key_id: hex::encode(crypto::key_id(&key))
+            },
+            snapshot.manifest.protection
+        );
+
+        assert_eq!(snapshot.manifest, read_backup_manifest(&snapshot.contents).unwrap());
+        assert_eq!(
+            snapshot.manifest,
+            verify_backup(&snapshot.contents, &protection).unwrap()
+        );
+
+        let restored = restore_backup(&snapshot.contents, &protection).unwrap();
+        let original = entries();
+        assert_eq!(2, restored.entries.len());
+        assert_eq!(original[0].content, restored.entries[0].content);
+        assert_eq!(original[1].note, restored.entries[1].note);
+    }
+
+    #[test]
+    fn can_create_verify_and_restore_password_backup() {
+        let protection = BackupProtection::Password("password".to_string());
+        let snapshot = create_backup(entries(), &protection, NOW).unwrap();
+
+        assert!(matches!(
+            snapshot.manifest.protection,
+            BackupProtectionInfo::Password { .. }
+        ));
+        verify_backup(&snapshot.contents, &protection).unwrap();
+        assert_eq!(
+            2,
+            restore_backup(&snapshot.contents, &protection).unwrap().entries.len()
+        );
+
+        let wrong = BackupProtection::Password("wrong".to_string());
+        assert!(matches!(
+            verify_backup(&snapshot.contents, &wrong),
+            Err(BackupError::AuthenticationFailed)
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_kdf_params_before_deriving() {
+        let protection = BackupProtection::Password("password".to_string());
+        let snapshot = create_backup(entries(), &protection, NOW).unwrap();
+
+        // Would need 4 TiB of memory if the parameters were used
+        let tampered = tamper_manifest(&snapshot.contents, |manifest| {
+            if let BackupProtectionInfo::Password { kdf, .. } = &mut manifest.protection {
+                kdf.memory_kib = u32::MAX;
+            }
+        });
+        assert!(matches!(
+            verify_backup(&tampered, &protection),
+            Err(BackupError::Authenticator(AuthenticatorError::SerializationError(_)))
+        ));
+        assert!(restore_backup(&tampered, &protection).is_err());
+    }
+
+    #[test]
+    fn detects_wrong_key_and_protection() {
+        let protection = BackupProtection::Key(generate_encryption_key());
+        let snapshot = create_backup(entries(), &protection, NOW).unwrap();
+
+        let other_key = BackupProtection::Key(generate_encryption_key());
+        assert!(matches!(
+            verify_backup(&snapshot.contents, &other_key),
+            Err(BackupError::AuthenticationFailed)
+        ));
+        assert!(restore_backup(&snapshot.contents, &other_key).is_err());
+
+        let password = BackupProtection::Password("password".to_string());
+        assert!(matches!(
+            verify_backup(&snapshot.contents, &password),
+            Err(BackupError::WrongProtection)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let protection = BackupProtection::Key(vec![1, 2, 3]);
+        assert!(matches!(
+            create_backup(entries(), &protection, NOW),
+            Err(BackupError::InvalidKey)
+        ));
+    }
+
+    #[test]
+    fn detects_corrupted_payload() {
+        let protection = BackupProtection::Key(generate_encryption_key());
+        let snapshot = create_backup(entries(), &protection, NOW).unwrap();
+
+        let mut file: BackupFile = serde_json::from_str(&snapshot.contents).unwrap();
+        file.payload.insert(0, 'A');
+        let corrupted = serde_json::to_string(&file).unwrap();
+
+        assert!(matches!(read_backup_manifest(&corrupted), Err(BackupError::Corrupted)));
+        assert!(matches!(
+            verify_backup(&corrupted, &protection),
+            Err(BackupError::Corrupted)
+        ));
+    }
+
+    #[test]
+    fn detects_tampered_manifest() {
+        let protection = BackupProtection::Key(generate_encryption_key());
+        let snapshot = create_backup(entries(), &protection, NOW).unwrap();
+
+        let tampered = tamper_manifest(&snapshot.contents, |manifest| manifest.entry_count = 10);
+        // The hash still matches, only the MAC can detect it
+        assert!(read_backup_manifest(&tampered).is_ok());
+        assert!(matches!(
+            verify_backup(&tampered, &protection),
+            Err(BackupError::AuthenticationFailed)
+        ));
+
+        let unsupported = tamper_manifest(&snapshot.contents, |manifest| manifest.version = 2);
+        assert!(matches!(
+            read_backup_manifest(&unsupported),
+            Err(BackupError::UnsupportedVersion(2))
+        ));
+
+        assert!(matches!(read_backup_manifest("not json"), Err(BackupError::BadContent)));
+    }
+
+    #[test]
+    fn lists_backups_newest_first() {
+        let names = vec![
+            backup_file_name(100),
+            "unrelated.json".to_string(),
+            backup_file_name(300),
+            "proton_authenticator_backup_invalid.json".to_string(),
+            backup_file_name(200),
+        ];
+
+        let backups = list_backups(&names);
+        assert_eq!(
+            vec![300, 200, 100],
+            backups.iter().map(|b| b.created_at).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn prunes_backups_according_to_policy() {
+        let day = SECONDS_PER_DAY;
+        let times = [
+            10 * day + 300,
+            10 * day + 200,
+            10 * day + 100,
+            9 * day + 200,
+            9 * day + 100,
+            8 * day + 100,
+            2 * day,
+        ];
+        let names: Vec<String> = times.iter().map(|t| backup_file_name(*t)).collect();
+
+        let policy = BackupRetentionPolicy {
+            keep_last: 2,
+            keep_daily: 3,
+        };
+        let mut pruned = backups_to_prune(&names, &policy);
+        pruned.sort();
+        let mut expected = vec![
+            backup_file_name(10 * day + 100),
+            backup_file_name(9 * day + 100),
+            backup_file_name(2 * day),
+        ];
+        expected.sort();
+        assert_eq!(expected, pruned);
+
+        assert!(backups_to_prune(&[], &policy).is_empty());
+    }
+
+    #[test]
+    fn pruning_always_keeps_the_newest_backup() {
+        let names: Vec<String> = [300, 200, 100].iter().map(|t| backup_file_name(*t)).collect();
+        let keep_nothing = BackupRetentionPolicy {
+            keep_last: 0,
+            keep_daily: 0,
+        };
+
+        let pruned = backups_to_prune(&names, &keep_nothing);
+        assert_eq!(vec![backup_file_name(200), backup_file_name(100)], pruned);
+        assert!(backups_to_prune(&names[..1], &keep_nothing).is_empty());
+    }
+}
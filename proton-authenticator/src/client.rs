@@ -91,6 +91,58 @@ impl AuthenticatorClient {
         })
     }
 
+    pub fn export_entries_with_password_and_params(
+        &self,
+        entries: Vec<AuthenticatorEntry>,
+        password: &str,
+        params: entry::KdfParams,
+    ) -> Result<String> {
+        entry::export_entries_with_password_and_params(entries, password, params).map_err(|e| {
+            let msg = format!("error exporting entries: {e:?}");
+            warn!("{}", msg);
+            AuthenticatorError::SerializationError(msg)
+        })
+    }
+
+    pub fn export_entries_to_aegis(&self, entries: Vec<AuthenticatorEntry>, password: Option<&str>) -> Result<String> {
+        entry::export_entries_to_aegis(entries, password).map_err(|e| {
+            let msg = format!("error exporting entries to Aegis: {e:?}");
+            warn!("{}", msg);
+            AuthenticatorError::SerializationError(msg)
+        })
+    }
+
+    pub fn export_entries_to_2fas(&self, entries: Vec<AuthenticatorEntry>) -> Result<String> {
+        entry::export_entries_to_2fas(entries).map_err(|e| {
+            let msg = format!("error exporting entries to 2FAS: {e:?}");
+            warn!("{}", msg);
+            AuthenticatorError::SerializationError(msg)
+        })
+    }
+
+    pub fn export_entries_to_bitwarden(&self, entries: Vec<AuthenticatorEntry>) -> Result<String> {
+        entry::export_entries_to_bitwarden(entries).map_err(|e| {
+            let msg = format!("error exporting entries to Bitwarden: {e:?}");
+            warn!("{}", msg);
+            AuthenticatorError::SerializationError(msg)
+        })
+    }
+
+    pub fn export_entries_to_google_migration(
+        &self,
+        entries: Vec<AuthenticatorEntry>,
+    ) -> Result<entry::GoogleMigrationExport> {
+        entry::export_entries_to_google_migration(entries).map_err(|e| {
+            let msg = format!("error exporting entries to Google Authenticator: {e:?}");
+            warn!("{}", msg);
+            AuthenticatorError::SerializationError(msg)
+        })
+    }
+
+    pub fn export_entries_to_otpauth_list(&self, entries: Vec<AuthenticatorEntry>) -> String {
+        entry::export_entries_to_otpauth_list(entries)
+    }
+
     pub(crate) fn generate_code(entry: &AuthenticatorEntry, time: u64) -> Result<AuthenticatorCodeResponse> {
         match &entry.content {
             AuthenticatorEntryContent::Totp(t) => {
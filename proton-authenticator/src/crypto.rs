@@ -1,11 +1,14 @@
 use aes_gcm::aead::{Aead, Payload};
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use rand::{RngCore, rngs::ThreadRng};
+use sha2::{Digest, Sha256};
 
 #[derive(Clone, Debug)]
 pub enum EncryptionTag {
     Entry,
     PasswordExport,
+    Backup,
+    EntryUsage,
     Unknown,
 }
 
@@ -14,12 +17,30 @@ impl EncryptionTag {
         match self {
             EncryptionTag::Entry => b"entrycontent".to_vec(),
             EncryptionTag::PasswordExport => b"proton.authenticator.export.v1".to_vec(),
+            EncryptionTag::Backup => b"proton.authenticator.backup.v1".to_vec(),
+            EncryptionTag::EntryUsage => b"proton.authenticator.usage.v1".to_vec(),
             EncryptionTag::Unknown => vec![],
         }
     }
 }
 
-const KEY_LENGTH: usize = 32;
+pub(crate) const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+/// Version of the envelope produced by [`seal`]
+pub const ENVELOPE_VERSION: u8 = 1;
+pub const KEY_ID_LENGTH: usize = 8;
+const ENVELOPE_HEADER_LENGTH: usize = 1 + KEY_ID_LENGTH;
+const KEY_ID_CONTEXT: &[u8] = b"proton.authenticator.keyid.v1";
+
+/// Format of an encrypted blob
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EnvelopeFormat {
+    /// Produced by [`encrypt`]: nonce followed by the ciphertext
+    Legacy,
+    /// Produced by [`seal`]: version, key id, nonce and ciphertext
+    Versioned { version: u8, key_id: [u8; KEY_ID_LENGTH] },
+}
 
 pub fn generate_encryption_key() -> Vec<u8> {
     random_bytes(KEY_LENGTH)
@@ -30,7 +51,7 @@ pub fn encrypt(data: &[u8], key: &[u8], tag: EncryptionTag) -> Result<Vec<u8>, a
     let cipher = Aes256Gcm::new(key.into());
 
     // Generate a random 12-byte nonce.
-    let nonce_bytes = random_bytes(12);
+    let nonce_bytes = random_bytes(NONCE_LENGTH);
     let nonce = Nonce::from_slice(&nonce_bytes);
 
     // Encrypt the data with the given AAD (or empty slice if None).
@@ -46,12 +67,12 @@ pub fn encrypt(data: &[u8], key: &[u8], tag: EncryptionTag) -> Result<Vec<u8>, a
 
 pub fn decrypt(ciphertext: &[u8], key: &[u8], tag: EncryptionTag) -> Result<Vec<u8>, aes_gcm::Error> {
     // Check that the ciphertext is at least large enough to contain the nonce.
-    if ciphertext.len() < 12 {
+    if ciphertext.len() < NONCE_LENGTH {
         return Err(aes_gcm::Error);
     }
 
     // Extract nonce and actual ciphertext.
-    let (nonce_bytes, cipherdata) = ciphertext.split_at(12);
+    let (nonce_bytes, cipherdata) = ciphertext.split_at(NONCE_LENGTH);
     let cipher = Aes256Gcm::new(key.into());
     let nonce = Nonce::from_slice(nonce_bytes);
     let aad = tag.aad();
@@ -62,7 +83,103 @@ pub fn decrypt(ciphertext: &[u8], key: &[u8], tag: EncryptionTag) -> Result<Vec<
     cipher.decrypt(nonce, payload)
 }
 
-fn random_bytes(count: usize) -> Vec<u8> {
+/// Identifier of a key, stored in the envelope so the key used to seal a blob can be detected
+pub fn key_id(key: &[u8]) -> [u8; KEY_ID_LENGTH] {
+    let mut hasher = Sha256::new();
+    hasher.update(KEY_ID_CONTEXT);
+    hasher.update(key);
+    let digest = hasher.finalize();
+
+    let mut id = [0; KEY_ID_LENGTH];
+    id.copy_from_slice(&digest[..KEY_ID_LENGTH]);
+    id
+}
+
+/// Encrypts the data into a versioned envelope. The envelope header is authenticated along with the tag.
+pub fn seal(data: &[u8], key: &[u8], tag: EncryptionTag) -> Result<Vec<u8>, aes_gcm::Error> {
+    if key.len() != KEY_LENGTH {
+        return Err(aes_gcm::Error);
+    }
+
+    let mut header = Vec::with_capacity(ENVELOPE_HEADER_LENGTH);
+    header.push(ENVELOPE_VERSION);
+    header.extend_from_slice(&key_id(key));
+
+    let cipher = Aes256Gcm::new(key.into());
+    let nonce_bytes = random_bytes(NONCE_LENGTH);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+    let aad = envelope_aad(&tag, &header);
+    let ciphertext = cipher.encrypt(nonce, Payload { msg: data, aad: &aad })?;
+
+    let mut result = header;
+    result.extend_from_slice(&nonce_bytes);
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+/// Decrypts a blob produced either by [`seal`] or by the legacy [`encrypt`]
+pub fn open(blob: &[u8], key: &[u8], tag: EncryptionTag) -> Result<Vec<u8>, aes_gcm::Error> {
+    if key.len() != KEY_LENGTH {
+        return Err(aes_gcm::Error);
+    }
+
+    // A legacy blob starts with a random nonce, so it could look like an envelope. Only trust the
+    // header if it was sealed with this key, and fall back to the legacy format otherwise.
+    match envelope_format(blob) {
+        EnvelopeFormat::Versioned { key_id: id, .. } if id == key_id(key) => {
+            let (header, rest) = blob.split_at(ENVELOPE_HEADER_LENGTH);
+            let (nonce_bytes, cipherdata) = rest.split_at(NONCE_LENGTH);
+            let cipher = Aes256Gcm::new(key.into());
+            let aad = envelope_aad(&tag, header);
+            cipher
+                .decrypt(
+                    Nonce::from_slice(nonce_bytes),
+                    Payload {
+                        msg: cipherdata,
+                        aad: &aad,
+                    },
+                )
+                .or_else(|_| decrypt(blob, key, tag))
+        }
+        _ => decrypt(blob, key, tag),
+    }
+}
+
+/// Format the blob appears to be in. As legacy blobs have no header, a legacy blob may be reported as
+/// versioned; use [`sealed_with`] to know which key can decrypt it.
+pub fn envelope_format(blob: &[u8]) -> EnvelopeFormat {
+    if blob.len() > ENVELOPE_HEADER_LENGTH + NONCE_LENGTH && blob[0] == ENVELOPE_VERSION {
+        let mut key_id = [0; KEY_ID_LENGTH];
+        key_id.copy_from_slice(&blob[1..ENVELOPE_HEADER_LENGTH]);
+        EnvelopeFormat::Versioned {
+            version: ENVELOPE_VERSION,
+            key_id,
+        }
+    } else {
+        EnvelopeFormat::Legacy
+    }
+}
+
+/// Returns the index of the key that the blob was encrypted with, if any of them can decrypt it
+pub fn sealed_with<K: AsRef<[u8]>>(blob: &[u8], keys: &[K], tag: EncryptionTag) -> Option<usize> {
+    if let EnvelopeFormat::Versioned { key_id: id, .. } = envelope_format(blob)
+        && let Some(idx) = keys.iter().position(|key| key_id(key.as_ref()) == id)
+    {
+        return Some(idx);
+    }
+
+    // Legacy blobs carry no key id, so the only way to know is to try every key
+    keys.iter()
+        .position(|key| key.as_ref().len() == KEY_LENGTH && decrypt(blob, key.as_ref(), tag.clone()).is_ok())
+}
+
+fn envelope_aad(tag: &EncryptionTag, header: &[u8]) -> Vec<u8> {
+    let mut aad = tag.aad();
+    aad.extend_from_slice(header);
+    aad
+}
+
+pub(crate) fn random_bytes(count: usize) -> Vec<u8> {
     let mut random_bytes = vec![0; count];
     let mut rng = ThreadRng::default();
     rng.fill_bytes(&mut random_bytes);
@@ -134,4 +251,89 @@ mod tests {
             assert!(decrypt(invalid_ciphertext, &key, EncryptionTag::Entry).is_err());
         }
     }
+
+    mod envelope {
+        use super::*;
+
+        #[test]
+        fn seal_and_open() {
+            let key = generate_encryption_key();
+            let data = b"Secret message!";
+            let sealed = seal(data, &key, EncryptionTag::Entry).expect("sealing failed");
+
+            assert_eq!(
+                EnvelopeFormat::Versioned {
+                    version: ENVELOPE_VERSION,
+                    key_id: key_id(&key)
+                },
+                envelope_format(&sealed)
+            );
+            assert_eq!(
+                data.to_vec(),
+                open(&sealed, &key, EncryptionTag::Entry).expect("opening failed")
+            );
+        }
+
+        #[test]
+        fn open_legacy_blob() {
+            let key = generate_encryption_key();
+            let data = b"Legacy message";
+            let legacy = encrypt(data, &key, EncryptionTag::Entry).expect("encryption failed");
+            assert_eq!(
+                data.to_vec(),
+                open(&legacy, &key, EncryptionTag::Entry).expect("opening failed")
+            );
+        }
+
+        #[test]
+        fn open_legacy_blob_that_looks_like_an_envelope() {
+            let key = generate_encryption_key();
+            let data = b"Legacy message";
+            let mut legacy = encrypt(data, &key, EncryptionTag::Entry).expect("encryption failed");
+            while legacy[0] != ENVELOPE_VERSION {
+                legacy = encrypt(data, &key, EncryptionTag::Entry).expect("encryption failed");
+            }
+            assert_eq!(
+                data.to_vec(),
+                open(&legacy, &key, EncryptionTag::Entry).expect("opening failed")
+            );
+        }
+
+        #[test]
+        fn tampered_header_is_rejected() {
+            let key = generate_encryption_key();
+            let mut sealed = seal(b"Tamper me", &key, EncryptionTag::Entry).expect("sealing failed");
+            sealed[0] = 2;
+            assert!(open(&sealed, &key, EncryptionTag::Entry).is_err());
+        }
+
+        #[test]
+        fn wrong_key_or_tag_is_rejected() {
+            let key = generate_encryption_key();
+            let sealed = seal(b"Message", &key, EncryptionTag::Entry).expect("sealing failed");
+            assert!(open(&sealed, &generate_encryption_key(), EncryptionTag::Entry).is_err());
+            assert!(open(&sealed, &key, EncryptionTag::Unknown).is_err());
+        }
+
+        #[test]
+        fn invalid_key_length_is_rejected() {
+            assert!(seal(b"Message", b"short", EncryptionTag::Entry).is_err());
+            assert!(open(&[0; 64], b"short", EncryptionTag::Entry).is_err());
+        }
+
+        #[test]
+        fn detects_key_used_to_seal() {
+            let old_key = generate_encryption_key();
+            let new_key = generate_encryption_key();
+            let keys = vec![old_key.clone(), new_key.clone()];
+
+            let sealed = seal(b"Message", &new_key, EncryptionTag::Entry).expect("sealing failed");
+            assert_eq!(Some(1), sealed_with(&sealed, &keys, EncryptionTag::Entry));
+
+            let legacy = encrypt(b"Message", &old_key, EncryptionTag::Entry).expect("encryption failed");
+            assert_eq!(Some(0), sealed_with(&legacy, &keys, EncryptionTag::Entry));
+
+            assert_eq!(None, sealed_with(&legacy, &[new_key], EncryptionTag::Entry));
+        }
+    }
 }
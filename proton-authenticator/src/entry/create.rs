@@ -41,6 +41,8 @@ impl AuthenticatorEntry {
             id: AuthenticatorEntry::generate_id(),
             content: AuthenticatorEntryContent::Totp(totp),
             note: params.note,
+            tags: Vec::new(),
+            favourite: false,
         })
     }
 
@@ -55,6 +57,8 @@ impl AuthenticatorEntry {
             id: AuthenticatorEntry::generate_id(),
             content: AuthenticatorEntryContent::Steam(steam),
             note: params.note,
+            tags: Vec::new(),
+            favourite: false,
         })
     }
 
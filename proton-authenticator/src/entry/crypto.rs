@@ -1,27 +1,31 @@
-use crate::crypto::{EncryptionTag, decrypt, encrypt};
+use crate::crypto::{EncryptionTag, KEY_LENGTH, encrypt, open, seal, sealed_with};
 use crate::{AuthenticatorEntry, AuthenticatorError};
+use zeroize::Zeroizing;
 
-pub fn encrypt_entries(entries: Vec<AuthenticatorEntry>, mut key: Vec<u8>) -> Result<Vec<Vec<u8>>, AuthenticatorError> {
+pub fn encrypt_entries(entries: Vec<AuthenticatorEntry>, key: Vec<u8>) -> Result<Vec<Vec<u8>>, AuthenticatorError> {
+    // Wiped on every return path
+    let key = Zeroizing::new(key);
     let mut encrypted_entries = Vec::with_capacity(entries.len());
     for entry in entries {
-        let serialized = entry.serialize().map_err(|e| {
+        let serialized = entry.serialize().map(Zeroizing::new).map_err(|e| {
             AuthenticatorError::SerializationError(format!("failed to serialize authenticator entry: {e:?}"))
         })?;
 
-        let encrypted = encrypt(&serialized, &key, EncryptionTag::Entry)
+        let encrypted = encrypt_entry(&serialized, &key)
             .map_err(|e| AuthenticatorError::Unknown(format!("failed to encrypt entry: {e:?}")))?;
 
         encrypted_entries.push(encrypted);
     }
 
-    key.clear();
     Ok(encrypted_entries)
 }
 
-pub fn decrypt_entries(entries: Vec<Vec<u8>>, mut key: Vec<u8>) -> Result<Vec<AuthenticatorEntry>, AuthenticatorError> {
+pub fn decrypt_entries(entries: Vec<Vec<u8>>, key: Vec<u8>) -> Result<Vec<AuthenticatorEntry>, AuthenticatorError> {
+    let key = Zeroizing::new(key);
     let mut decrypted_entries = Vec::with_capacity(entries.len());
     for entry in entries {
-        let decrypted = decrypt(&entry, &key, EncryptionTag::Entry)
+        let decrypted = open(&entry, &key, EncryptionTag::Entry)
+            .map(Zeroizing::new)
             .map_err(|e| AuthenticatorError::Unknown(format!("failed to decrypt entry: {e:?}")))?;
 
         let deserialized = AuthenticatorEntry::deserialize(&decrypted).map_err(|e| {
@@ -31,14 +35,58 @@ pub fn decrypt_entries(entries: Vec<Vec<u8>>, mut key: Vec<u8>) -> Result<Vec<Au
         decrypted_entries.push(deserialized);
     }
 
-    key.clear();
     Ok(decrypted_entries)
 }
 
+/// Migrates encrypted entries from `old_key` to `new_key`. Entries already sealed with `new_key` are kept as they are,
+/// so an interrupted migration can be resumed.
+pub fn reencrypt_entries(
+    entries: Vec<Vec<u8>>,
+    old_key: Vec<u8>,
+    new_key: Vec<u8>,
+) -> Result<Vec<Vec<u8>>, AuthenticatorError> {
+    let old_key = Zeroizing::new(old_key);
+    let new_key = Zeroizing::new(new_key);
+    let mut reencrypted_entries = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if sealed_with(&entry, &[new_key.as_slice()], EncryptionTag::Entry).is_some() {
+            reencrypted_entries.push(entry);
+            continue;
+        }
+
+        let decrypted = open(&entry, &old_key, EncryptionTag::Entry)
+            .map(Zeroizing::new)
+            .map_err(|e| AuthenticatorError::Unknown(format!("failed to decrypt entry: {e:?}")))?;
+        let reencrypted = encrypt_entry(&decrypted, &new_key)
+            .map_err(|e| AuthenticatorError::Unknown(format!("failed to encrypt entry: {e:?}")))?;
+
+        reencrypted_entries.push(reencrypted);
+    }
+
+    Ok(reencrypted_entries)
+}
+
+// Synced entries reach every device of the user, and clients that predate the versioned envelope can't open it. Entries
+// are written in the legacy format until the `versioned-envelope` feature is enabled, once all readers have shipped.
+fn encrypt_entry(data: &[u8], key: &[u8]) -> Result<Vec<u8>, aes_gcm::Error> {
+    if cfg!(feature = "versioned-envelope") {
+        seal(data, key, EncryptionTag::Entry)
+    } else if key.len() != KEY_LENGTH {
+        Err(aes_gcm::Error)
+    } else {
+        encrypt(data, key, EncryptionTag::Entry)
+    }
+}
+
+/// Returns the index of the key the encrypted entry was sealed with
+pub fn find_entry_key(entry: &[u8], keys: &[Vec<u8>]) -> Option<usize> {
+    sealed_with(entry, keys, EncryptionTag::Entry)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::crypto::generate_encryption_key;
+    use crate::crypto::{decrypt, generate_encryption_key};
     use crate::entry::serializer::deserialize_entry;
 
     #[test]
@@ -48,6 +96,7 @@ mod test {
     }
 
     #[test]
+    #[cfg(not(feature = "versioned-envelope"))]
     fn test_encrypt_entries_can_be_decrypted() {
         let key = generate_encryption_key();
         let e1 = AuthenticatorEntry::from_uri(
@@ -82,9 +131,96 @@ mod test {
         assert_eq!(deserialized_e3, e3);
     }
 
+    #[test]
+    fn test_encrypt_entries_can_be_opened() {
+        let key = generate_encryption_key();
+        let entries = vec![entry("LABEL1"), entry("LABEL2")];
+        let res = encrypt_entries(entries.clone(), key.clone()).expect("should be able to encrypt");
+
+        for (encrypted, expected) in res.iter().zip(entries) {
+            let decrypted = open(encrypted, &key, EncryptionTag::Entry).expect("should be able to open");
+            assert_eq!(
+                expected,
+                deserialize_entry(&decrypted).expect("should be able to deserialize")
+            );
+        }
+    }
+
     #[test]
     fn test_decrypt_entries_empty_list() {
         let res = decrypt_entries(vec![], generate_encryption_key()).expect("should not fail");
         assert!(res.is_empty());
     }
+
+    fn entry(label: &str) -> AuthenticatorEntry {
+        AuthenticatorEntry::from_uri(
+            &format!("otpauth://totp/{label}?secret=MYSECRET&issuer=MYISSUER&algorithm=SHA1&digits=6&period=30"),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decrypt_legacy_entries() {
+        let key = generate_encryption_key();
+        let e1 = entry("LEGACY");
+        let legacy = encrypt(&e1.clone().serialize().unwrap(), &key, EncryptionTag::Entry).unwrap();
+
+        let res = decrypt_entries(vec![legacy], key).expect("should be able to decrypt");
+        assert_eq!(vec![e1], res);
+    }
+
+    #[test]
+    fn test_legacy_clients_reject_versioned_entries() {
+        let key = generate_encryption_key();
+        let sealed = seal(&entry("LABEL").serialize().unwrap(), &key, EncryptionTag::Entry).unwrap();
+
+        // The header shifts the nonce, so authentication fails instead of returning garbage
+        assert!(decrypt(&sealed, &key, EncryptionTag::Entry).is_err());
+        assert_eq!(1, decrypt_entries(vec![sealed], key).unwrap().len());
+    }
+
+    #[test]
+    fn test_encrypt_entries_with_invalid_key_fails() {
+        assert!(encrypt_entries(vec![entry("LABEL")], vec![0; 16]).is_err());
+    }
+
+    #[test]
+    fn test_reencrypt_entries() {
+        let old_key = generate_encryption_key();
+        let new_key = generate_encryption_key();
+        let e1 = entry("LEGACY");
+        let e2 = entry("VERSIONED");
+        let e3 = entry("ALREADY_MIGRATED");
+
+        let legacy = encrypt(&e1.clone().serialize().unwrap(), &old_key, EncryptionTag::Entry).unwrap();
+        let versioned = seal(&e2.clone().serialize().unwrap(), &old_key, EncryptionTag::Entry).unwrap();
+        let migrated = encrypt_entries(vec![e3.clone()], new_key.clone()).unwrap().remove(0);
+
+        let res = reencrypt_entries(
+            vec![legacy, versioned, migrated.clone()],
+            old_key.clone(),
+            new_key.clone(),
+        )
+        .expect("should be able to reencrypt");
+        assert_eq!(migrated, res[2]);
+
+        let keys = vec![old_key.clone(), new_key.clone()];
+        for encrypted in &res {
+            assert_eq!(Some(1), find_entry_key(encrypted, &keys));
+            assert!(decrypt(encrypted, &old_key, EncryptionTag::Entry).is_err());
+        }
+
+        let decrypted = decrypt_entries(res, new_key).expect("should be able to decrypt");
+        assert_eq!(vec![e1, e2, e3], decrypted);
+    }
+
+    #[test]
+    fn test_reencrypt_entries_with_wrong_key_fails() {
+        let key = generate_encryption_key();
+        let encrypted = encrypt_entries(vec![entry("LABEL")], key).unwrap();
+
+        let res = reencrypt_entries(encrypted, generate_encryption_key(), generate_encryption_key());
+        assert!(res.is_err());
+    }
 }
@@ -14,6 +14,11 @@ pub struct ExportedAuthenticatorEntryContent {
     pub uri: String,
     pub entry_type: ExportedAuthenticatorEntryType,
     pub name: Option<String>,
+    /// Steam Guard enrollment data, only present on Steam entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revocation_code: Option<String>,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -22,6 +27,10 @@ pub struct ExportedAuthenticatorEntry {
     pub content: ExportedAuthenticatorEntryContent,
     #[serde(default)]
     pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favourite: bool,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -37,30 +46,33 @@ pub struct AuthenticatorEntriesExportHeader {
 
 impl From<AuthenticatorEntryContent> for ExportedAuthenticatorEntryContent {
     fn from(content: AuthenticatorEntryContent) -> Self {
-        match content {
+        match &content {
             AuthenticatorEntryContent::Totp(totp) => ExportedAuthenticatorEntryContent {
                 uri: totp.to_uri(None, None),
                 entry_type: ExportedAuthenticatorEntryType::Totp,
-                name: match totp.label {
-                    Some(label) => Some(label),
-                    None => totp.issuer,
-                },
+                name: totp.label.clone().or_else(|| totp.issuer.clone()),
+                account_name: None,
+                revocation_code: None,
             },
             AuthenticatorEntryContent::Steam(steam) => ExportedAuthenticatorEntryContent {
                 uri: steam.uri(),
                 entry_type: ExportedAuthenticatorEntryType::Steam,
-                name: steam.name,
+                name: steam.name.clone(),
+                account_name: steam.account_name(),
+                revocation_code: steam.revocation_code(),
             },
         }
     }
 }
 
 impl From<AuthenticatorEntry> for ExportedAuthenticatorEntry {
-    fn from(entry: AuthenticatorEntry) -> Self {
+    fn from(mut entry: AuthenticatorEntry) -> Self {
         Self {
-            id: entry.id,
-            note: entry.note,
-            content: ExportedAuthenticatorEntryContent::from(entry.content),
+            id: std::mem::take(&mut entry.id),
+            note: entry.note.take(),
+            content: ExportedAuthenticatorEntryContent::from(entry.content.clone()),
+            tags: std::mem::take(&mut entry.tags),
+            favourite: entry.favourite,
         }
     }
 }
@@ -78,6 +90,8 @@ impl TryFrom<ExportedAuthenticatorEntryContent> for AuthenticatorEntryContent {
                 let mut steam = SteamTotp::new_from_uri(&content.uri)
                     .map_err(|e| AuthenticatorError::SerializationError(format!("error parsing Steam uri: {e:?}")))?;
                 steam.set_name(content.name);
+                steam.set_account_name(content.account_name);
+                steam.set_revocation_code(content.revocation_code);
 
                 Ok(AuthenticatorEntryContent::Steam(steam))
             }
@@ -93,6 +107,8 @@ impl TryFrom<ExportedAuthenticatorEntry> for AuthenticatorEntry {
                 .map_err(|e| AuthenticatorError::SerializationError(format!("error parsing entry content: {e:?}")))?,
             note: entry.note,
             id: entry.id,
+            tags: entry.tags,
+            favourite: entry.favourite,
         })
     }
 }
@@ -198,11 +214,15 @@ mod tests {
         let steam_name = "STEAM_NAME";
         let mut steam_content = SteamTotp::new_from_uri("steam://STEAMKEY").unwrap();
         steam_content.set_name(Some(steam_name.to_string()));
+        steam_content.set_account_name(Some("steam_account".to_string()));
+        steam_content.set_revocation_code(Some("R12345".to_string()));
         let steam_note = "STEAM NOTE";
         let steam_entry = AuthenticatorEntry {
             id: AuthenticatorEntry::generate_id(),
             content: AuthenticatorEntryContent::Steam(steam_content),
             note: Some(steam_note.to_string()),
+            tags: vec!["Games".to_string()],
+            favourite: true,
         };
 
         let totp_id = totp_entry.id.clone();
@@ -220,6 +240,17 @@ mod tests {
         assert_eq!(steam_id, imported.entries[1].id);
         assert_eq!(Some(steam_note.to_string()), imported.entries[1].note);
         assert_eq!(steam_name.to_string(), imported.entries[1].name());
+        assert_eq!(vec!["Games".to_string()], imported.entries[1].tags);
+        assert!(imported.entries[1].favourite);
+        match &imported.entries[1].content {
+            AuthenticatorEntryContent::Steam(steam) => {
+                assert_eq!(Some("steam_account".to_string()), steam.account_name());
+                assert_eq!(Some("R12345".to_string()), steam.revocation_code());
+            }
+            _ => panic!("Should be a Steam entry"),
+        }
+        assert!(imported.entries[0].tags.is_empty());
+        assert!(!imported.entries[0].favourite);
     }
 
     #[test]
@@ -152,6 +152,10 @@ pub struct AuthenticatorEntryContentSteam {
     // message fields
     // @@protoc_insertion_point(field:AuthenticatorEntryContentSteam.secret)
     pub secret: ::std::string::String,
+    // @@protoc_insertion_point(field:AuthenticatorEntryContentSteam.account_name)
+    pub account_name: ::std::string::String,
+    // @@protoc_insertion_point(field:AuthenticatorEntryContentSteam.revocation_code)
+    pub revocation_code: ::std::string::String,
     // special fields
     // @@protoc_insertion_point(special_field:AuthenticatorEntryContentSteam.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -169,13 +173,23 @@ impl AuthenticatorEntryContentSteam {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(1);
+        let mut fields = ::std::vec::Vec::with_capacity(3);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "secret",
             |m: &AuthenticatorEntryContentSteam| { &m.secret },
             |m: &mut AuthenticatorEntryContentSteam| { &mut m.secret },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "account_name",
+            |m: &AuthenticatorEntryContentSteam| { &m.account_name },
+            |m: &mut AuthenticatorEntryContentSteam| { &mut m.account_name },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "revocation_code",
+            |m: &AuthenticatorEntryContentSteam| { &m.revocation_code },
+            |m: &mut AuthenticatorEntryContentSteam| { &mut m.revocation_code },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<AuthenticatorEntryContentSteam>(
             "AuthenticatorEntryContentSteam",
             fields,
@@ -197,6 +211,12 @@ impl ::protobuf::Message for AuthenticatorEntryContentSteam {
                 10 => {
                     self.secret = is.read_string()?;
                 },
+                18 => {
+                    self.account_name = is.read_string()?;
+                },
+                26 => {
+                    self.revocation_code = is.read_string()?;
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -212,6 +232,12 @@ impl ::protobuf::Message for AuthenticatorEntryContentSteam {
         if !self.secret.is_empty() {
             my_size += ::protobuf::rt::string_size(1, &self.secret);
         }
+        if !self.account_name.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.account_name);
+        }
+        if !self.revocation_code.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.revocation_code);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -221,6 +247,12 @@ impl ::protobuf::Message for AuthenticatorEntryContentSteam {
         if !self.secret.is_empty() {
             os.write_string(1, &self.secret)?;
         }
+        if !self.account_name.is_empty() {
+            os.write_string(2, &self.account_name)?;
+        }
+        if !self.revocation_code.is_empty() {
+            os.write_string(3, &self.revocation_code)?;
+        }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -239,12 +271,16 @@ impl ::protobuf::Message for AuthenticatorEntryContentSteam {
 
     fn clear(&mut self) {
         self.secret.clear();
+        self.account_name.clear();
+        self.revocation_code.clear();
         self.special_fields.clear();
     }
 
     fn default_instance() -> &'static AuthenticatorEntryContentSteam {
         static instance: AuthenticatorEntryContentSteam = AuthenticatorEntryContentSteam {
             secret: ::std::string::String::new(),
+            account_name: ::std::string::String::new(),
+            revocation_code: ::std::string::String::new(),
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
@@ -557,6 +593,10 @@ pub struct AuthenticatorEntryMetadata {
     pub note: ::std::string::String,
     // @@protoc_insertion_point(field:AuthenticatorEntryMetadata.id)
     pub id: ::std::string::String,
+    // @@protoc_insertion_point(field:AuthenticatorEntryMetadata.tags)
+    pub tags: ::std::vec::Vec<::std::string::String>,
+    // @@protoc_insertion_point(field:AuthenticatorEntryMetadata.favourite)
+    pub favourite: bool,
     // special fields
     // @@protoc_insertion_point(special_field:AuthenticatorEntryMetadata.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -574,7 +614,7 @@ impl AuthenticatorEntryMetadata {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(3);
+        let mut fields = ::std::vec::Vec::with_capacity(5);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "name",
@@ -591,6 +631,16 @@ impl AuthenticatorEntryMetadata {
             |m: &AuthenticatorEntryMetadata| { &m.id },
             |m: &mut AuthenticatorEntryMetadata| { &mut m.id },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "tags",
+            |m: &AuthenticatorEntryMetadata| { &m.tags },
+            |m: &mut AuthenticatorEntryMetadata| { &mut m.tags },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "favourite",
+            |m: &AuthenticatorEntryMetadata| { &m.favourite },
+            |m: &mut AuthenticatorEntryMetadata| { &mut m.favourite },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<AuthenticatorEntryMetadata>(
             "AuthenticatorEntryMetadata",
             fields,
@@ -618,6 +668,12 @@ impl ::protobuf::Message for AuthenticatorEntryMetadata {
                 26 => {
                     self.id = is.read_string()?;
                 },
+                34 => {
+                    self.tags.push(is.read_string()?);
+                },
+                40 => {
+                    self.favourite = is.read_bool()?;
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -639,6 +695,12 @@ impl ::protobuf::Message for AuthenticatorEntryMetadata {
         if !self.id.is_empty() {
             my_size += ::protobuf::rt::string_size(3, &self.id);
         }
+        for value in &self.tags {
+            my_size += ::protobuf::rt::string_size(4, &value);
+        };
+        if self.favourite != false {
+            my_size += 1 + 1;
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -654,6 +716,12 @@ impl ::protobuf::Message for AuthenticatorEntryMetadata {
         if !self.id.is_empty() {
             os.write_string(3, &self.id)?;
         }
+        for v in &self.tags {
+            os.write_string(4, &v)?;
+        };
+        if self.favourite != false {
+            os.write_bool(5, self.favourite)?;
+        }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -674,6 +742,8 @@ impl ::protobuf::Message for AuthenticatorEntryMetadata {
         self.name.clear();
         self.note.clear();
         self.id.clear();
+        self.tags.clear();
+        self.favourite = false;
         self.special_fields.clear();
     }
 
@@ -682,6 +752,8 @@ impl ::protobuf::Message for AuthenticatorEntryMetadata {
             name: ::std::string::String::new(),
             note: ::std::string::String::new(),
             id: ::std::string::String::new(),
+            tags: ::std::vec::Vec::new(),
+            favourite: false,
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
@@ -849,17 +921,20 @@ impl ::protobuf::reflect::ProtobufValue for AuthenticatorEntry {
 
 static file_descriptor_proto_data: &'static [u8] = b"\
     \n\x19authenticator_entry.proto\"1\n\x1dAuthenticatorEntryContentTotp\
-    \x12\x10\n\x03uri\x18\x01\x20\x01(\tR\x03uri\"8\n\x1eAuthenticatorEntryC\
-    ontentSteam\x12\x16\n\x06secret\x18\x01\x20\x01(\tR\x06secret\"\x95\x01\
-    \n\x19AuthenticatorEntryContent\x124\n\x04totp\x18\x01\x20\x01(\x0b2\x1e\
-    .AuthenticatorEntryContentTotpH\0R\x04totp\x127\n\x05steam\x18\x02\x20\
-    \x01(\x0b2\x1f.AuthenticatorEntryContentSteamH\0R\x05steamB\t\n\x07conte\
-    nt\"T\n\x1aAuthenticatorEntryMetadata\x12\x12\n\x04name\x18\x01\x20\x01(\
-    \tR\x04name\x12\x12\n\x04note\x18\x02\x20\x01(\tR\x04note\x12\x0e\n\x02i\
-    d\x18\x03\x20\x01(\tR\x02id\"\x83\x01\n\x12AuthenticatorEntry\x127\n\x08\
-    metadata\x18\x01\x20\x01(\x0b2\x1b.AuthenticatorEntryMetadataR\x08metada\
-    ta\x124\n\x07content\x18\x02\x20\x01(\x0b2\x1a.AuthenticatorEntryContent\
-    R\x07contentb\x06proto3\
+    \x12\x10\n\x03uri\x18\x01\x20\x01(\tR\x03uri\"\x84\x01\n\x1eAuthenticato\
+    rEntryContentSteam\x12\x16\n\x06secret\x18\x01\x20\x01(\tR\x06secret\x12\
+    !\n\x0caccount_name\x18\x02\x20\x01(\tR\x0baccountName\x12'\n\x0frevocat\
+    ion_code\x18\x03\x20\x01(\tR\x0erevocationCode\"\x95\x01\n\x19Authentica\
+    torEntryContent\x124\n\x04totp\x18\x01\x20\x01(\x0b2\x1e.AuthenticatorEn\
+    tryContentTotpH\0R\x04totp\x127\n\x05steam\x18\x02\x20\x01(\x0b2\x1f.Aut\
+    henticatorEntryContentSteamH\0R\x05steamB\t\n\x07content\"\x86\x01\n\x1a\
+    AuthenticatorEntryMetadata\x12\x12\n\x04name\x18\x01\x20\x01(\tR\x04name\
+    \x12\x12\n\x04note\x18\x02\x20\x01(\tR\x04note\x12\x0e\n\x02id\x18\x03\
+    \x20\x01(\tR\x02id\x12\x12\n\x04tags\x18\x04\x20\x03(\tR\x04tags\x12\x1c\
+    \n\tfavourite\x18\x05\x20\x01(\x08R\tfavourite\"\x83\x01\n\x12Authentica\
+    torEntry\x127\n\x08metadata\x18\x01\x20\x01(\x0b2\x1b.AuthenticatorEntry\
+    MetadataR\x08metadata\x124\n\x07content\x18\x02\x20\x01(\x0b2\x1a.Authen\
+    ticatorEntryContentR\x07contentb\x06proto3\
 ";
 
 /// `FileDescriptorProto` object which was a source for this generated file
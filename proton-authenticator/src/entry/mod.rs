@@ -6,17 +6,29 @@ mod r#gen;
 mod model;
 mod password_exporter;
 mod serializer;
+mod third_party_exporter;
 mod update;
+mod usage;
 
 use crate::steam::{PERIOD as STEAM_PERIOD, STEAM_DIGITS, STEAM_ISSUER, SteamTotp};
 pub use create::{AuthenticatorEntrySteamCreateParameters, AuthenticatorEntryTotpCreateParameters};
-pub use crypto::{decrypt_entries, encrypt_entries};
+pub use crypto::{decrypt_entries, encrypt_entries, find_entry_key, reencrypt_entries};
 pub use exporter::{export_entries, import_authenticator_entries};
 #[cfg(any(feature = "uniffi", feature = "wasm"))]
 pub use model::{AuthenticatorCodeResponseModel, AuthenticatorEntryModel};
-pub use password_exporter::{export_entries_with_password, import_entries_with_password};
+pub(crate) use password_exporter::derive_password_key;
+pub use password_exporter::{
+    KdfAlgorithm, KdfParams, KdfProfile, calibrate_kdf_params, export_entries_with_password,
+    export_entries_with_password_and_params, import_entries_with_password,
+};
 use proton_pass_totp::{Algorithm, TOTP};
+pub use third_party_exporter::{
+    GoogleMigrationExport, export_entries_to_2fas, export_entries_to_aegis, export_entries_to_bitwarden,
+    export_entries_to_google_migration, export_entries_to_otpauth_list,
+};
 pub use update::{AuthenticatorEntryType, AuthenticatorEntryUpdateContents};
+pub use usage::{EntryUsage, decrypt_usage, encrypt_usage, merge_usage};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Clone, Debug)]
 pub enum AuthenticatorInvalidDataParam {
@@ -39,6 +51,24 @@ pub enum AuthenticatorEntryContent {
     Steam(SteamTotp),
 }
 
+impl Zeroize for AuthenticatorEntryContent {
+    fn zeroize(&mut self) {
+        match self {
+            AuthenticatorEntryContent::Totp(totp) => totp.zeroize(),
+            AuthenticatorEntryContent::Steam(steam) => steam.zeroize(),
+        }
+    }
+}
+
+// The secret is wiped when the content is dropped
+impl Drop for AuthenticatorEntryContent {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for AuthenticatorEntryContent {}
+
 impl AuthenticatorEntryContent {
     pub fn from_uri(uri: &str) -> Result<AuthenticatorEntryContent, AuthenticatorEntryError> {
         let parsed = url::Url::parse(uri).map_err(|_| AuthenticatorEntryError::UnsupportedUri)?;
@@ -72,11 +102,18 @@ pub struct AuthenticatorEntry {
     pub id: String,
     pub content: AuthenticatorEntryContent,
     pub note: Option<String>,
+    /// Tags used to group entries. Folders from other apps are imported as tags
+    pub tags: Vec<String>,
+    pub favourite: bool,
 }
 
 impl PartialEq for AuthenticatorEntry {
     fn eq(&self, other: &Self) -> bool {
-        if !self.id.eq(&other.id) || !self.content.eq(&other.content) {
+        if !self.id.eq(&other.id)
+            || !self.content.eq(&other.content)
+            || self.tags != other.tags
+            || self.favourite != other.favourite
+        {
             return false;
         }
 
@@ -89,6 +126,21 @@ impl PartialEq for AuthenticatorEntry {
     }
 }
 
+impl Zeroize for AuthenticatorEntry {
+    fn zeroize(&mut self) {
+        self.content.zeroize();
+    }
+}
+
+// The secret is wiped when the entry is dropped
+impl Drop for AuthenticatorEntry {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for AuthenticatorEntry {}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct AuthenticatorEntryTotpParameters {
@@ -110,7 +162,18 @@ impl AuthenticatorEntry {
 
     pub fn from_uri_and_id(uri: &str, note: Option<String>, id: String) -> Result<Self, AuthenticatorEntryError> {
         let content = AuthenticatorEntryContent::from_uri(uri)?;
-        Ok(AuthenticatorEntry { content, note, id })
+        Ok(AuthenticatorEntry {
+            content,
+            note,
+            id,
+            tags: Vec::new(),
+            favourite: false,
+        })
+    }
+
+    /// Replaces the tags of the entry, trimming them and removing empty and duplicated ones
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.tags = normalize_tags(tags);
     }
 
     pub fn serialize(self) -> Result<Vec<u8>, AuthenticatorEntryError> {
@@ -189,6 +252,17 @@ impl AuthenticatorEntry {
     }
 }
 
+pub(crate) fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +333,40 @@ mod tests {
         let uri = entry.uri();
         assert_eq!(expected, uri);
     }
+
+    #[test]
+    fn set_tags_normalizes_input() {
+        let mut entry = AuthenticatorEntry::from_uri("otpauth://totp/label?secret=MYSECRET", None).unwrap();
+        entry.set_tags(vec![
+            " Work ".to_string(),
+            "".to_string(),
+            "Personal".to_string(),
+            "Work".to_string(),
+        ]);
+        assert_eq!(vec!["Work".to_string(), "Personal".to_string()], entry.tags);
+    }
+
+    #[test]
+    fn entries_wipe_their_secret() {
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<AuthenticatorEntry>();
+        assert_zeroize_on_drop::<AuthenticatorEntryContent>();
+
+        let mut entry = AuthenticatorEntry::from_uri("otpauth://totp/label?secret=MYSECRET&issuer=issuer", None)
+            .expect("Should be able to parse");
+        entry.zeroize();
+
+        let AuthenticatorEntryContent::Totp(totp) = &mut entry.content else {
+            panic!("should be a TOTP entry");
+        };
+        let mut buffer = std::mem::take(&mut totp.secret).into_bytes();
+        assert!(buffer.is_empty());
+        // SAFETY: zeroize has written every byte of the capacity, so the spare capacity is initialized
+        let wiped = buffer
+            .spare_capacity_mut()
+            .iter()
+            .all(|byte| unsafe { byte.assume_init() } == 0);
+        assert!(wiped);
+        assert_eq!("label", entry.name());
+    }
 }
@@ -13,17 +13,26 @@ pub struct AuthenticatorEntryModel {
     pub secret: String,
     pub note: Option<String>,
     pub entry_type: AuthenticatorEntryType,
+    pub tags: Vec<String>,
+    pub favourite: bool,
+    /// Steam Guard enrollment data, only present on Steam entries
+    pub steam_account_name: Option<String>,
+    pub steam_revocation_code: Option<String>,
 }
 
 impl AuthenticatorEntryModel {
     pub fn to_entry(&self) -> Result<AuthenticatorEntry, AuthenticatorEntryError> {
         let mut entry = AuthenticatorEntry::from_uri_and_id(&self.uri, self.note.clone(), self.id.clone())?;
 
-        if let AuthenticatorEntryContent::Steam(ref mut steam) = entry.content
-            && !self.name.trim().is_empty()
-        {
-            steam.set_name(Some(self.name.trim().to_string()));
+        if let AuthenticatorEntryContent::Steam(ref mut steam) = entry.content {
+            if !self.name.trim().is_empty() {
+                steam.set_name(Some(self.name.trim().to_string()));
+            }
+            steam.set_account_name(self.steam_account_name.clone());
+            steam.set_revocation_code(self.steam_revocation_code.clone());
         }
+        entry.set_tags(self.tags.clone());
+        entry.favourite = self.favourite;
 
         Ok(entry)
     }
@@ -31,6 +40,10 @@ impl AuthenticatorEntryModel {
 
 impl From<AuthenticatorEntry> for AuthenticatorEntryModel {
     fn from(entry: AuthenticatorEntry) -> Self {
+        let (steam_account_name, steam_revocation_code) = match &entry.content {
+            AuthenticatorEntryContent::Steam(steam) => (steam.account_name(), steam.revocation_code()),
+            AuthenticatorEntryContent::Totp(_) => (None, None),
+        };
         Self {
             id: entry.id.to_string(),
             name: entry.name(),
@@ -43,6 +56,10 @@ impl From<AuthenticatorEntry> for AuthenticatorEntryModel {
                 AuthenticatorEntryContent::Totp(_) => AuthenticatorEntryType::Totp,
                 AuthenticatorEntryContent::Steam(_) => AuthenticatorEntryType::Steam,
             },
+            tags: entry.tags.clone(),
+            favourite: entry.favourite,
+            steam_account_name,
+            steam_revocation_code,
         }
     }
 }
@@ -8,10 +8,93 @@ use argon2::{Argon2, password_hash::rand_core::OsRng};
 use base64::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use zeroize::Zeroizing;
+
+// Version 1 exports do not record the KDF parameters and always use the standard profile. They are still written
+// when the standard profile is used, so clients that only read version 1 can import them.
+const LEGACY_VERSION: u8 = 1;
+const CURRENT_VERSION: u8 = 2;
+
+// Calibration never goes above these values, as the export has to be importable on slower devices
+const CALIBRATION_MAX_MEMORY_KIB: u32 = 256 * 1024;
+const CALIBRATION_MAX_ITERATIONS: u32 = 10;
+
+// Upper bounds for the parameters read from an export, so a crafted file cannot exhaust the device. No profile nor
+// calibration needs more memory than the paranoid profile.
+const MAX_MEMORY_KIB: u32 = CALIBRATION_MAX_MEMORY_KIB;
+const MAX_ITERATIONS: u32 = 64;
+const MAX_PARALLELISM: u32 = 16;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KdfAlgorithm {
+    Argon2id,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KdfProfile {
+    /// Parameters used by version 1 exports
+    Standard,
+    Strong,
+    Paranoid,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub algorithm: KdfAlgorithm,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    pub fn for_profile(profile: KdfProfile) -> Self {
+        let (memory_kib, iterations) = match profile {
+            KdfProfile::Standard => (19 * 1024, 2),
+            KdfProfile::Strong => (64 * 1024, 3),
+            KdfProfile::Paranoid => (256 * 1024, 4),
+        };
+        Self {
+            algorithm: KdfAlgorithm::Argon2id,
+            memory_kib,
+            iterations,
+            parallelism: 1,
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), AuthenticatorError> {
+        if self.memory_kib > MAX_MEMORY_KIB || self.iterations > MAX_ITERATIONS || self.parallelism > MAX_PARALLELISM {
+            return Err(AuthenticatorError::SerializationError(format!(
+                "Unsupported KDF parameters: {self:?}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Same as [`Self::validate`], also rejecting parameters weaker than the standard profile
+    fn validate_for_export(&self) -> Result<(), AuthenticatorError> {
+        self.validate()?;
+        let standard = Self::for_profile(KdfProfile::Standard);
+        if self.memory_kib < standard.memory_kib || self.iterations < standard.iterations || self.parallelism == 0 {
+            return Err(AuthenticatorError::SerializationError(format!(
+                "KDF parameters are weaker than the standard profile: {self:?}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self::for_profile(KdfProfile::Standard)
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 struct EncryptedExport {
     version: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kdf: Option<KdfParams>,
     salt: String,
     content: String,
 }
@@ -20,25 +103,46 @@ pub fn export_entries_with_password(
     entries: Vec<AuthenticatorEntry>,
     password: &str,
 ) -> Result<String, AuthenticatorError> {
-    let exported_data = export_entries(entries)?;
+    export_entries_with_password_and_params(entries, password, KdfParams::default())
+}
+
+/// Parameters weaker than the standard profile are rejected. Exports using any other parameters than the standard
+/// profile record them in a version 2 export, which clients that only read version 1 cannot import.
+pub fn export_entries_with_password_and_params(
+    entries: Vec<AuthenticatorEntry>,
+    password: &str,
+    params: KdfParams,
+) -> Result<String, AuthenticatorError> {
+    params.validate_for_export()?;
+    let exported_data = Zeroizing::new(export_entries(entries)?);
 
     let mut salt = [0u8; 16];
     OsRng.fill_bytes(&mut salt);
-    let aes_key = derive_password_key(password, &salt).map_err(|e| {
+    let aes_key = derive_password_key(password, &salt, &params).map_err(|e| {
         AuthenticatorError::SerializationError(format!(
             "Error exporting authenticator entries, could not derive password: {e:?}"
         ))
     })?;
 
-    let cipher_text =
-        crypto::encrypt(&exported_data.into_bytes(), &aes_key, EncryptionTag::PasswordExport).map_err(|e| {
-            AuthenticatorError::SerializationError(format!(
-                "Error exporting authenticator entries, could not encrypt exported data: {e:?}"
-            ))
-        })?;
+    let cipher_text = crypto::encrypt(
+        exported_data.as_bytes(),
+        aes_key.as_slice(),
+        EncryptionTag::PasswordExport,
+    )
+    .map_err(|e| {
+        AuthenticatorError::SerializationError(format!(
+            "Error exporting authenticator entries, could not encrypt exported data: {e:?}"
+        ))
+    })?;
 
+    let (version, kdf) = if params == KdfParams::default() {
+        (LEGACY_VERSION, None)
+    } else {
+        (CURRENT_VERSION, Some(params))
+    };
     let encrypted_export = EncryptedExport {
-        version: 1,
+        version,
+        kdf,
         salt: BASE64_STANDARD.encode(salt),
         content: BASE64_STANDARD.encode(&cipher_text),
     };
@@ -50,17 +154,57 @@ pub fn export_entries_with_password(
     })
 }
 
-fn derive_password_key(password: &str, salt: &[u8; 16]) -> Result<[u8; 32], Box<dyn Error>> {
-    let argon2_params = argon2::ParamsBuilder::new()
-        .m_cost(19 * 1024)
-        .t_cost(2)
-        .p_cost(1)
-        .build()
-        .map_err(|e| AuthenticatorError::SerializationError(e.to_string()))?;
+/// Finds the strongest parameters whose key derivation takes at most `target_ms` on this device, never going below
+/// the standard profile. `now_ms` returns the current time in milliseconds.
+pub fn calibrate_kdf_params(target_ms: u64, now_ms: impl Fn() -> u64) -> Result<KdfParams, AuthenticatorError> {
+    let salt = [0u8; 16];
+    let mut best = KdfParams::default();
+    let mut candidate = Some(best.clone());
+
+    while let Some(params) = candidate {
+        let start = now_ms();
+        derive_password_key("calibration", &salt, &params)
+            .map_err(|e| AuthenticatorError::Unknown(format!("Error calibrating KDF parameters: {e:?}")))?;
+        if now_ms().saturating_sub(start) > target_ms {
+            break;
+        }
+
+        candidate = if params.memory_kib * 2 <= CALIBRATION_MAX_MEMORY_KIB {
+            Some(KdfParams {
+                memory_kib: params.memory_kib * 2,
+                ..params.clone()
+            })
+        } else if params.iterations < CALIBRATION_MAX_ITERATIONS {
+            Some(KdfParams {
+                iterations: params.iterations + 1,
+                ..params.clone()
+            })
+        } else {
+            None
+        };
+        best = params;
+    }
+
+    Ok(best)
+}
+
+pub(crate) fn derive_password_key(
+    password: &str,
+    salt: &[u8; 16],
+    params: &KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, Box<dyn Error>> {
+    let argon2_params = match params.algorithm {
+        KdfAlgorithm::Argon2id => argon2::ParamsBuilder::new()
+            .m_cost(params.memory_kib)
+            .t_cost(params.iterations)
+            .p_cost(params.parallelism)
+            .build()
+            .map_err(|e| AuthenticatorError::SerializationError(e.to_string()))?,
+    };
     let argon2 = Argon2::new(Argon2id, V0x13, argon2_params);
-    let mut aes_key = [0u8; 32];
+    let mut aes_key = Zeroizing::new([0u8; 32]);
     argon2
-        .hash_password_into(password.as_bytes(), salt, &mut aes_key)
+        .hash_password_into(password.as_bytes(), salt, aes_key.as_mut_slice())
         .map_err(|e| {
             AuthenticatorError::SerializationError(format!(
                 "Error exporting authenticator entries, could not hash password: {e:?}"
@@ -75,12 +219,23 @@ pub fn import_entries_with_password(input: &str, password: &str) -> Result<Impor
             "Error importing authenticator entries, could deserialize json: {e:?}"
         ))
     })?;
-    if encrypted_export.version != 1 {
-        return Err(AuthenticatorError::SerializationError(format!(
-            "Only encrypted export version 1 is supported, got version {}",
-            encrypted_export.version
-        )));
-    }
+    let params = match (encrypted_export.version, encrypted_export.kdf) {
+        (LEGACY_VERSION, _) => KdfParams::default(),
+        (CURRENT_VERSION, Some(params)) => {
+            params.validate()?;
+            params
+        }
+        (CURRENT_VERSION, None) => {
+            return Err(AuthenticatorError::SerializationError(
+                "Encrypted export is missing its KDF parameters".to_string(),
+            ));
+        }
+        (version, _) => {
+            return Err(AuthenticatorError::SerializationError(format!(
+                "Only encrypted export versions {LEGACY_VERSION} and {CURRENT_VERSION} are supported, got version {version}"
+            )));
+        }
+    };
     let salt = BASE64_STANDARD.decode(&encrypted_export.salt).map_err(|e| {
         AuthenticatorError::SerializationError(format!(
             "Error importing authenticator entries, could not decode salt: {e:?}"
@@ -93,7 +248,7 @@ pub fn import_entries_with_password(input: &str, password: &str) -> Result<Impor
         ))
     })?;
 
-    let aes_key = derive_password_key(password, salt_ref).map_err(|e| {
+    let aes_key = derive_password_key(password, salt_ref, &params).map_err(|e| {
         AuthenticatorError::SerializationError(format!(
             "Error importing authenticator entries, could not derive password: {e:?}"
         ))
@@ -103,10 +258,12 @@ pub fn import_entries_with_password(input: &str, password: &str) -> Result<Impor
             "Error importing authenticator entries, could not decode contents: {e:?}"
         ))
     })?;
-    let binary_export = crypto::decrypt(&cypher_text, &aes_key, EncryptionTag::PasswordExport).map_err(|e| {
-        warn!("Error importing authenticator entries, could not decrypt export: {e:?}");
-        AuthenticatorError::Import(ThirdPartyImportError::BadPassword)
-    })?;
+    let binary_export = crypto::decrypt(&cypher_text, aes_key.as_slice(), EncryptionTag::PasswordExport)
+        .map(Zeroizing::new)
+        .map_err(|e| {
+            warn!("Error importing authenticator entries, could not decrypt export: {e:?}");
+            AuthenticatorError::Import(ThirdPartyImportError::BadPassword)
+        })?;
 
     let plain_text = std::str::from_utf8(&binary_export).map_err(|e| {
         AuthenticatorError::SerializationError(format!(
@@ -121,6 +278,7 @@ pub fn import_entries_with_password(input: &str, password: &str) -> Result<Impor
 mod tests {
     use super::*;
     use crate::{AuthenticatorEntryContent, ThirdPartyImportError};
+    use zeroize::Zeroize;
 
     #[test]
     fn test_export_import_encrypted() {
@@ -159,4 +317,146 @@ mod tests {
             AuthenticatorError::Import(ThirdPartyImportError::BadPassword)
         ));
     }
+
+    #[test]
+    fn derived_key_can_be_wiped() {
+        let mut key = derive_password_key("DummyPassword", &[1; 16], &KdfParams::default()).unwrap();
+        assert_ne!([0u8; 32], *key);
+
+        key.zeroize();
+        assert_eq!([0u8; 32], *key);
+    }
+
+    fn entries() -> Vec<AuthenticatorEntry> {
+        vec![
+            AuthenticatorEntry::from_uri(
+                "otpauth://totp/MYLABEL?secret=MYSECRET&issuer=MYISSUER&algorithm=SHA256&digits=8&period=15",
+                None,
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn export_records_kdf_params() {
+        let params = KdfParams::for_profile(KdfProfile::Strong);
+        let exported = export_entries_with_password_and_params(entries(), "password", params.clone()).unwrap();
+
+        let parsed: EncryptedExport = serde_json::from_str(&exported).unwrap();
+        assert_eq!(CURRENT_VERSION, parsed.version);
+        assert_eq!(Some(params), parsed.kdf);
+
+        let imported = import_entries_with_password(&exported, "password").unwrap();
+        assert_eq!(1, imported.entries.len());
+    }
+
+    #[test]
+    fn default_export_is_readable_by_version_1_clients() {
+        let exported = export_entries_with_password(entries(), "password").unwrap();
+
+        let parsed: EncryptedExport = serde_json::from_str(&exported).unwrap();
+        assert_eq!(LEGACY_VERSION, parsed.version);
+        assert_eq!(None, parsed.kdf);
+        assert!(!exported.contains("kdf"));
+
+        let imported = import_entries_with_password(&exported, "password").unwrap();
+        assert_eq!(1, imported.entries.len());
+    }
+
+    #[test]
+    fn rejects_export_params_weaker_than_standard() {
+        let standard = KdfParams::default();
+        for weak in [
+            KdfParams {
+                memory_kib: standard.memory_kib - 1,
+                ..standard.clone()
+            },
+            KdfParams {
+                iterations: 1,
+                ..standard.clone()
+            },
+            KdfParams {
+                parallelism: 0,
+                ..standard.clone()
+            },
+        ] {
+            assert!(export_entries_with_password_and_params(entries(), "password", weak).is_err());
+        }
+    }
+
+    #[test]
+    fn can_import_legacy_export() {
+        let salt = [7u8; 16];
+        let key = derive_password_key("password", &salt, &KdfParams::default()).unwrap();
+        let contents = export_entries(entries()).unwrap();
+        let cipher_text = crypto::encrypt(contents.as_bytes(), key.as_slice(), EncryptionTag::PasswordExport).unwrap();
+        let legacy = format!(
+            r#"{{"version":1,"salt":"{}","content":"{}"}}"#,
+            BASE64_STANDARD.encode(salt),
+            BASE64_STANDARD.encode(cipher_text)
+        );
+
+        let imported = import_entries_with_password(&legacy, "password").unwrap();
+        assert_eq!(1, imported.entries.len());
+    }
+
+    #[test]
+    fn rejects_unsupported_params() {
+        let params = KdfParams {
+            memory_kib: MAX_MEMORY_KIB + 1,
+            ..KdfParams::default()
+        };
+        assert!(export_entries_with_password_and_params(entries(), "password", params.clone()).is_err());
+
+        let exported =
+            export_entries_with_password_and_params(entries(), "password", KdfParams::for_profile(KdfProfile::Strong))
+                .unwrap();
+        let mut parsed: EncryptedExport = serde_json::from_str(&exported).unwrap();
+        parsed.kdf = Some(params);
+        let tampered = serde_json::to_string(&parsed).unwrap();
+        assert!(matches!(
+            import_entries_with_password(&tampered, "password"),
+            Err(AuthenticatorError::SerializationError(_))
+        ));
+
+        parsed.kdf = None;
+        let missing = serde_json::to_string(&parsed).unwrap();
+        assert!(import_entries_with_password(&missing, "password").is_err());
+
+        parsed.version = 3;
+        let unknown_version = serde_json::to_string(&parsed).unwrap();
+        assert!(import_entries_with_password(&unknown_version, "password").is_err());
+    }
+
+    #[test]
+    fn calibration_stops_before_exceeding_target() {
+        // Every measurement takes 100ms longer than the previous one
+        let calls = std::cell::Cell::new(0u64);
+        let clock = || {
+            let call = calls.get() + 1;
+            calls.set(call);
+            let measurement = call.div_ceil(2);
+            // Start and end of each measurement
+            if call % 2 == 1 {
+                measurement * 1000
+            } else {
+                measurement * 1000 + measurement * 100
+            }
+        };
+
+        let params = calibrate_kdf_params(250, clock).unwrap();
+        let standard = KdfParams::default();
+        assert_eq!(standard.memory_kib * 2, params.memory_kib);
+        assert_eq!(standard.iterations, params.iterations);
+    }
+
+    #[test]
+    fn calibration_never_goes_below_standard_profile() {
+        let calls = std::cell::Cell::new(0u64);
+        let clock = || {
+            calls.set(calls.get() + 1000);
+            calls.get()
+        };
+        assert_eq!(KdfParams::default(), calibrate_kdf_params(10, clock).unwrap());
+    }
 }
@@ -7,11 +7,13 @@ use proton_pass_totp::totp::TOTP;
 
 impl From<AuthenticatorEntryContent> for proto::AuthenticatorEntryContent {
     fn from(content: AuthenticatorEntryContent) -> Self {
-        match content {
+        match &content {
             AuthenticatorEntryContent::Steam(steam) => proto::AuthenticatorEntryContent {
                 content: Some(proto::authenticator_entry_content::Content::Steam(
                     proto::AuthenticatorEntryContentSteam {
                         secret: steam.secret(),
+                        account_name: steam.account_name().unwrap_or_default(),
+                        revocation_code: steam.revocation_code().unwrap_or_default(),
                         ..Default::default()
                     },
                 )),
@@ -40,9 +42,11 @@ impl From<AuthenticatorEntry> for proto::AuthenticatorEntry {
                 },
                 name: entry.name(),
                 id: entry.id.to_string(),
+                tags: entry.tags.clone(),
+                favourite: entry.favourite,
                 ..Default::default()
             }),
-            content: protobuf::MessageField::some(entry.content.into()),
+            content: protobuf::MessageField::some(entry.content.clone().into()),
             ..Default::default()
         }
     }
@@ -65,20 +69,24 @@ impl TryFrom<proto::AuthenticatorEntry> for AuthenticatorEntry {
                             )));
                         }
                     },
-                    proto::authenticator_entry_content::Content::Steam(steam) => match SteamTotp::new(&steam.secret) {
-                        Ok(mut steam) => {
-                            if !metadata.name.is_empty() {
-                                steam.name = Some(metadata.name.to_string())
-                            }
+                    proto::authenticator_entry_content::Content::Steam(steam_content) => {
+                        match SteamTotp::new(&steam_content.secret) {
+                            Ok(mut steam) => {
+                                if !metadata.name.is_empty() {
+                                    steam.name = Some(metadata.name.to_string())
+                                }
+                                steam.set_account_name(Some(steam_content.account_name.to_string()));
+                                steam.set_revocation_code(Some(steam_content.revocation_code.to_string()));
 
-                            AuthenticatorEntryContent::Steam(steam)
-                        }
-                        Err(e) => {
-                            return Err(AuthenticatorEntryError::SerializationError(format!(
-                                "error parsing Steam uri: {e:?}"
-                            )));
+                                AuthenticatorEntryContent::Steam(steam)
+                            }
+                            Err(e) => {
+                                return Err(AuthenticatorEntryError::SerializationError(format!(
+                                    "error parsing Steam uri: {e:?}"
+                                )));
+                            }
                         }
-                    },
+                    }
                 },
                 None => {
                     return Err(AuthenticatorEntryError::SerializationError(
@@ -96,6 +104,8 @@ impl TryFrom<proto::AuthenticatorEntry> for AuthenticatorEntry {
             } else {
                 metadata.id.to_string()
             },
+            tags: metadata.tags.clone(),
+            favourite: metadata.favourite,
         })
     }
 }
@@ -127,4 +137,70 @@ mod test {
         let deserialized = deserialize_entry(&serialized).expect("should be able to deserialize");
         assert_eq!(entry.id, deserialized.id);
     }
+
+    #[test]
+    fn tags_and_favourite_are_persisted() {
+        let mut entry = AuthenticatorEntry::from_uri("otpauth://totp/MYLABEL?secret=MYSECRET", None).unwrap();
+        entry.tags = vec!["Work".to_string(), "Cloud".to_string()];
+        entry.favourite = true;
+
+        let serialized = entry.clone().serialize().expect("should be able to serialize");
+        let deserialized = deserialize_entry(&serialized).expect("should be able to deserialize");
+        assert_eq!(entry.tags, deserialized.tags);
+        assert!(deserialized.favourite);
+    }
+
+    #[test]
+    fn entries_without_tags_can_be_deserialized() {
+        // Serialized before tags and favourite were added
+        let legacy = proto::AuthenticatorEntry {
+            metadata: protobuf::MessageField::some(proto::AuthenticatorEntryMetadata {
+                name: "MYLABEL".to_string(),
+                id: "some-id".to_string(),
+                ..Default::default()
+            }),
+            content: protobuf::MessageField::some(proto::AuthenticatorEntryContent {
+                content: Some(proto::authenticator_entry_content::Content::Totp(
+                    proto::AuthenticatorEntryContentTotp {
+                        uri: "otpauth://totp/MYLABEL?secret=MYSECRET".to_string(),
+                        ..Default::default()
+                    },
+                )),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let bytes = legacy.write_to_bytes().unwrap();
+
+        let deserialized = deserialize_entry(&bytes).expect("should be able to deserialize");
+        assert_eq!("some-id", deserialized.id);
+        assert!(deserialized.tags.is_empty());
+        assert!(!deserialized.favourite);
+    }
+
+    #[test]
+    fn steam_enrollment_is_persisted() {
+        let mut steam = SteamTotp::new("STEAMKEY").unwrap();
+        steam.set_name(Some("Steam account".to_string()));
+        steam.set_account_name(Some("gaben".to_string()));
+        steam.set_revocation_code(Some("R12345".to_string()));
+        let entry = AuthenticatorEntry {
+            content: AuthenticatorEntryContent::Steam(steam),
+            note: None,
+            id: AuthenticatorEntry::generate_id(),
+            tags: Vec::new(),
+            favourite: false,
+        };
+
+        let serialized = entry.clone().serialize().expect("should be able to serialize");
+        let deserialized = deserialize_entry(&serialized).expect("should be able to deserialize");
+        assert_eq!(entry.content, deserialized.content);
+        match &deserialized.content {
+            AuthenticatorEntryContent::Steam(steam) => {
+                assert_eq!(Some("gaben".to_string()), steam.account_name());
+                assert_eq!(Some("R12345".to_string()), steam.revocation_code());
+            }
+            _ => panic!("Should be a Steam entry"),
+        }
+    }
 }
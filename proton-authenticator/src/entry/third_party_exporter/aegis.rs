@@ -0,0 +1,237 @@
+use super::{ExportGroup, OtpKind, OtpParameters, collect_groups, group_id, to_json};
+use crate::crypto::random_bytes;
+use crate::{AuthenticatorEntry, AuthenticatorError};
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::Engine;
+use scrypt::{Params as ScryptParams, scrypt};
+
+const VAULT_VERSION: u8 = 1;
+const DB_VERSION: u8 = 3;
+const PASSWORD_SLOT_TYPE: u8 = 1;
+
+// Same parameters Aegis uses when creating a password slot
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+#[derive(serde::Serialize)]
+struct AegisInfo {
+    secret: String,
+    algo: String,
+    digits: u8,
+    period: u16,
+}
+
+#[derive(serde::Serialize)]
+struct AegisEntry {
+    #[serde(rename = "type")]
+    entry_type: &'static str,
+    uuid: String,
+    name: String,
+    issuer: String,
+    note: String,
+    favorite: bool,
+    icon: Option<String>,
+    info: AegisInfo,
+    groups: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+struct AegisGroup {
+    uuid: String,
+    name: String,
+}
+
+#[derive(serde::Serialize)]
+struct AegisDb {
+    version: u8,
+    entries: Vec<AegisEntry>,
+    groups: Vec<AegisGroup>,
+}
+
+#[derive(serde::Serialize)]
+struct AegisKeyParams {
+    nonce: String,
+    tag: String,
+}
+
+#[derive(serde::Serialize)]
+struct AegisSlot {
+    #[serde(rename = "type")]
+    slot_type: u8,
+    uuid: String,
+    key: String,
+    key_params: AegisKeyParams,
+    n: u32,
+    r: u32,
+    p: u32,
+    salt: String,
+    repaired: bool,
+    is_backup: bool,
+}
+
+#[derive(serde::Serialize)]
+struct AegisHeader {
+    slots: Option<Vec<AegisSlot>>,
+    params: Option<AegisKeyParams>,
+}
+
+#[derive(serde::Serialize)]
+struct AegisVault<T> {
+    version: u8,
+    header: AegisHeader,
+    db: T,
+}
+
+impl AegisEntry {
+    fn new(entry: AuthenticatorEntry, groups: &[ExportGroup]) -> Self {
+        let params = OtpParameters::from(&entry);
+        Self {
+            entry_type: match params.kind {
+                OtpKind::Totp => "totp",
+                OtpKind::Steam => "steam",
+            },
+            uuid: entry.id.clone(),
+            name: params.name,
+            issuer: params.issuer,
+            note: entry.note.clone().unwrap_or_default(),
+            favorite: entry.favourite,
+            icon: None,
+            info: AegisInfo {
+                secret: params.secret,
+                algo: params.algorithm.value().to_string(),
+                digits: params.digits,
+                period: params.period,
+            },
+            groups: entry.tags.iter().filter_map(|tag| group_id(groups, tag)).collect(),
+        }
+    }
+}
+
+/// Exports the entries as an Aegis vault. When a password is provided the vault is encrypted the same
+/// way Aegis does it: a random master key encrypts the db, and is itself stored in a scrypt password slot.
+pub fn export_entries_to_aegis(
+    entries: Vec<AuthenticatorEntry>,
+    password: Option<&str>,
+) -> Result<String, AuthenticatorError> {
+    let groups = collect_groups(entries.iter().flat_map(|e| &e.tags));
+    let db = AegisDb {
+        version: DB_VERSION,
+        entries: entries.into_iter().map(|e| AegisEntry::new(e, &groups)).collect(),
+        groups: groups
+            .into_iter()
+            .map(|g| AegisGroup {
+                uuid: g.id,
+                name: g.name,
+            })
+            .collect(),
+    };
+
+    match password {
+        None => to_json(
+            &AegisVault {
+                version: VAULT_VERSION,
+                header: AegisHeader {
+                    slots: None,
+                    params: None,
+                },
+                db,
+            },
+            "Aegis",
+        ),
+        Some(password) => {
+            let db_json = to_json(&db, "Aegis")?;
+            let master_key = random_bytes(32);
+            let (db_ciphertext, db_params) = encrypt(&master_key, db_json.into_bytes())?;
+
+            let salt = random_bytes(32);
+            let params = ScryptParams::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, 32).map_err(|e| {
+                AuthenticatorError::SerializationError(format!("Error creating Aegis scrypt params: {e:?}"))
+            })?;
+            let mut derived_key = [0u8; 32];
+            scrypt(password.as_bytes(), &salt, &params, &mut derived_key).map_err(|e| {
+                AuthenticatorError::SerializationError(format!("Error deriving Aegis password key: {e:?}"))
+            })?;
+            let (encrypted_master_key, key_params) = encrypt(&derived_key, master_key)?;
+
+            let slot = AegisSlot {
+                slot_type: PASSWORD_SLOT_TYPE,
+                uuid: AuthenticatorEntry::generate_id(),
+                key: hex::encode(encrypted_master_key),
+                key_params,
+                n: 1 << SCRYPT_LOG_N,
+                r: SCRYPT_R,
+                p: SCRYPT_P,
+                salt: hex::encode(salt),
+                repaired: true,
+                is_backup: false,
+            };
+
+            to_json(
+                &AegisVault {
+                    version: VAULT_VERSION,
+                    header: AegisHeader {
+                        slots: Some(vec![slot]),
+                        params: Some(db_params),
+                    },
+                    db: base64::engine::general_purpose::STANDARD.encode(db_ciphertext),
+                },
+                "Aegis",
+            )
+        }
+    }
+}
+
+// Aegis stores the GCM tag separately from the ciphertext
+fn encrypt(key: &[u8], mut data: Vec<u8>) -> Result<(Vec<u8>, AegisKeyParams), AuthenticatorError> {
+    let nonce = random_bytes(12);
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| AuthenticatorError::SerializationError(format!("Error creating Aegis cipher: {e:?}")))?;
+    let tag = cipher
+        .encrypt_in_place_detached(Nonce::from_slice(&nonce), b"", &mut data)
+        .map_err(|e| AuthenticatorError::SerializationError(format!("Error encrypting Aegis vault: {e:?}")))?;
+
+    Ok((
+        data,
+        AegisKeyParams {
+            nonce: hex::encode(nonce),
+            tag: hex::encode(tag),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entry::third_party_exporter::test::{check_round_trip, entries_to_export};
+    use crate::parse_aegis_json;
+
+    #[test]
+    fn can_import_plain_export() {
+        let exported = export_entries_to_aegis(entries_to_export(), None).expect("should be able to export");
+        let imported = parse_aegis_json(&exported, None).expect("should be able to import");
+        assert!(imported.errors.is_empty());
+        check_round_trip(&imported.entries, true);
+        assert_eq!(Some("A note".to_string()), imported.entries[0].note);
+        assert_eq!(vec!["Work".to_string()], imported.entries[0].tags);
+        assert!(imported.entries[0].favourite);
+        assert!(imported.entries[1].tags.is_empty());
+        assert_eq!(vec!["Games".to_string(), "Work".to_string()], imported.entries[2].tags);
+    }
+
+    #[test]
+    fn can_import_encrypted_export() {
+        let exported = export_entries_to_aegis(entries_to_export(), Some("test")).expect("should be able to export");
+        let imported = parse_aegis_json(&exported, Some("test".to_string())).expect("should be able to import");
+        assert!(imported.errors.is_empty());
+        check_round_trip(&imported.entries, true);
+    }
+
+    #[test]
+    fn encrypted_export_requires_password() {
+        let exported = export_entries_to_aegis(entries_to_export(), Some("test")).expect("should be able to export");
+        assert!(parse_aegis_json(&exported, None).is_err());
+        assert!(parse_aegis_json(&exported, Some("invalid".to_string())).is_err());
+    }
+}
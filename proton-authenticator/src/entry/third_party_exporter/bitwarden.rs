@@ -0,0 +1,99 @@
+use super::{OtpKind, OtpParameters, collect_groups, group_id, to_json};
+use crate::{AuthenticatorEntry, AuthenticatorError};
+
+// Bitwarden item type for logins
+const LOGIN_TYPE: u8 = 1;
+
+#[derive(serde::Serialize)]
+struct BitwardenLogin {
+    totp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct BitwardenItem {
+    id: String,
+    name: String,
+    notes: Option<String>,
+    favorite: bool,
+    #[serde(rename = "type")]
+    item_type: u8,
+    login: BitwardenLogin,
+    #[serde(rename = "folderId")]
+    folder_id: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct BitwardenFolder {
+    id: String,
+    name: String,
+}
+
+#[derive(serde::Serialize)]
+struct BitwardenExport {
+    encrypted: bool,
+    folders: Vec<BitwardenFolder>,
+    items: Vec<BitwardenItem>,
+}
+
+/// Exports the entries as an unencrypted Bitwarden JSON export, in the format used by Bitwarden Authenticator.
+/// Bitwarden items belong to a single folder, which is the first tag of the entry
+pub fn export_entries_to_bitwarden(entries: Vec<AuthenticatorEntry>) -> Result<String, AuthenticatorError> {
+    let folders = collect_groups(entries.iter().filter_map(|e| e.tags.first()));
+    let items = entries
+        .into_iter()
+        .map(|entry| {
+            let params = OtpParameters::from(&entry);
+            let name = match params.kind {
+                OtpKind::Steam if !params.name.is_empty() => params.name.clone(),
+                _ if !params.issuer.is_empty() => params.issuer.clone(),
+                _ => params.name.clone(),
+            };
+
+            BitwardenItem {
+                name,
+                notes: entry.note.clone(),
+                favorite: entry.favourite,
+                folder_id: entry.tags.first().and_then(|tag| group_id(&folders, tag)),
+                item_type: LOGIN_TYPE,
+                login: BitwardenLogin {
+                    totp: entry.uri(),
+                    username: Some(params.name).filter(|n| !n.is_empty()),
+                },
+                id: entry.id.clone(),
+            }
+        })
+        .collect();
+
+    to_json(
+        &BitwardenExport {
+            encrypted: false,
+            folders: folders
+                .into_iter()
+                .map(|f| BitwardenFolder { id: f.id, name: f.name })
+                .collect(),
+            items,
+        },
+        "Bitwarden",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entry::third_party_exporter::test::{check_round_trip, entries_to_export};
+    use crate::parse_bitwarden_json;
+
+    #[test]
+    fn can_import_export() {
+        let exported = export_entries_to_bitwarden(entries_to_export()).expect("should be able to export");
+        let imported = parse_bitwarden_json(&exported).expect("should be able to import");
+        assert!(imported.errors.is_empty());
+        check_round_trip(&imported.entries, true);
+        assert_eq!(Some("A note".to_string()), imported.entries[0].note);
+        assert_eq!(vec!["Work".to_string()], imported.entries[0].tags);
+        assert!(imported.entries[0].favourite);
+        assert_eq!(vec!["Games".to_string()], imported.entries[2].tags);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::parser::google::r#gen::google_authenticator::MigrationPayload;
+use crate::parser::google::r#gen::google_authenticator::migration_payload as google;
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent, AuthenticatorError};
+use base64::Engine;
+use protobuf::{EnumOrUnknown, Message};
+use proton_pass_totp::Algorithm;
+use proton_pass_totp::totp::TOTP;
+use rand::Rng;
+use url::Url;
+
+const MIGRATION_BASE_URI: &str = "otpauth-migration://offline";
+const MIGRATION_VERSION: i32 = 1;
+// Google Authenticator does not put more than 10 accounts in a single code
+const MAX_ENTRIES_PER_PAYLOAD: usize = 10;
+// Byte mode capacity of a version 40 QR code with the default (M) error correction level
+const MAX_PAYLOAD_LEN: usize = 2331;
+// Google Authenticator ignores the period and always uses 30 seconds
+const GOOGLE_PERIOD: u16 = 30;
+
+#[derive(Clone, Debug, Default)]
+pub struct GoogleMigrationExport {
+    /// `otpauth-migration://offline?data=` URIs, one per QR code
+    pub payloads: Vec<String>,
+    /// Ids of the entries Google Authenticator cannot represent (Steam, custom periods or digits)
+    pub skipped: Vec<String>,
+}
+
+/// Encodes the entries into Google Authenticator migration payloads, splitting them into a batch of
+/// payloads small enough to fit into a QR code each.
+pub fn export_entries_to_google_migration(
+    entries: Vec<AuthenticatorEntry>,
+) -> Result<GoogleMigrationExport, AuthenticatorError> {
+    let mut export = GoogleMigrationExport::default();
+    let mut batches: Vec<Vec<google::OtpParameters>> = vec![];
+    let mut current = vec![];
+    for entry in entries {
+        let Some(parameters) = to_otp_parameters(&entry) else {
+            export.skipped.push(entry.id.clone());
+            continue;
+        };
+
+        current.push(parameters);
+        let too_big =
+            current.len() > MAX_ENTRIES_PER_PAYLOAD || payload_uri(&current, 0, 0, 0)?.len() > MAX_PAYLOAD_LEN;
+        if too_big && current.len() > 1 {
+            let last = current.pop().expect("current batch is not empty");
+            batches.push(std::mem::replace(&mut current, vec![last]));
+        }
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+
+    let batch_id = rand::thread_rng().r#gen::<i32>();
+    let batch_size = batches.len() as i32;
+    for (idx, batch) in batches.iter().enumerate() {
+        export
+            .payloads
+            .push(payload_uri(batch, batch_size, idx as i32, batch_id)?);
+    }
+
+    Ok(export)
+}
+
+fn to_otp_parameters(entry: &AuthenticatorEntry) -> Option<google::OtpParameters> {
+    let AuthenticatorEntryContent::Totp(totp) = &entry.content else {
+        return None;
+    };
+    if totp.period.is_some_and(|p| p != GOOGLE_PERIOD) {
+        return None;
+    }
+
+    let digits = match totp.digits {
+        None | Some(6) => google::DigitCount::DIGIT_COUNT_SIX,
+        Some(8) => google::DigitCount::DIGIT_COUNT_EIGHT,
+        Some(_) => return None,
+    };
+    let algorithm = match totp.algorithm {
+        None | Some(Algorithm::SHA1) => google::Algorithm::ALGORITHM_SHA1,
+        Some(Algorithm::SHA256) => google::Algorithm::ALGORITHM_SHA256,
+        Some(Algorithm::SHA512) => google::Algorithm::ALGORITHM_SHA512,
+    };
+
+    Some(google::OtpParameters {
+        secret: decode_secret(totp)?,
+        name: totp.label.clone().unwrap_or_default(),
+        issuer: totp.issuer.clone().unwrap_or_default(),
+        algorithm: EnumOrUnknown::new(algorithm),
+        digits: EnumOrUnknown::new(digits),
+        type_: EnumOrUnknown::new(google::OtpType::OTP_TYPE_TOTP),
+        ..Default::default()
+    })
+}
+
+fn decode_secret(totp: &TOTP) -> Option<Vec<u8>> {
+    let normalized: String = totp
+        .secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .collect::<String>()
+        .to_uppercase();
+    base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &normalized).filter(|s| !s.is_empty())
+}
+
+fn payload_uri(
+    parameters: &[google::OtpParameters],
+    batch_size: i32,
+    batch_index: i32,
+    batch_id: i32,
+) -> Result<String, AuthenticatorError> {
+    let payload = MigrationPayload {
+        otp_parameters: parameters.to_vec(),
+        version: MIGRATION_VERSION,
+        batch_size,
+        batch_index: Some(batch_index),
+        batch_id,
+        ..Default::default()
+    };
+    let bytes = payload.write_to_bytes().map_err(|e| {
+        AuthenticatorError::SerializationError(format!("Error encoding Google Authenticator payload: {e:?}"))
+    })?;
+
+    let mut uri = match Url::parse(MIGRATION_BASE_URI) {
+        Ok(uri) => uri,
+        Err(_) => panic!("Should be able to parse {MIGRATION_BASE_URI}"),
+    };
+    uri.query_pairs_mut()
+        .append_pair("data", &base64::engine::general_purpose::STANDARD.encode(bytes));
+    Ok(uri.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entry::third_party_exporter::test::entries_to_export;
+    use crate::parse_google_authenticator_totp;
+
+    fn decode_payload(uri: &str) -> MigrationPayload {
+        let parsed = Url::parse(uri).expect("should be a valid uri");
+        let data = parsed
+            .query_pairs()
+            .find(|(k, _)| k == "data")
+            .map(|(_, v)| v.to_string())
+            .expect("should have data");
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .expect("should be base64");
+        MigrationPayload::parse_from_bytes(&bytes).expect("should be a migration payload")
+    }
+
+    #[test]
+    fn can_import_export() {
+        let entries = entries_to_export();
+        let steam_id = entries[2].id.clone();
+
+        // Custom period is not supported by Google Authenticator
+        let export = export_entries_to_google_migration(entries).expect("should be able to export");
+        assert_eq!(export.payloads.len(), 1);
+        assert_eq!(export.skipped.len(), 2);
+        assert_eq!(export.skipped[1], steam_id);
+
+        let imported = parse_google_authenticator_totp(&export.payloads[0]).expect("should be able to import");
+        assert!(imported.errors.is_empty());
+        assert_eq!(imported.entries.len(), 1);
+        assert_eq!("Simple", imported.entries[0].issuer());
+        assert_eq!("GEZDGNBVGY3TQOJQ", imported.entries[0].secret());
+    }
+
+    #[test]
+    fn splits_entries_into_batches() {
+        let entries: Vec<AuthenticatorEntry> = (0..25)
+            .map(|i| {
+                AuthenticatorEntry::from_uri(
+                    &format!(
+                        "otpauth://totp/account{i}?secret=JBSWY3DPEHPK3PXP&issuer=Issuer{i}&algorithm=SHA256&digits=8"
+                    ),
+                    None,
+                )
+                .expect("should be able to create")
+            })
+            .collect();
+
+        let export = export_entries_to_google_migration(entries).expect("should be able to export");
+        assert!(export.skipped.is_empty());
+        assert_eq!(export.payloads.len(), 3);
+
+        let payloads: Vec<MigrationPayload> = export.payloads.iter().map(|p| decode_payload(p)).collect();
+        let batch_id = payloads[0].batch_id;
+        for (idx, payload) in payloads.iter().enumerate() {
+            assert_eq!(3, payload.batch_size);
+            assert_eq!(Some(idx as i32), payload.batch_index);
+            assert_eq!(batch_id, payload.batch_id);
+            assert_eq!(MIGRATION_VERSION, payload.version);
+        }
+
+        let mut imported = vec![];
+        for payload in &export.payloads {
+            assert!(payload.len() <= MAX_PAYLOAD_LEN);
+            let res = parse_google_authenticator_totp(payload).expect("should be able to import");
+            assert!(res.errors.is_empty());
+            imported.extend(res.entries);
+        }
+        assert_eq!(imported.len(), 25);
+        assert_eq!("Issuer24", imported[24].issuer());
+        assert_eq!("account24", imported[24].name());
+        let params = imported[24].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA256, params.algorithm);
+        assert_eq!(8, params.digits);
+    }
+
+    #[test]
+    fn respects_qr_capacity() {
+        let long_name = "a".repeat(600);
+        let entries: Vec<AuthenticatorEntry> = (0..6)
+            .map(|i| {
+                AuthenticatorEntry::from_uri(
+                    &format!("otpauth://totp/{long_name}{i}?secret=JBSWY3DPEHPK3PXP&issuer=Issuer"),
+                    None,
+                )
+                .expect("should be able to create")
+            })
+            .collect();
+
+        let export = export_entries_to_google_migration(entries).expect("should be able to export");
+        assert!(export.payloads.len() > 1);
+        for payload in &export.payloads {
+            assert!(payload.len() <= MAX_PAYLOAD_LEN);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::steam::{PERIOD as STEAM_PERIOD, STEAM_DIGITS, STEAM_ISSUER};
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent, AuthenticatorError};
+use proton_pass_totp::Algorithm;
+
+mod aegis;
+mod bitwarden;
+mod google;
+mod otpauth;
+mod twofas;
+
+pub use aegis::export_entries_to_aegis;
+pub use bitwarden::export_entries_to_bitwarden;
+pub use google::{GoogleMigrationExport, export_entries_to_google_migration};
+pub use otpauth::export_entries_to_otpauth_list;
+pub use twofas::export_entries_to_2fas;
+
+const DEFAULT_DIGITS: u8 = 6;
+const DEFAULT_PERIOD: u16 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OtpKind {
+    Totp,
+    Steam,
+}
+
+/// Flattened view of an entry with every parameter resolved, which is what third-party formats expect
+#[derive(Clone, Debug)]
+struct OtpParameters {
+    kind: OtpKind,
+    name: String,
+    issuer: String,
+    secret: String,
+    algorithm: Algorithm,
+    digits: u8,
+    period: u16,
+}
+
+impl From<&AuthenticatorEntry> for OtpParameters {
+    fn from(entry: &AuthenticatorEntry) -> Self {
+        match &entry.content {
+            AuthenticatorEntryContent::Totp(totp) => Self {
+                kind: OtpKind::Totp,
+                name: totp.label.clone().unwrap_or_default(),
+                issuer: totp.issuer.clone().unwrap_or_default(),
+                secret: totp.secret.clone(),
+                algorithm: totp.algorithm.unwrap_or(Algorithm::SHA1),
+                digits: totp.digits.unwrap_or(DEFAULT_DIGITS),
+                period: totp.period.unwrap_or(DEFAULT_PERIOD),
+            },
+            AuthenticatorEntryContent::Steam(steam) => Self {
+                kind: OtpKind::Steam,
+                name: steam.name(),
+                issuer: STEAM_ISSUER.to_string(),
+                // Other apps only understand base32 Steam secrets
+                secret: base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &steam.secret),
+                algorithm: Algorithm::SHA1,
+                digits: STEAM_DIGITS as u8,
+                period: STEAM_PERIOD,
+            },
+        }
+    }
+}
+
+/// A tag exported as a group/folder, for formats that reference them by id
+struct ExportGroup {
+    id: String,
+    name: String,
+}
+
+/// Distinct tags in order of first appearance, with a new id for each of them
+fn collect_groups<'a>(tags: impl Iterator<Item = &'a String>) -> Vec<ExportGroup> {
+    let mut groups: Vec<ExportGroup> = Vec::new();
+    for tag in tags {
+        if !groups.iter().any(|g| &g.name == tag) {
+            groups.push(ExportGroup {
+                id: AuthenticatorEntry::generate_id(),
+                name: tag.to_string(),
+            });
+        }
+    }
+    groups
+}
+
+fn group_id(groups: &[ExportGroup], tag: &str) -> Option<String> {
+    groups.iter().find(|g| g.name == tag).map(|g| g.id.to_string())
+}
+
+fn to_json<T: serde::Serialize>(value: &T, format: &str) -> Result<String, AuthenticatorError> {
+    serde_json::to_string(value)
+        .map_err(|e| AuthenticatorError::SerializationError(format!("Error exporting entries to {format}: {e:?}")))
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::steam::SteamTotp;
+    use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+
+    pub fn entries_to_export() -> Vec<AuthenticatorEntry> {
+        let totp = AuthenticatorEntry::from_uri(
+            "otpauth://totp/sometestaccount%40proton.me?secret=JBSWY3DPEHPK3PXP&issuer=Proton&algorithm=SHA256&digits=8&period=15",
+            Some("A note".to_string()),
+        )
+        .map(|mut entry| {
+            entry.tags = vec!["Work".to_string()];
+            entry.favourite = true;
+            entry
+        })
+        .expect("should be able to create");
+        let defaults =
+            AuthenticatorEntry::from_uri("otpauth://totp/Simple?secret=GEZDGNBVGY3TQOJQ&issuer=Simple", None)
+                .expect("should be able to create");
+
+        let mut steam = SteamTotp::new("STEAMKEY").expect("should be able to create");
+        steam.set_name(Some("SteamAccount".to_string()));
+        let steam = AuthenticatorEntry {
+            id: AuthenticatorEntry::generate_id(),
+            content: AuthenticatorEntryContent::Steam(steam),
+            note: None,
+            tags: vec!["Games".to_string(), "Work".to_string()],
+            favourite: false,
+        };
+
+        vec![totp, defaults, steam]
+    }
+
+    /// Checks the entries imported back match the ones returned by [entries_to_export]
+    pub fn check_round_trip(imported: &[AuthenticatorEntry], check_steam_name: bool) {
+        let original = entries_to_export();
+        assert_eq!(imported.len(), original.len());
+
+        for (imported, original) in imported.iter().zip(original.iter()) {
+            assert_eq!(imported.secret(), original.secret());
+            assert_eq!(imported.issuer(), original.issuer());
+            assert_eq!(imported.period(), original.period());
+            if check_steam_name || matches!(original.content, AuthenticatorEntryContent::Totp(_)) {
+                assert_eq!(imported.name(), original.name());
+            }
+        }
+
+        let totp = imported[0].get_totp_parameters().expect("should have parameters");
+        assert_eq!(proton_pass_totp::Algorithm::SHA256, totp.algorithm);
+        assert_eq!(8, totp.digits);
+
+        assert!(matches!(imported[2].content, AuthenticatorEntryContent::Steam(_)));
+        let code = crate::AuthenticatorClient::generate_code(&imported[2], 1742298317).expect("should generate");
+        assert_eq!("VKFDN", code.current_code);
+    }
+}
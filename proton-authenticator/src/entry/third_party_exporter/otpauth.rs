@@ -0,0 +1,57 @@
+use super::{OtpKind, OtpParameters};
+use crate::AuthenticatorEntry;
+use url::Url;
+
+const STEAM_BASE_URI: &str = "otpauth://steam/";
+
+/// Exports the entries as a newline-separated list of `otpauth://` URIs.
+/// Steam entries use the `otpauth://steam/` form understood by Aegis and most other apps.
+pub fn export_entries_to_otpauth_list(entries: Vec<AuthenticatorEntry>) -> String {
+    let mut lines = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let params = OtpParameters::from(&entry);
+        let line = match params.kind {
+            OtpKind::Totp => entry.uri(),
+            OtpKind::Steam => {
+                let label = if params.name.is_empty() {
+                    params.issuer.clone()
+                } else {
+                    format!("{}:{}", params.issuer, params.name)
+                };
+                let mut uri = match Url::parse(STEAM_BASE_URI) {
+                    Ok(uri) => uri,
+                    Err(_) => panic!("Should be able to parse {STEAM_BASE_URI}"),
+                };
+                uri.set_path(&label);
+                uri.query_pairs_mut()
+                    .append_pair("secret", &params.secret)
+                    .append_pair("issuer", &params.issuer)
+                    .append_pair("digits", &params.digits.to_string())
+                    .append_pair("period", &params.period.to_string())
+                    .append_pair("algorithm", params.algorithm.value());
+                uri.to_string()
+            }
+        };
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entry::third_party_exporter::test::{check_round_trip, entries_to_export};
+    use crate::parse_aegis_txt;
+
+    #[test]
+    fn can_import_export() {
+        let exported = export_entries_to_otpauth_list(entries_to_export());
+        assert_eq!(exported.lines().count(), 3);
+
+        let imported = parse_aegis_txt(&exported).expect("should be able to import");
+        assert!(imported.errors.is_empty());
+        // Steam URIs only carry the secret
+        check_round_trip(&imported.entries, false);
+    }
+}
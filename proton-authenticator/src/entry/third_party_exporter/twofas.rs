@@ -0,0 +1,122 @@
+use super::{OtpKind, OtpParameters, collect_groups, group_id, to_json};
+use crate::{AuthenticatorEntry, AuthenticatorError};
+
+const SCHEMA_VERSION: u8 = 4;
+
+#[derive(serde::Serialize)]
+struct TwoFasOtp {
+    label: String,
+    account: String,
+    issuer: String,
+    digits: u8,
+    period: u16,
+    algorithm: String,
+    #[serde(rename = "tokenType")]
+    token_type: &'static str,
+    source: &'static str,
+}
+
+#[derive(serde::Serialize)]
+struct TwoFasOrder {
+    position: usize,
+}
+
+#[derive(serde::Serialize)]
+struct TwoFasService {
+    name: String,
+    secret: String,
+    otp: TwoFasOtp,
+    order: TwoFasOrder,
+    #[serde(rename = "groupId", skip_serializing_if = "Option::is_none")]
+    group_id: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct TwoFasGroup {
+    id: String,
+    name: String,
+    #[serde(rename = "isExpanded")]
+    is_expanded: bool,
+}
+
+#[derive(serde::Serialize)]
+struct TwoFasExport {
+    services: Vec<TwoFasService>,
+    groups: Vec<TwoFasGroup>,
+    #[serde(rename = "schemaVersion")]
+    schema_version: u8,
+}
+
+/// Exports the entries as an unencrypted `.2fas` backup. 2FAS only supports one group per entry, so only the
+/// first tag of each entry is exported
+pub fn export_entries_to_2fas(entries: Vec<AuthenticatorEntry>) -> Result<String, AuthenticatorError> {
+    let groups = collect_groups(entries.iter().filter_map(|e| e.tags.first()));
+    let services = entries
+        .iter()
+        .enumerate()
+        .map(|(position, entry)| {
+            let params = OtpParameters::from(entry);
+            // 2FAS names the service after the issuer, and only uses the label as the account
+            let name = if params.issuer.is_empty() {
+                params.name.clone()
+            } else {
+                params.issuer.clone()
+            };
+
+            TwoFasService {
+                name,
+                secret: params.secret,
+                otp: TwoFasOtp {
+                    label: params.name.clone(),
+                    account: params.name,
+                    issuer: params.issuer,
+                    digits: params.digits,
+                    period: params.period,
+                    algorithm: params.algorithm.value().to_string(),
+                    token_type: match params.kind {
+                        OtpKind::Totp => "TOTP",
+                        OtpKind::Steam => "STEAM",
+                    },
+                    source: "Manual",
+                },
+                order: TwoFasOrder { position },
+                group_id: entry.tags.first().and_then(|tag| group_id(&groups, tag)),
+            }
+        })
+        .collect();
+
+    to_json(
+        &TwoFasExport {
+            services,
+            groups: groups
+                .into_iter()
+                .map(|g| TwoFasGroup {
+                    id: g.id,
+                    name: g.name,
+                    is_expanded: true,
+                })
+                .collect(),
+            schema_version: SCHEMA_VERSION,
+        },
+        "2FAS",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entry::third_party_exporter::test::{check_round_trip, entries_to_export};
+    use crate::parse_2fas_file;
+
+    #[test]
+    fn can_import_export() {
+        let exported = export_entries_to_2fas(entries_to_export()).expect("should be able to export");
+        let imported = parse_2fas_file(&exported, None).expect("should be able to import");
+        assert!(imported.errors.is_empty());
+        // 2FAS names Steam entries after the service
+        check_round_trip(&imported.entries, false);
+        assert_eq!(vec!["Work".to_string()], imported.entries[0].tags);
+        assert!(imported.entries[1].tags.is_empty());
+        assert_eq!(vec!["Games".to_string()], imported.entries[2].tags);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    AuthenticatorEntry, AuthenticatorEntryError, AuthenticatorEntrySteamCreateParameters,
+    AuthenticatorEntry, AuthenticatorEntryContent, AuthenticatorEntryError, AuthenticatorEntrySteamCreateParameters,
     AuthenticatorEntryTotpCreateParameters,
 };
 use proton_pass_derive::ffi_type;
@@ -51,8 +51,17 @@ impl AuthenticatorEntry {
             }
         }?;
 
-        self.note = new_entry.note;
-        self.content = new_entry.content;
+        let mut content = new_entry.content.clone();
+        // The enrollment data can't be edited, so keep it while the entry stays a Steam one
+        if let (AuthenticatorEntryContent::Steam(previous), AuthenticatorEntryContent::Steam(updated)) =
+            (&self.content, &mut content)
+        {
+            updated.set_account_name(previous.account_name());
+            updated.set_revocation_code(previous.revocation_code());
+        }
+
+        self.note = new_entry.note.clone();
+        self.content = content;
         Ok(())
     }
 }
@@ -60,7 +69,6 @@ impl AuthenticatorEntry {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::AuthenticatorEntryContent;
 
     #[test]
     fn can_update_totp_entry() {
@@ -88,13 +96,13 @@ mod test {
         assert_eq!(original_id, entry.id);
         assert_eq!(update_params.note, entry.note);
 
-        if let AuthenticatorEntryContent::Totp(totp) = entry.content {
+        if let AuthenticatorEntryContent::Totp(totp) = &entry.content {
             assert_eq!(update_params.algorithm, totp.get_algorithm());
             assert_eq!(update_params.digits, totp.get_digits());
             assert_eq!(update_params.period, totp.get_period());
             assert_eq!(update_params.secret, totp.secret);
-            assert_eq!(update_params.issuer, totp.issuer.unwrap());
-            assert_eq!(update_params.name, totp.label.unwrap());
+            assert_eq!(update_params.issuer, totp.issuer.clone().unwrap());
+            assert_eq!(update_params.name, totp.label.clone().unwrap());
         } else {
             panic!("Should be a TOTP entry");
         }
@@ -108,6 +116,9 @@ mod test {
             note: Some("original_note".to_string()),
         })
         .unwrap();
+        if let AuthenticatorEntryContent::Steam(ref mut steam) = entry.content {
+            steam.set_revocation_code(Some("R12345".to_string()));
+        }
 
         let original_id = entry.id.clone();
 
@@ -129,9 +140,10 @@ mod test {
         assert_eq!(original_id, entry.id);
         assert_eq!(update_params.note, entry.note);
 
-        if let AuthenticatorEntryContent::Steam(steam) = entry.content {
+        if let AuthenticatorEntryContent::Steam(steam) = &entry.content {
             assert_eq!(update_params.secret, steam.secret());
             assert_eq!(update_params.name, steam.name());
+            assert_eq!(Some("R12345".to_string()), steam.revocation_code());
         } else {
             panic!("Should be a Steam entry");
         }
@@ -164,13 +176,13 @@ mod test {
         assert_eq!(original_id, entry.id);
         assert_eq!(update_params.note, entry.note);
 
-        if let AuthenticatorEntryContent::Totp(totp) = entry.content {
+        if let AuthenticatorEntryContent::Totp(totp) = &entry.content {
             assert_eq!(update_params.algorithm, totp.get_algorithm());
             assert_eq!(update_params.digits, totp.get_digits());
             assert_eq!(update_params.period, totp.get_period());
             assert_eq!(update_params.secret, totp.secret);
-            assert_eq!(update_params.issuer, totp.issuer.unwrap());
-            assert_eq!(update_params.name, totp.label.unwrap());
+            assert_eq!(update_params.issuer, totp.issuer.clone().unwrap());
+            assert_eq!(update_params.name, totp.label.clone().unwrap());
         } else {
             panic!("Should be a TOTP entry");
         }
@@ -203,7 +215,7 @@ mod test {
         assert_eq!(original_id, entry.id);
         assert_eq!(update_params.note, entry.note);
 
-        if let AuthenticatorEntryContent::Steam(steam) = entry.content {
+        if let AuthenticatorEntryContent::Steam(steam) = &entry.content {
             assert_eq!(update_params.secret, steam.secret());
             assert_eq!(update_params.name, steam.name());
         } else {
@@ -0,0 +1,130 @@
+use crate::AuthenticatorError;
+use crate::crypto::{EncryptionTag, open, seal};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zeroize::Zeroizing;
+
+/// Usage statistics of an entry.
+///
+/// Kept outside of [`crate::AuthenticatorEntry`] so copying a code does not modify the entry contents, which would
+/// mark it as pending to sync and bump its revision on every copy.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EntryUsage {
+    pub entry_id: String,
+    /// Unix timestamp in seconds of the last time a code was used
+    pub last_used: Option<i64>,
+    pub use_count: u32,
+}
+
+impl EntryUsage {
+    pub fn new(entry_id: String) -> Self {
+        Self {
+            entry_id,
+            last_used: None,
+            use_count: 0,
+        }
+    }
+
+    pub fn record_use(&mut self, now: i64) {
+        self.last_used = self.last_used.max(Some(now));
+        self.use_count = self.use_count.saturating_add(1);
+    }
+
+    /// Combines the usage of the same entry recorded on two devices
+    pub fn merge(&mut self, other: &EntryUsage) {
+        self.last_used = self.last_used.max(other.last_used);
+        // Both sides may already include the uses of the other one, so adding them would count them twice
+        self.use_count = self.use_count.max(other.use_count);
+    }
+}
+
+/// Merges the local and remote usage records, returning one record per entry sorted by entry id
+pub fn merge_usage(local: &[EntryUsage], remote: &[EntryUsage]) -> Vec<EntryUsage> {
+    let mut merged: BTreeMap<&str, EntryUsage> = BTreeMap::new();
+    for usage in local.iter().chain(remote) {
+        merged
+            .entry(usage.entry_id.as_str())
+            .and_modify(|existing| existing.merge(usage))
+            .or_insert_with(|| usage.clone());
+    }
+    merged.into_values().collect()
+}
+
+/// Encrypts the usage records of all entries into a single blob, to be synced apart from the entries
+pub fn encrypt_usage(usage: &[EntryUsage], key: Vec<u8>) -> Result<Vec<u8>, AuthenticatorError> {
+    let key = Zeroizing::new(key);
+    let serialized = serde_json::to_vec(usage)
+        .map(Zeroizing::new)
+        .map_err(|e| AuthenticatorError::SerializationError(format!("failed to serialize entry usage: {e:?}")))?;
+
+    seal(&serialized, &key, EncryptionTag::EntryUsage)
+        .map_err(|e| AuthenticatorError::Unknown(format!("failed to encrypt entry usage: {e:?}")))
+}
+
+pub fn decrypt_usage(encrypted: &[u8], key: Vec<u8>) -> Result<Vec<EntryUsage>, AuthenticatorError> {
+    let key = Zeroizing::new(key);
+    let decrypted = open(encrypted, &key, EncryptionTag::EntryUsage)
+        .map(Zeroizing::new)
+        .map_err(|e| AuthenticatorError::Unknown(format!("failed to decrypt entry usage: {e:?}")))?;
+
+    serde_json::from_slice(&decrypted)
+        .map_err(|e| AuthenticatorError::SerializationError(format!("failed to deserialize entry usage: {e:?}")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::generate_encryption_key;
+
+    fn usage(entry_id: &str, last_used: Option<i64>, use_count: u32) -> EntryUsage {
+        EntryUsage {
+            entry_id: entry_id.to_string(),
+            last_used,
+            use_count,
+        }
+    }
+
+    #[test]
+    fn record_use_updates_time_and_count() {
+        let mut entry_usage = EntryUsage::new("a".to_string());
+        entry_usage.record_use(100);
+        entry_usage.record_use(200);
+        assert_eq!(usage("a", Some(200), 2), entry_usage);
+
+        // A clock going backwards does not move the last use back
+        entry_usage.record_use(150);
+        assert_eq!(usage("a", Some(200), 3), entry_usage);
+    }
+
+    #[test]
+    fn merge_keeps_most_recent_values() {
+        let local = vec![usage("a", Some(100), 5), usage("b", None, 0)];
+        let remote = vec![
+            usage("a", Some(200), 3),
+            usage("b", Some(50), 1),
+            usage("c", Some(10), 1),
+        ];
+
+        let merged = merge_usage(&local, &remote);
+        assert_eq!(
+            vec![
+                usage("a", Some(200), 5),
+                usage("b", Some(50), 1),
+                usage("c", Some(10), 1)
+            ],
+            merged
+        );
+    }
+
+    #[test]
+    fn can_encrypt_and_decrypt_usage() {
+        let key = generate_encryption_key();
+        let records = vec![usage("a", Some(100), 5), usage("b", None, 0)];
+
+        let encrypted = encrypt_usage(&records, key.clone()).expect("should encrypt");
+        let decrypted = decrypt_usage(&encrypted, key).expect("should decrypt");
+        assert_eq!(records, decrypted);
+
+        assert!(decrypt_usage(&encrypted, generate_encryption_key()).is_err());
+    }
+}
@@ -1,14 +1,25 @@
 use crate::{AuthenticatorClient, AuthenticatorCodeResponse, AuthenticatorEntry};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{
-    Arc,
+    Arc, Mutex, MutexGuard,
     atomic::{AtomicBool, Ordering},
 };
+use std::task::{Context, Poll, Waker};
 
 //
 // Core traits and types
 //
 pub trait GeneratorCurrentTimeProvider: Send + Sync {
+    /// Current unix time in seconds
     fn now(&self) -> u64;
+
+    /// Current unix time in milliseconds. Providers that can offer sub-second precision should override it, so the
+    /// generator wakes up right at the code boundaries instead of up to a second late.
+    fn now_ms(&self) -> u64 {
+        self.now().saturating_mul(1000)
+    }
 }
 
 // Use Arc pointers so the dependencies can be cloned into the worker thread.
@@ -33,6 +44,9 @@ pub struct TotpGenerator {
 impl TotpGenerator {
     const MAX_ERRORS: usize = 3;
 
+    /// - `only_on_code_change`: if true, the generator sleeps until the next code boundary of any entry and only emits
+    ///   the entries whose code changed. If false, all the codes are emitted every `period_ms`
+    /// - `period_ms`: interval between emissions when `only_on_code_change` is false
     pub fn new(dependencies: TotpGeneratorDependencies, only_on_code_change: bool, period_ms: u32) -> Self {
         Self {
             dependencies,
@@ -48,27 +62,34 @@ impl TotpGenerator {
         callback: impl TotpGeneratorCallback,
     ) -> TotpGenerationHandle {
         let cancelled = Arc::new(AtomicBool::new(false));
+        let shared = Arc::new(GeneratorShared::new(entries));
 
         let join_handle = {
             let cancelled_cloned = cancelled.clone();
+            let shared_cloned = shared.clone();
             let time_provider = self.dependencies.current_time_provider.clone();
             let period = self.period_ms;
             let only_on_code_change = self.only_on_code_change;
             Some(tokio::spawn(async move {
                 Self::generate_codes_loop(
-                    entries,
+                    shared_cloned,
                     only_on_code_change,
+                    period,
                     callback,
                     time_provider,
                     cancelled_cloned,
-                    || async move {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(period as u64)).await;
+                    |delay_ms| async move {
+                        tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
                     },
                 )
                 .await;
             }))
         };
-        TotpGenerationHandle { cancelled, join_handle }
+        TotpGenerationHandle {
+            cancelled,
+            shared,
+            join_handle,
+        }
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -78,99 +99,195 @@ impl TotpGenerator {
         callback: impl TotpGeneratorCallback,
     ) -> TotpGenerationHandle {
         let cancelled = Arc::new(AtomicBool::new(false));
+        let shared = Arc::new(GeneratorShared::new(entries));
         let time_provider = self.dependencies.current_time_provider.clone();
         let period = self.period_ms;
         let only_on_code_change = self.only_on_code_change;
 
         let cancelled_cloned = cancelled.clone();
+        let shared_cloned = shared.clone();
         wasm_bindgen_futures::spawn_local(async move {
             Self::generate_codes_loop(
-                entries,
+                shared_cloned,
                 only_on_code_change,
+                period,
                 callback,
                 time_provider,
                 cancelled_cloned,
-                || async move {
-                    gloo_timers::future::TimeoutFuture::new(period).await;
+                |delay_ms| async move {
+                    let delay_ms = u32::try_from(delay_ms).unwrap_or(u32::MAX);
+                    gloo_timers::future::TimeoutFuture::new(delay_ms).await;
                 },
             )
             .await;
         });
-        TotpGenerationHandle { cancelled }
+        TotpGenerationHandle { cancelled, shared }
     }
 
     async fn generate_codes_loop<Fut>(
-        entries: Vec<AuthenticatorEntry>,
+        shared: Arc<GeneratorShared>,
         only_on_code_change: bool,
+        period_ms: u32,
         callback: impl TotpGeneratorCallback,
         time_provider: Arc<dyn GeneratorCurrentTimeProvider>,
         cancelled: Arc<AtomicBool>,
-        delayer: impl Fn() -> Fut,
+        delayer: impl Fn(u64) -> Fut,
     ) where
-        Fut: std::future::Future<Output = ()>,
+        Fut: Future<Output = ()>,
     {
         let client = AuthenticatorClient;
         let prefix = "[TOTP_GENERATOR]";
         let mut error_count = 0;
-        let mut has_generated = false;
         info!("{prefix} Started TOTP generator");
 
-        let mut last_state: Option<String> = None;
+        // Last emitted code of each entry
+        let mut last_codes: HashMap<String, String> = HashMap::new();
         while !cancelled.load(Ordering::Relaxed) {
-            let now = time_provider.now();
-            debug!("{prefix} Got now {now}");
-            let should_generate = if !has_generated {
-                true
-            } else if only_on_code_change {
-                entries.iter().any(|e| now.is_multiple_of(e.period() as u64))
-            } else {
-                true
-            };
-            if should_generate {
-                trace!("{prefix} Generating codes");
-                match client.generate_codes(&entries, now) {
-                    Ok(codes) => {
-                        let should_invoke = if only_on_code_change {
-                            let codes_as_str = codes
-                                .iter()
-                                .map(|c| c.current_code.as_str())
-                                .collect::<Vec<&str>>()
-                                .join("");
-                            let res = match last_state {
-                                Some(ref last) => codes_as_str != last.as_str(),
-                                None => true,
-                            };
-                            last_state = Some(codes_as_str);
-                            res
-                        } else {
-                            true
-                        };
-
-                        if should_invoke {
-                            trace!("{prefix} Got codes size={}", codes.len());
-                            callback.on_codes(codes);
-                        } else {
-                            trace!("{prefix} Callback not invoked");
-                        }
+            let entries = shared.entries().clone();
+            let now_ms = time_provider.now_ms();
+            let now = now_ms / 1000;
+            debug!("{prefix} Got now {now_ms}");
+
+            trace!("{prefix} Generating codes");
+            match client.generate_codes(&entries, now) {
+                Ok(codes) => {
+                    let codes = if only_on_code_change {
+                        last_codes.retain(|id, _| entries.iter().any(|e| &e.id == id));
+                        changed_codes(&mut last_codes, codes)
+                    } else {
+                        codes
+                    };
+
+                    if only_on_code_change && codes.is_empty() {
+                        trace!("{prefix} Callback not invoked");
+                    } else {
+                        trace!("{prefix} Got codes size={}", codes.len());
+                        callback.on_codes(codes);
                     }
-                    Err(e) => {
-                        warn!("{prefix} Failed to generate codes: {e}");
-                        error_count += 1;
-                        if error_count > Self::MAX_ERRORS {
-                            break;
-                        }
+                }
+                Err(e) => {
+                    warn!("{prefix} Failed to generate codes: {e}");
+                    error_count += 1;
+                    if error_count > Self::MAX_ERRORS {
+                        break;
                     }
                 }
-                has_generated = true;
             }
 
-            delayer().await
+            let delay_ms = if only_on_code_change {
+                delay_until_code_change(&entries, now_ms)
+            } else {
+                Some(period_ms as u64)
+            };
+            trace!("{prefix} Sleeping for {delay_ms:?} ms");
+            SleepOrWakeup {
+                sleep: delay_ms.map(|delay_ms| Box::pin(delayer(delay_ms))),
+                shared: &shared,
+            }
+            .await;
+        }
+    }
+}
+
+/// Keeps the codes that differ from the last emitted ones, and records them as emitted
+fn changed_codes(
+    last_codes: &mut HashMap<String, String>,
+    codes: Vec<AuthenticatorCodeResponse>,
+) -> Vec<AuthenticatorCodeResponse> {
+    codes
+        .into_iter()
+        .filter(|code| {
+            let previous = last_codes.insert(code.entry.id.clone(), code.current_code.clone());
+            previous.as_deref() != Some(code.current_code.as_str())
+        })
+        .collect()
+}
+
+/// Unix time in seconds of the next code change of any of the entries
+fn next_code_change(entries: &[AuthenticatorEntry], now: u64) -> Option<u64> {
+    entries
+        .iter()
+        .map(|entry| {
+            let period = entry.period().max(1) as u64;
+            (now / period + 1) * period
+        })
+        .min()
+}
+
+/// Milliseconds from `now_ms` until the next code change of any of the entries
+fn delay_until_code_change(entries: &[AuthenticatorEntry], now_ms: u64) -> Option<u64> {
+    next_code_change(entries, now_ms / 1000).map(|boundary| boundary.saturating_mul(1000).saturating_sub(now_ms))
+}
+
+/// State shared between the generation loop and its handle
+struct GeneratorShared {
+    entries: Mutex<Vec<AuthenticatorEntry>>,
+    wakeup: Mutex<Wakeup>,
+}
+
+#[derive(Default)]
+struct Wakeup {
+    pending: bool,
+    waker: Option<Waker>,
+}
+
+impl GeneratorShared {
+    fn new(entries: Vec<AuthenticatorEntry>) -> Self {
+        Self {
+            entries: Mutex::new(entries),
+            wakeup: Mutex::new(Wakeup::default()),
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Vec<AuthenticatorEntry>> {
+        // A panic while holding the lock cannot leave the list in an inconsistent state
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn wakeup(&self) -> MutexGuard<'_, Wakeup> {
+        self.wakeup.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Interrupts the current sleep of the generation loop
+    fn wake(&self) {
+        let mut wakeup = self.wakeup();
+        wakeup.pending = true;
+        if let Some(waker) = wakeup.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Completes when the sleep finishes or the loop is woken up, whatever happens first.
+/// Without a sleep, it waits until the loop is woken up.
+struct SleepOrWakeup<'a, Fut> {
+    sleep: Option<Pin<Box<Fut>>>,
+    shared: &'a GeneratorShared,
+}
+
+impl<Fut: Future<Output = ()>> Future for SleepOrWakeup<'_, Fut> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        {
+            let mut wakeup = self.shared.wakeup();
+            if wakeup.pending {
+                wakeup.pending = false;
+                return Poll::Ready(());
+            }
+            wakeup.waker = Some(cx.waker().clone());
+        }
+
+        match self.sleep.as_mut() {
+            Some(sleep) => sleep.as_mut().poll(cx),
+            None => Poll::Pending,
         }
     }
 }
 
 pub struct TotpGenerationHandle {
     cancelled: Arc<AtomicBool>,
+    shared: Arc<GeneratorShared>,
     #[cfg(not(target_arch = "wasm32"))]
     join_handle: Option<tokio::task::JoinHandle<()>>,
 }
@@ -180,11 +297,39 @@ impl TotpGenerationHandle {
     pub fn cancel(&mut self) {
         info!("[TOTP_GENERATOR] Canceled");
         self.cancelled.store(true, Ordering::Relaxed);
+        self.shared.wake();
         #[cfg(not(target_arch = "wasm32"))]
         if let Some(ref handle) = self.join_handle {
             handle.abort();
         }
     }
+
+    /// Adds entries to the running generator. Entries with the id of an existing one replace it.
+    /// The codes of the new entries are emitted right away.
+    pub fn add_entries(&self, entries: Vec<AuthenticatorEntry>) {
+        {
+            let mut current = self.shared.entries();
+            for entry in entries {
+                match current.iter_mut().find(|e| e.id == entry.id) {
+                    Some(existing) => *existing = entry,
+                    None => current.push(entry),
+                }
+            }
+        }
+        self.shared.wake();
+    }
+
+    /// Removes the entries with the given ids from the running generator
+    pub fn remove_entries(&self, entry_ids: &[String]) {
+        self.shared.entries().retain(|e| !entry_ids.contains(&e.id));
+        self.shared.wake();
+    }
+
+    /// Replaces all the entries of the running generator
+    pub fn set_entries(&self, entries: Vec<AuthenticatorEntry>) {
+        *self.shared.entries() = entries;
+        self.shared.wake();
+    }
 }
 
 #[cfg(test)]
@@ -313,42 +458,70 @@ mod tests {
         assert!(handle_err.is_cancelled());
     }
 
-    #[tokio::test]
-    async fn can_generate_codes_only_on_code_change() {
-        let entry =
-            get_entry("otpauth://totp/MYLABEL?secret=MYSECRET&issuer=MYISSUER&algorithm=SHA256&digits=8&period=15");
+    /// Real clock shifted so that the generator starts at `start_ms`
+    struct ShiftedTimeProvider {
+        started: std::time::Instant,
+        start_ms: u64,
+    }
 
-        let times_to_return = vec![
-            1741764120, // Generate
-            1741764121, // Don't generate
-            1741764122, // Don't generate
-            1741890120, // Generate
-        ];
-        let times = times_to_return.len();
-        let current_time_provider = TestCurrentTimeProvider::new(times_to_return);
+    impl ShiftedTimeProvider {
+        pub fn new(start_ms: u64) -> Self {
+            Self {
+                started: std::time::Instant::now(),
+                start_ms,
+            }
+        }
+    }
 
-        let period = 10;
+    impl GeneratorCurrentTimeProvider for ShiftedTimeProvider {
+        fn now(&self) -> u64 {
+            self.now_ms() / 1000
+        }
+
+        fn now_ms(&self) -> u64 {
+            self.start_ms + self.started.elapsed().as_millis() as u64
+        }
+    }
+
+    fn start_on_code_change(start_ms: u64) -> TotpGenerator {
         let dependencies = TotpGeneratorDependencies {
-            current_time_provider: Arc::new(current_time_provider),
+            current_time_provider: Arc::new(ShiftedTimeProvider::new(start_ms)),
         };
-        let generator = TotpGenerator::new(dependencies, true, period);
+        TotpGenerator::new(dependencies, true, 10)
+    }
 
-        let accumulator_callback = Arc::new(TestTotpGeneratorCallbackAccumulator::new());
+    async fn sleep_ms(ms: u64) {
+        tokio::time::sleep(tokio::time::Duration::from_millis(ms)).await;
+    }
 
-        let accumulator_clone = accumulator_callback.clone();
-        let mut handle = generator.start_async(vec![entry], accumulator_clone).await;
+    #[tokio::test]
+    async fn can_generate_codes_only_on_code_change() {
+        let entry15 =
+            get_entry("otpauth://totp/MYLABEL?secret=MYSECRET&issuer=MYISSUER&algorithm=SHA256&digits=8&period=15");
+        let entry30 =
+            get_entry("otpauth://totp/MYLABEL?secret=MYSECRET123&issuer=MYISSUER&algorithm=SHA256&digits=8&period=30");
 
-        // Proceed to run all 4 ticks
-        tokio::time::sleep(tokio::time::Duration::from_millis((period * times as u32) as u64)).await;
+        // 1741764135 is a boundary for the 15s period, but not for the 30s one
+        let generator = start_on_code_change(1_741_764_135_000 - 200);
+        let accumulator_callback = Arc::new(TestTotpGeneratorCallbackAccumulator::new());
+        let mut handle = generator
+            .start_async(vec![entry15.clone(), entry30.clone()], accumulator_callback.clone())
+            .await;
 
-        // Should have generated only 2
+        sleep_ms(100).await;
         let accumulated_ref = accumulator_callback.get_values();
-        assert_eq!(2, accumulated_ref.len());
-
+        assert_eq!(1, accumulated_ref.len());
+        assert_eq!(2, accumulated_ref[0].len());
         assert_eq!("55894277", accumulated_ref[0][0].current_code);
         assert_eq!("32755418", accumulated_ref[0][0].next_code);
-        assert_eq!("07871325", accumulated_ref[1][0].current_code);
-        assert_eq!("49179669", accumulated_ref[1][0].next_code);
+
+        // Wakes up at the boundary and only emits the entry whose code changed
+        sleep_ms(400).await;
+        let accumulated_ref = accumulator_callback.get_values();
+        assert_eq!(2, accumulated_ref.len());
+        assert_eq!(1, accumulated_ref[1].len());
+        assert_eq!(entry15.id, accumulated_ref[1][0].entry.id);
+        assert_eq!("32755418", accumulated_ref[1][0].current_code);
 
         handle.cancel();
         let handle_err = handle
@@ -358,4 +531,88 @@ mod tests {
             .expect_err("should have an error (Cancelled)");
         assert!(handle_err.is_cancelled());
     }
+
+    #[tokio::test]
+    async fn can_add_and_remove_entries_while_running() {
+        let entry1 =
+            get_entry("otpauth://totp/MYLABEL?secret=MYSECRET&issuer=MYISSUER&algorithm=SHA256&digits=8&period=15");
+        let entry2 =
+            get_entry("otpauth://totp/MYLABEL?secret=MYSECRET123&issuer=MYISSUER&algorithm=SHA256&digits=8&period=15");
+
+        let generator = start_on_code_change(1_741_764_120_000);
+        let accumulator_callback = Arc::new(TestTotpGeneratorCallbackAccumulator::new());
+        let mut handle = generator.start_async(vec![], accumulator_callback.clone()).await;
+
+        // Nothing to emit without entries
+        sleep_ms(50).await;
+        assert!(accumulator_callback.get_values().is_empty());
+
+        // Only the new entry is emitted
+        handle.add_entries(vec![entry1.clone()]);
+        sleep_ms(50).await;
+        handle.add_entries(vec![entry2.clone()]);
+        sleep_ms(50).await;
+        let accumulated_ref = accumulator_callback.get_values();
+        assert_eq!(2, accumulated_ref.len());
+        assert_eq!(entry1.id, accumulated_ref[0][0].entry.id);
+        assert_eq!(1, accumulated_ref[1].len());
+        assert_eq!(entry2.id, accumulated_ref[1][0].entry.id);
+        assert_eq!("03271278", accumulated_ref[1][0].current_code);
+
+        // Removed entries are not emitted, and are emitted again if added back
+        handle.remove_entries(std::slice::from_ref(&entry1.id));
+        sleep_ms(50).await;
+        assert_eq!(2, accumulator_callback.get_values().len());
+        handle.add_entries(vec![entry1.clone()]);
+        sleep_ms(50).await;
+        let accumulated_ref = accumulator_callback.get_values();
+        assert_eq!(3, accumulated_ref.len());
+        assert_eq!(entry1.id, accumulated_ref[2][0].entry.id);
+
+        handle.cancel();
+        sleep_ms(50).await;
+        assert!(handle.join_handle.unwrap().await.is_err());
+    }
+
+    #[test]
+    fn next_code_change_is_the_closest_boundary() {
+        let entry15 = get_entry("otpauth://totp/MYLABEL?secret=MYSECRET&period=15");
+        let entry30 = get_entry("otpauth://totp/MYLABEL?secret=MYSECRET&period=30");
+
+        assert_eq!(None, next_code_change(&[], 100));
+        assert_eq!(Some(120), next_code_change(std::slice::from_ref(&entry30), 100));
+        assert_eq!(Some(105), next_code_change(&[entry30.clone(), entry15.clone()], 100));
+        // Exactly on a boundary, the next one is a full period later
+        assert_eq!(Some(135), next_code_change(&[entry15], 120));
+    }
+
+    #[test]
+    fn delay_until_code_change_keeps_sub_second_precision() {
+        let entry15 = get_entry("otpauth://totp/MYLABEL?secret=MYSECRET&period=15");
+        let entries = std::slice::from_ref(&entry15);
+
+        assert_eq!(None, delay_until_code_change(&[], 1_741_764_134_750));
+        assert_eq!(Some(250), delay_until_code_change(entries, 1_741_764_134_750));
+        assert_eq!(Some(15_000), delay_until_code_change(entries, 1_741_764_135_000));
+        assert_eq!(Some(14_999), delay_until_code_change(entries, 1_741_764_135_001));
+    }
+
+    #[tokio::test]
+    async fn wakes_up_at_the_code_change_of_a_sub_second_clock() {
+        let entry15 = get_entry("otpauth://totp/MYLABEL?secret=MYSECRET&period=15");
+
+        // 250ms before the boundary: relying on the time in seconds would wake up 750ms late
+        let generator = start_on_code_change(1_741_764_134_750);
+        let accumulator_callback = Arc::new(TestTotpGeneratorCallbackAccumulator::new());
+        let mut handle = generator.start_async(vec![entry15], accumulator_callback.clone()).await;
+
+        sleep_ms(100).await;
+        assert_eq!(1, accumulator_callback.get_values().len());
+        sleep_ms(300).await;
+        assert_eq!(2, accumulator_callback.get_values().len());
+
+        handle.cancel();
+        sleep_ms(50).await;
+        assert!(handle.join_handle.unwrap().await.is_err());
+    }
 }
@@ -0,0 +1,264 @@
+use crate::issuer_mapper::ISSUER_FILE;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const ICON_BUNDLE_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const ICONS_DIR: &str = "icons/";
+
+#[derive(Clone, Debug, proton_pass_derive::Error)]
+pub enum IconBundleError {
+    BadContent(String),
+    UnsupportedVersion(u32),
+    /// An icon does not match the hash it is addressed by
+    Corrupted(String),
+    MissingIcon(String),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct IconBundleEntry {
+    pub name: String,
+    pub domain: String,
+    /// Hex encoded SHA-256 of the icon
+    pub hash: String,
+}
+
+/// Describes the contents of a bundle. The icon fetcher can also write it to a separate file, set with
+/// `icon_manifest_file` in its config, so changes to the bundle can be reviewed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct IconBundleManifest {
+    pub version: u32,
+    /// Hex encoded SHA-256 of the `issuerInfos.txt` the bundle was generated from
+    pub issuer_infos_hash: String,
+    pub mime_type: String,
+    pub icons: Vec<IconBundleEntry>,
+}
+
+impl IconBundleManifest {
+    /// Whether the bundle was generated from the issuer list this library was built with. Older bundles can still
+    /// be used, but may lack the icons of newer issuers.
+    pub fn is_current(&self) -> bool {
+        self.issuer_infos_hash == hash_hex(ISSUER_FILE.as_bytes())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IssuerIcon {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Issuer icons served from a local bundle, so loading them does not disclose which services the user has 2FA on to
+/// third party favicon services.
+pub struct IconBundle {
+    manifest: IconBundleManifest,
+    by_domain: HashMap<String, String>,
+    icons: HashMap<String, Vec<u8>>,
+}
+
+impl IconBundle {
+    pub fn from_bytes(bundle: &[u8]) -> Result<Self, IconBundleError> {
+        let mut archive = ZipArchive::new(Cursor::new(bundle))
+            .map_err(|e| IconBundleError::BadContent(format!("cannot open bundle: {e:?}")))?;
+
+        let manifest: IconBundleManifest = {
+            let file = archive
+                .by_name(MANIFEST_FILE)
+                .map_err(|_| IconBundleError::BadContent("missing manifest".to_string()))?;
+            serde_json::from_reader(file)
+                .map_err(|e| IconBundleError::BadContent(format!("invalid manifest: {e:?}")))?
+        };
+        if manifest.version != ICON_BUNDLE_VERSION {
+            return Err(IconBundleError::UnsupportedVersion(manifest.version));
+        }
+
+        let mut icons = HashMap::new();
+        let mut by_domain = HashMap::new();
+        for entry in &manifest.icons {
+            if !icons.contains_key(&entry.hash) {
+                let mut file = archive
+                    .by_name(&icon_path(&entry.hash))
+                    .map_err(|_| IconBundleError::MissingIcon(entry.hash.clone()))?;
+                let mut data = Vec::new();
+                file.read_to_end(&mut data)
+                    .map_err(|e| IconBundleError::BadContent(format!("cannot read icon {}: {e:?}", entry.hash)))?;
+                if hash_hex(&data) != entry.hash {
+                    return Err(IconBundleError::Corrupted(entry.hash.clone()));
+                }
+                icons.insert(entry.hash.clone(), data);
+            }
+            by_domain
+                .entry(entry.domain.to_lowercase())
+                .or_insert_with(|| entry.hash.clone());
+        }
+
+        Ok(Self {
+            manifest,
+            by_domain,
+            icons,
+        })
+    }
+
+    pub fn manifest(&self) -> &IconBundleManifest {
+        &self.manifest
+    }
+
+    pub fn icon_for_domain(&self, domain: &str) -> Option<IssuerIcon> {
+        let hash = self.by_domain.get(&domain.to_lowercase())?;
+        self.icons.get(hash).map(|data| IssuerIcon {
+            mime_type: self.manifest.mime_type.clone(),
+            data: data.clone(),
+        })
+    }
+}
+
+/// Builds an icon bundle. Icons are stored once per content, so issuers sharing an icon don't grow the bundle.
+pub struct IconBundleBuilder {
+    issuer_infos_hash: String,
+    mime_type: String,
+    entries: Vec<IconBundleEntry>,
+    icons: BTreeMap<String, Vec<u8>>,
+}
+
+impl IconBundleBuilder {
+    /// - issuer_infos: contents of the `issuerInfos.txt` the icons were fetched for
+    /// - mime_type: format of all the icons in the bundle
+    pub fn new(issuer_infos: &str, mime_type: &str) -> Self {
+        Self {
+            issuer_infos_hash: hash_hex(issuer_infos.as_bytes()),
+            mime_type: mime_type.to_string(),
+            entries: Vec::new(),
+            icons: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the icon of an issuer, returning its hash
+    pub fn add(&mut self, name: &str, domain: &str, icon: Vec<u8>) -> String {
+        let hash = hash_hex(&icon);
+        self.icons.entry(hash.clone()).or_insert(icon);
+        self.entries.push(IconBundleEntry {
+            name: name.to_string(),
+            domain: domain.to_lowercase(),
+            hash: hash.clone(),
+        });
+        hash
+    }
+
+    pub fn manifest(&self) -> IconBundleManifest {
+        let mut icons = self.entries.clone();
+        icons.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.domain.cmp(&b.domain)));
+        IconBundleManifest {
+            version: ICON_BUNDLE_VERSION,
+            issuer_infos_hash: self.issuer_infos_hash.clone(),
+            mime_type: self.mime_type.clone(),
+            icons,
+        }
+    }
+
+    pub fn build(&self) -> Result<Vec<u8>, IconBundleError> {
+        let manifest = serde_json::to_vec_pretty(&self.manifest())
+            .map_err(|e| IconBundleError::BadContent(format!("cannot serialize manifest: {e:?}")))?;
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file(MANIFEST_FILE, SimpleFileOptions::default())
+            .map_err(write_error)?;
+        writer.write_all(&manifest).map_err(write_error)?;
+
+        // Icons are already compressed
+        let icon_options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        for (hash, icon) in &self.icons {
+            writer.start_file(icon_path(hash), icon_options).map_err(write_error)?;
+            writer.write_all(icon).map_err(write_error)?;
+        }
+
+        writer.finish().map(Cursor::into_inner).map_err(write_error)
+    }
+}
+
+fn icon_path(hash: &str) -> String {
+    format!("{ICONS_DIR}{hash}")
+}
+
+fn write_error(e: impl std::fmt::Debug) -> IconBundleError {
+    IconBundleError::BadContent(format!("cannot write bundle: {e:?}"))
+}
+
+fn hash_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIME_TYPE: &str = "image/webp";
+
+    fn build_bundle() -> Vec<u8> {
+        let mut builder = IconBundleBuilder::new(ISSUER_FILE, MIME_TYPE);
+        builder.add("google", "google.com", vec![1, 2, 3]);
+        builder.add("gmail", "Gmail.com", vec![1, 2, 3]);
+        builder.add("github", "github.com", vec![4, 5, 6]);
+        builder.build().expect("should build")
+    }
+
+    #[test]
+    fn can_build_and_read_bundle() {
+        let bundle = IconBundle::from_bytes(&build_bundle()).expect("should read");
+
+        let manifest = bundle.manifest();
+        assert!(manifest.is_current());
+        let names: Vec<&str> = manifest.icons.iter().map(|icon| icon.name.as_str()).collect();
+        assert_eq!(vec!["github", "gmail", "google"], names);
+
+        let google = bundle.icon_for_domain("google.com").expect("should have icon");
+        assert_eq!(MIME_TYPE, google.mime_type);
+        assert_eq!(vec![1, 2, 3], google.data);
+        assert_eq!(Some(google), bundle.icon_for_domain("gmail.com"));
+        assert_eq!(vec![4, 5, 6], bundle.icon_for_domain("GitHub.com").unwrap().data);
+        assert!(bundle.icon_for_domain("unknown.com").is_none());
+    }
+
+    #[test]
+    fn stores_each_icon_once() {
+        let mut builder = IconBundleBuilder::new("", MIME_TYPE);
+        let first = builder.add("a", "a.com", vec![1; 64]);
+        let second = builder.add("b", "b.com", vec![1; 64]);
+        assert_eq!(first, second);
+
+        let bundle = builder.build().expect("should build");
+        let archive = ZipArchive::new(Cursor::new(bundle)).expect("should open");
+        assert_eq!(2, archive.len());
+        assert!(!builder.manifest().is_current());
+    }
+
+    #[test]
+    fn detects_corrupted_icons() {
+        let mut builder = IconBundleBuilder::new(ISSUER_FILE, MIME_TYPE);
+        builder.add("google", "google.com", vec![1, 2, 3]);
+        let mut manifest = builder.manifest();
+        manifest.icons[0].hash = hash_hex(&[7, 8, 9]);
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file(MANIFEST_FILE, SimpleFileOptions::default()).unwrap();
+        writer.write_all(&serde_json::to_vec(&manifest).unwrap()).unwrap();
+        writer
+            .start_file(icon_path(&manifest.icons[0].hash), SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&[1, 2, 3]).unwrap();
+        let bundle = writer.finish().unwrap().into_inner();
+
+        assert!(matches!(
+            IconBundle::from_bytes(&bundle),
+            Err(IconBundleError::Corrupted(_))
+        ));
+        assert!(matches!(
+            IconBundle::from_bytes(b"not a bundle"),
+            Err(IconBundleError::BadContent(_))
+        ));
+    }
+}
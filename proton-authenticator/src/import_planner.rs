@@ -0,0 +1,502 @@
+use crate::entry::normalize_tags;
+use crate::parser::{ImportError, ImportResult};
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImportConflictField {
+    Type,
+    Name,
+    Issuer,
+    Note,
+    Algorithm,
+    Digits,
+    Period,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ImportConflictResolution {
+    #[default]
+    KeepExisting,
+    KeepImported,
+    KeepBoth,
+}
+
+/// Imported entry that is already present with the same contents
+#[derive(Clone, Debug)]
+pub struct ImportDuplicate {
+    pub existing_id: String,
+    pub imported: AuthenticatorEntry,
+}
+
+/// Imported entry sharing the secret of an existing one, but with different information
+#[derive(Clone, Debug)]
+pub struct ImportConflict {
+    pub existing: AuthenticatorEntry,
+    pub imported: AuthenticatorEntry,
+    pub fields: Vec<ImportConflictField>,
+}
+
+/// Imported entry sharing the secret of an existing one, which can fill in its missing information
+#[derive(Clone, Debug)]
+pub struct ImportMerge {
+    pub existing: AuthenticatorEntry,
+    pub imported: AuthenticatorEntry,
+    /// Existing entry with the missing information taken from the imported one
+    pub merged: AuthenticatorEntry,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ImportPreview {
+    pub new_entries: Vec<AuthenticatorEntry>,
+    pub duplicates: Vec<ImportDuplicate>,
+    pub conflicts: Vec<ImportConflict>,
+    pub merges: Vec<ImportMerge>,
+    pub errors: Vec<ImportError>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ImportDecisions {
+    pub apply_merges: bool,
+    /// Resolution for each conflict, keyed by the imported entry id. Conflicts without a resolution
+    /// keep the existing entry.
+    pub conflict_resolutions: HashMap<String, ImportConflictResolution>,
+}
+
+enum EntryComparison {
+    Identical,
+    Mergeable(AuthenticatorEntry),
+    Conflicting(Vec<ImportConflictField>),
+}
+
+/// Compares the imported entries against the existing ones, matching them by secret.
+/// Entries repeated within the import itself are also detected.
+pub fn plan_import(existing: &[AuthenticatorEntry], import: ImportResult) -> ImportPreview {
+    let mut preview = ImportPreview {
+        errors: import.errors,
+        ..Default::default()
+    };
+
+    let mut existing_by_secret: HashMap<Vec<u8>, &AuthenticatorEntry> = HashMap::new();
+    for entry in existing {
+        existing_by_secret.entry(secret_key(entry)).or_insert(entry);
+    }
+    // Index into `preview.new_entries`
+    let mut new_by_secret: HashMap<Vec<u8>, usize> = HashMap::new();
+
+    for imported in import.entries {
+        let key = secret_key(&imported);
+
+        if let Some(existing) = existing_by_secret.get(&key) {
+            match compare_entries(existing, &imported) {
+                EntryComparison::Identical => preview.duplicates.push(ImportDuplicate {
+                    existing_id: existing.id.clone(),
+                    imported,
+                }),
+                EntryComparison::Mergeable(merged) => preview.merges.push(ImportMerge {
+                    existing: (*existing).clone(),
+                    imported,
+                    merged,
+                }),
+                EntryComparison::Conflicting(fields) => preview.conflicts.push(ImportConflict {
+                    existing: (*existing).clone(),
+                    imported,
+                    fields,
+                }),
+            }
+            continue;
+        }
+
+        if let Some(idx) = new_by_secret.get(&key) {
+            let previous = &preview.new_entries[*idx];
+            match compare_entries(previous, &imported) {
+                EntryComparison::Identical => preview.duplicates.push(ImportDuplicate {
+                    existing_id: previous.id.clone(),
+                    imported,
+                }),
+                // Both entries come from the import, so there is no need to ask for confirmation
+                EntryComparison::Mergeable(merged) => preview.new_entries[*idx] = merged,
+                EntryComparison::Conflicting(fields) => preview.conflicts.push(ImportConflict {
+                    existing: previous.clone(),
+                    imported,
+                    fields,
+                }),
+            }
+            continue;
+        }
+
+        new_by_secret.insert(key, preview.new_entries.len());
+        preview.new_entries.push(imported);
+    }
+
+    preview
+}
+
+/// Produces the list of entries to persist: the existing ones with the accepted merges and conflict
+/// resolutions applied, followed by the new entries.
+pub fn apply_import(
+    existing: Vec<AuthenticatorEntry>,
+    preview: ImportPreview,
+    decisions: &ImportDecisions,
+) -> Vec<AuthenticatorEntry> {
+    let mut result = existing;
+    result.extend(preview.new_entries);
+
+    if decisions.apply_merges {
+        for merge in preview.merges {
+            replace_entry(&mut result, &merge.existing.id, merge.merged);
+        }
+    }
+
+    for conflict in preview.conflicts {
+        let resolution = decisions
+            .conflict_resolutions
+            .get(&conflict.imported.id)
+            .copied()
+            .unwrap_or_default();
+        match resolution {
+            ImportConflictResolution::KeepExisting => {}
+            ImportConflictResolution::KeepImported => {
+                let replacement = AuthenticatorEntry {
+                    id: conflict.existing.id.clone(),
+                    content: conflict.imported.content.clone(),
+                    note: conflict.imported.note.clone(),
+                    tags: normalize_tags(conflict.existing.tags.iter().chain(&conflict.imported.tags).cloned()),
+                    favourite: conflict.existing.favourite,
+                };
+                replace_entry(&mut result, &conflict.existing.id, replacement);
+            }
+            ImportConflictResolution::KeepBoth => result.push(conflict.imported),
+        }
+    }
+
+    result
+}
+
+fn replace_entry(entries: &mut [AuthenticatorEntry], id: &str, replacement: AuthenticatorEntry) {
+    if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+        *entry = replacement;
+    }
+}
+
+fn secret_key(entry: &AuthenticatorEntry) -> Vec<u8> {
+    match &entry.content {
+        AuthenticatorEntryContent::Totp(totp) => {
+            let normalized: String = totp
+                .secret
+                .chars()
+                .filter(|c| !c.is_whitespace() && *c != '=' && *c != '-')
+                .collect::<String>()
+                .to_uppercase();
+            base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &normalized)
+                .unwrap_or_else(|| normalized.into_bytes())
+        }
+        AuthenticatorEntryContent::Steam(steam) => steam.secret.clone(),
+    }
+}
+
+fn compare_entries(existing: &AuthenticatorEntry, imported: &AuthenticatorEntry) -> EntryComparison {
+    let mut conflicts = Vec::new();
+
+    let existing_is_steam = is_steam(existing);
+    if existing_is_steam != is_steam(imported) {
+        conflicts.push(ImportConflictField::Type);
+    } else if let (Ok(existing_params), Ok(imported_params)) =
+        (existing.get_totp_parameters(), imported.get_totp_parameters())
+    {
+        if existing_params.algorithm != imported_params.algorithm {
+            conflicts.push(ImportConflictField::Algorithm);
+        }
+        if existing_params.digits != imported_params.digits {
+            conflicts.push(ImportConflictField::Digits);
+        }
+        if existing_params.period != imported_params.period {
+            conflicts.push(ImportConflictField::Period);
+        }
+    }
+
+    let mut merged = existing.clone();
+    let mut missing_fields = false;
+
+    match compare_field(&existing.name(), &imported.name()) {
+        FieldComparison::Equal => {}
+        FieldComparison::Missing(name) => {
+            missing_fields = true;
+            set_name(&mut merged, name);
+        }
+        FieldComparison::Different => conflicts.push(ImportConflictField::Name),
+    }
+
+    // Steam entries always have the same issuer
+    if !existing_is_steam {
+        match compare_field(&existing.issuer(), &imported.issuer()) {
+            FieldComparison::Equal => {}
+            FieldComparison::Missing(issuer) => {
+                missing_fields = true;
+                if let AuthenticatorEntryContent::Totp(totp) = &mut merged.content {
+                    totp.issuer = Some(issuer);
+                }
+            }
+            FieldComparison::Different => conflicts.push(ImportConflictField::Issuer),
+        }
+    }
+
+    match compare_field(
+        existing.note.as_deref().unwrap_or_default(),
+        imported.note.as_deref().unwrap_or_default(),
+    ) {
+        FieldComparison::Equal => {}
+        FieldComparison::Missing(note) => {
+            missing_fields = true;
+            merged.note = Some(note);
+        }
+        FieldComparison::Different => conflicts.push(ImportConflictField::Note),
+    }
+
+    // Tags never conflict, the ones missing in the existing entry are added
+    if imported.tags.iter().any(|tag| !existing.tags.contains(tag)) {
+        missing_fields = true;
+        merged.tags = normalize_tags(existing.tags.iter().chain(&imported.tags).cloned());
+    }
+
+    if !conflicts.is_empty() {
+        EntryComparison::Conflicting(conflicts)
+    } else if missing_fields {
+        EntryComparison::Mergeable(merged)
+    } else {
+        EntryComparison::Identical
+    }
+}
+
+enum FieldComparison {
+    Equal,
+    /// The field is empty in the existing entry but present in the imported one
+    Missing(String),
+    Different,
+}
+
+fn compare_field(existing: &str, imported: &str) -> FieldComparison {
+    let existing = existing.trim();
+    let imported = imported.trim();
+    if existing == imported || imported.is_empty() {
+        FieldComparison::Equal
+    } else if existing.is_empty() {
+        FieldComparison::Missing(imported.to_string())
+    } else {
+        FieldComparison::Different
+    }
+}
+
+fn is_steam(entry: &AuthenticatorEntry) -> bool {
+    matches!(entry.content, AuthenticatorEntryContent::Steam(_))
+}
+
+fn set_name(entry: &mut AuthenticatorEntry, name: String) {
+    match &mut entry.content {
+        AuthenticatorEntryContent::Totp(totp) => totp.label = Some(name),
+        AuthenticatorEntryContent::Steam(steam) => steam.set_name(Some(name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::SteamTotp;
+
+    fn entry(uri: &str, note: Option<&str>) -> AuthenticatorEntry {
+        AuthenticatorEntry::from_uri(uri, note.map(|n| n.to_string())).expect("should be able to create")
+    }
+
+    fn steam(secret: &str, name: &str) -> AuthenticatorEntry {
+        let mut steam = SteamTotp::new(secret).expect("should be able to create");
+        steam.set_name(Some(name.to_string()));
+        AuthenticatorEntry {
+            id: AuthenticatorEntry::generate_id(),
+            content: AuthenticatorEntryContent::Steam(steam),
+            note: None,
+            tags: Vec::new(),
+            favourite: false,
+        }
+    }
+
+    fn import(entries: Vec<AuthenticatorEntry>) -> ImportResult {
+        ImportResult {
+            entries,
+            errors: vec![],
+        }
+    }
+
+    const PROTON: &str = "otpauth://totp/Proton:user%40proton.me?secret=JBSWY3DPEHPK3PXP&issuer=Proton";
+
+    #[test]
+    fn detects_new_entries() {
+        let existing = vec![entry(PROTON, None)];
+        let imported = entry("otpauth://totp/Other:user?secret=GEZDGNBVGY3TQOJQ&issuer=Other", None);
+
+        let preview = plan_import(&existing, import(vec![imported.clone()]));
+        assert_eq!(preview.new_entries.len(), 1);
+        assert_eq!(preview.new_entries[0].id, imported.id);
+        assert!(preview.duplicates.is_empty());
+        assert!(preview.conflicts.is_empty());
+        assert!(preview.merges.is_empty());
+    }
+
+    #[test]
+    fn detects_exact_duplicates() {
+        let existing = vec![entry(PROTON, Some("note"))];
+        // Same secret with a different format and no note is still a duplicate
+        let imported = entry(
+            "otpauth://totp/Proton:user%40proton.me?secret=jbsw%20y3dp%20ehpk%203pxp&issuer=Proton",
+            None,
+        );
+
+        let preview = plan_import(&existing, import(vec![imported]));
+        assert!(preview.new_entries.is_empty());
+        assert_eq!(preview.duplicates.len(), 1);
+        assert_eq!(preview.duplicates[0].existing_id, existing[0].id);
+    }
+
+    #[test]
+    fn detects_duplicates_within_import() {
+        let first = entry(PROTON, None);
+        let second = entry(PROTON, Some("A note"));
+
+        let preview = plan_import(&[], import(vec![first.clone(), second.clone(), second]));
+        assert_eq!(preview.new_entries.len(), 1);
+        assert_eq!(preview.new_entries[0].id, first.id);
+        assert_eq!(preview.new_entries[0].note, Some("A note".to_string()));
+        assert_eq!(preview.duplicates.len(), 1);
+        assert_eq!(preview.duplicates[0].existing_id, first.id);
+    }
+
+    #[test]
+    fn detects_conflicts() {
+        let existing = vec![entry(PROTON, Some("note"))];
+        let imported = entry(
+            "otpauth://totp/Other:another?secret=JBSWY3DPEHPK3PXP&issuer=Other&digits=8",
+            Some("other note"),
+        );
+
+        let preview = plan_import(&existing, import(vec![imported]));
+        assert!(preview.new_entries.is_empty());
+        assert_eq!(preview.conflicts.len(), 1);
+        assert_eq!(
+            preview.conflicts[0].fields,
+            vec![
+                ImportConflictField::Digits,
+                ImportConflictField::Name,
+                ImportConflictField::Issuer,
+                ImportConflictField::Note,
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_type_conflicts() {
+        let existing = vec![steam("JBSWY3DPEHPK3PXP", "user@proton.me")];
+        let imported = entry(PROTON, None);
+
+        let preview = plan_import(&existing, import(vec![imported]));
+        assert_eq!(preview.conflicts.len(), 1);
+        assert_eq!(preview.conflicts[0].fields, vec![ImportConflictField::Type]);
+    }
+
+    #[test]
+    fn suggests_merges() {
+        let existing = vec![entry("otpauth://totp/user%40proton.me?secret=JBSWY3DPEHPK3PXP", None)];
+        let imported = entry(PROTON, Some("A note"));
+
+        let preview = plan_import(&existing, import(vec![imported]));
+        assert_eq!(preview.merges.len(), 1);
+
+        let merged = &preview.merges[0].merged;
+        assert_eq!(merged.id, existing[0].id);
+        assert_eq!("Proton", merged.issuer());
+        assert_eq!("user@proton.me", merged.name());
+        assert_eq!(Some("A note".to_string()), merged.note);
+    }
+
+    #[test]
+    fn keeps_import_errors() {
+        let preview = plan_import(
+            &[],
+            ImportResult {
+                entries: vec![],
+                errors: vec![ImportError {
+                    context: "context".to_string(),
+                    message: "message".to_string(),
+                }],
+            },
+        );
+        assert_eq!(preview.errors.len(), 1);
+    }
+
+    #[test]
+    fn apply_adds_new_entries_and_merges() {
+        let existing = vec![
+            entry("otpauth://totp/user%40proton.me?secret=JBSWY3DPEHPK3PXP", None),
+            steam("STEAMKEY", "SteamAccount"),
+        ];
+        let new_entry = entry("otpauth://totp/Other:user?secret=GEZDGNBVGY3TQOJQ&issuer=Other", None);
+        let preview = plan_import(
+            &existing,
+            import(vec![
+                entry(PROTON, None),
+                new_entry.clone(),
+                steam("STEAMKEY", "SteamAccount"),
+            ]),
+        );
+        assert_eq!(preview.duplicates.len(), 1);
+
+        let without_merges = apply_import(existing.clone(), preview.clone(), &ImportDecisions::default());
+        assert_eq!(without_merges.len(), 3);
+        assert_eq!("", without_merges[0].issuer());
+        assert_eq!(new_entry.id, without_merges[2].id);
+
+        let decisions = ImportDecisions {
+            apply_merges: true,
+            ..Default::default()
+        };
+        let with_merges = apply_import(existing.clone(), preview, &decisions);
+        assert_eq!(with_merges.len(), 3);
+        assert_eq!(existing[0].id, with_merges[0].id);
+        assert_eq!("Proton", with_merges[0].issuer());
+    }
+
+    #[test]
+    fn apply_resolves_conflicts() {
+        let existing = vec![entry(PROTON, None)];
+        let keep_imported = entry(
+            "otpauth://totp/Other:another?secret=JBSWY3DPEHPK3PXP&issuer=Other",
+            None,
+        );
+        let keep_both = entry(
+            "otpauth://totp/Another:another?secret=JBSWY3DPEHPK3PXP&issuer=Another",
+            None,
+        );
+        let keep_existing = entry(
+            "otpauth://totp/Third:another?secret=JBSWY3DPEHPK3PXP&issuer=Third",
+            None,
+        );
+
+        let preview = plan_import(
+            &existing,
+            import(vec![keep_imported.clone(), keep_both.clone(), keep_existing]),
+        );
+        assert_eq!(preview.conflicts.len(), 3);
+
+        let decisions = ImportDecisions {
+            apply_merges: false,
+            conflict_resolutions: HashMap::from([
+                (keep_imported.id.clone(), ImportConflictResolution::KeepImported),
+                (keep_both.id.clone(), ImportConflictResolution::KeepBoth),
+            ]),
+        };
+        let result = apply_import(existing.clone(), preview, &decisions);
+        assert_eq!(result.len(), 2);
+        assert_eq!(existing[0].id, result[0].id);
+        assert_eq!("Other", result[0].issuer());
+        assert_eq!(keep_both.id, result[1].id);
+        assert_eq!("Another", result[1].issuer());
+    }
+}
@@ -1,17 +1,84 @@
+use crate::icon_bundle::{IconBundle, IconBundleError, IssuerIcon};
+use crate::search::fold;
+use public_suffix::{DEFAULT_PROVIDER, EffectiveTLDProvider};
 use std::collections::HashMap;
 
 // Include the domains file and images directory at compile time
-static ISSUER_FILE: &str = include_str!("../resources/issuerInfos.txt");
+pub(crate) static ISSUER_FILE: &str = include_str!("../resources/issuerInfos.txt");
 static OVERRIDE_FILE: &str = include_str!("../resources/issuerManualOverrides.txt");
 
-#[derive(Clone)]
+/// Words that don't identify the issuer, such as legal entity suffixes
+const IGNORED_SUFFIXES: &[&str] = &[
+    "ab",
+    "ag",
+    "bv",
+    "co",
+    "company",
+    "corp",
+    "corporation",
+    "gmbh",
+    "inc",
+    "incorporated",
+    "limited",
+    "llc",
+    "ltd",
+    "oy",
+    "plc",
+    "pty",
+    "sa",
+    "sarl",
+    "srl",
+];
+
+const EXACT_CONFIDENCE: f32 = 1.0;
+const DOMAIN_CONFIDENCE: f32 = 0.95;
+const ROOT_DOMAIN_CONFIDENCE: f32 = 0.9;
+const NORMALIZED_CONFIDENCE: f32 = 0.9;
+const COMPACT_CONFIDENCE: f32 = 0.85;
+/// Fuzzy matches score at most this, scaled by the token similarity
+const MAX_FUZZY_CONFIDENCE: f32 = 0.8;
+/// Matches below this confidence are discarded
+pub const MIN_CONFIDENCE: f32 = 0.5;
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct IssuerInfo {
     pub domain: String,
     pub icon_url: String,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IssuerMatchKind {
+    /// Same name, ignoring case
+    Exact,
+    /// The issuer is a domain or URL of the issuer
+    Domain,
+    /// Same name once punctuation, qualifiers and legal suffixes are removed
+    Normalized,
+    /// Similar words
+    Fuzzy,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IssuerMatch {
+    pub info: IssuerInfo,
+    pub kind: IssuerMatchKind,
+    /// From 0 to 1
+    pub confidence: f32,
+}
+
+struct KnownIssuer {
+    info: IssuerInfo,
+    tokens: Vec<Vec<char>>,
+}
+
 pub struct TOTPIssuerMapper {
-    issuer_infos: HashMap<String, IssuerInfo>,
+    issuers: Vec<KnownIssuer>,
+    by_name: HashMap<String, usize>,
+    by_normalized_name: HashMap<String, usize>,
+    by_compact_name: HashMap<String, usize>,
+    by_domain: HashMap<String, usize>,
+    by_root_domain: HashMap<String, usize>,
+    icons: Option<IconBundle>,
 }
 
 impl Default for TOTPIssuerMapper {
@@ -27,16 +94,154 @@ impl TOTPIssuerMapper {
         let overrides = Self::load_domains(OVERRIDE_FILE);
         domains.extend(infos);
         domains.extend(overrides);
-        let issuer_infos = Self::get_issuer_info(&domains);
 
-        TOTPIssuerMapper { issuer_infos }
+        let mut mapper = TOTPIssuerMapper {
+            issuers: Vec::new(),
+            by_name: HashMap::new(),
+            by_normalized_name: HashMap::new(),
+            by_compact_name: HashMap::new(),
+            by_domain: HashMap::new(),
+            by_root_domain: HashMap::new(),
+            icons: None,
+        };
+        for (name, info) in Self::get_issuer_info(&domains) {
+            mapper.insert(name, info);
+        }
+        mapper
+    }
+
+    /// Creates a mapper that serves the issuer icons from the given bundle, generated by the icon fetcher
+    pub fn with_icon_bundle(bundle: &[u8]) -> Result<Self, IconBundleError> {
+        let mut mapper = Self::new();
+        mapper.set_icon_bundle(IconBundle::from_bytes(bundle)?);
+        Ok(mapper)
     }
 
+    pub fn set_icon_bundle(&mut self, bundle: IconBundle) {
+        if !bundle.manifest().is_current() {
+            warn!("Icon bundle was generated from a different issuer list, some icons may be missing");
+        }
+        self.icons = Some(bundle);
+    }
+
+    pub fn icon_bundle(&self) -> Option<&IconBundle> {
+        self.icons.as_ref()
+    }
+
+    /// Icon of the issuer from the icon bundle, if one is loaded
+    pub fn icon(&self, issuer: &str) -> Option<IssuerIcon> {
+        let found = self.find(issuer)?;
+        self.icon_for_info(&found.info)
+    }
+
+    /// Icon of the issuer of a URL or domain from the icon bundle, if one is loaded
+    pub fn icon_for_url(&self, url: &str) -> Option<IssuerIcon> {
+        let found = self.find_by_url(url)?;
+        self.icon_for_info(&found.info)
+    }
+
+    fn icon_for_info(&self, info: &IssuerInfo) -> Option<IssuerIcon> {
+        let icons = self.icons.as_ref()?;
+        icons.icon_for_domain(&info.domain).or_else(|| {
+            let root = root_domain(&info.domain.to_lowercase())?;
+            icons.icon_for_domain(&root)
+        })
+    }
+
+    /// Returns the info of the issuer if it can be found with enough confidence
     pub fn lookup(&self, issuer: &str) -> Option<IssuerInfo> {
-        // Normalize the issuer string
-        let normalized = issuer.to_lowercase();
+        self.find(issuer).map(|m| m.info)
+    }
+
+    /// Finds the issuer trying, in order: the exact name, the issuer as a domain, the normalized name and a fuzzy
+    /// match on its words. Matches below [`MIN_CONFIDENCE`] are discarded.
+    pub fn find(&self, issuer: &str) -> Option<IssuerMatch> {
+        if let Some(idx) = self.by_name.get(&issuer.trim().to_lowercase()) {
+            return Some(self.matched(*idx, IssuerMatchKind::Exact, EXACT_CONFIDENCE));
+        }
 
-        self.issuer_infos.get(&normalized).cloned()
+        if let Some(found) = self.find_by_url(issuer) {
+            return Some(found);
+        }
+
+        let normalized = normalize(issuer);
+        if normalized.is_empty() {
+            return None;
+        }
+        if let Some(idx) = self.by_normalized_name.get(&normalized) {
+            return Some(self.matched(*idx, IssuerMatchKind::Normalized, NORMALIZED_CONFIDENCE));
+        }
+        if let Some(idx) = self.by_compact_name.get(&normalized.replace(' ', "")) {
+            return Some(self.matched(*idx, IssuerMatchKind::Normalized, COMPACT_CONFIDENCE));
+        }
+
+        self.find_fuzzy(&normalized)
+    }
+
+    /// Reverse lookup of the issuer of a URL or domain, such as `https://accounts.google.com/login`
+    pub fn find_by_url(&self, url: &str) -> Option<IssuerMatch> {
+        let host = host_of(url)?;
+        if let Some(idx) = self.by_domain.get(&host) {
+            return Some(self.matched(*idx, IssuerMatchKind::Domain, DOMAIN_CONFIDENCE));
+        }
+
+        let root = root_domain(&host)?;
+        self.by_root_domain
+            .get(&root)
+            .map(|idx| self.matched(*idx, IssuerMatchKind::Domain, ROOT_DOMAIN_CONFIDENCE))
+    }
+
+    fn insert(&mut self, name: String, info: IssuerInfo) {
+        let idx = self.issuers.len();
+        let normalized = normalize(&name);
+        let domain = info.domain.to_lowercase();
+
+        // Later entries (the manual overrides) replace the name matches of earlier ones
+        self.by_name.insert(name.to_lowercase(), idx);
+        if !normalized.is_empty() {
+            self.by_normalized_name.insert(normalized.clone(), idx);
+            self.by_compact_name.insert(normalized.replace(' ', ""), idx);
+        }
+        // Several issuers can share a domain, such as the products of a company. Prefer the one named after it
+        let named_after_domain = domain.split('.').next() == Some(normalized.replace(' ', "").as_str());
+        if named_after_domain {
+            self.by_domain.insert(domain.clone(), idx);
+        } else {
+            self.by_domain.entry(domain.clone()).or_insert(idx);
+        }
+        if let Some(root) = root_domain(&domain) {
+            // Prefer the issuer that owns the root domain over the ones of its subdomains
+            if root == domain && named_after_domain {
+                self.by_root_domain.insert(root, idx);
+            } else {
+                self.by_root_domain.entry(root).or_insert(idx);
+            }
+        }
+
+        self.issuers.push(KnownIssuer {
+            info,
+            tokens: tokenize(&normalized),
+        });
+    }
+
+    fn find_fuzzy(&self, normalized: &str) -> Option<IssuerMatch> {
+        let query_tokens = tokenize(normalized);
+        let mut best: Option<(usize, f32)> = None;
+        for (idx, issuer) in self.issuers.iter().enumerate() {
+            let confidence = MAX_FUZZY_CONFIDENCE * token_similarity(&query_tokens, &issuer.tokens);
+            if confidence >= MIN_CONFIDENCE && best.is_none_or(|(_, best_confidence)| confidence > best_confidence) {
+                best = Some((idx, confidence));
+            }
+        }
+        best.map(|(idx, confidence)| self.matched(idx, IssuerMatchKind::Fuzzy, confidence))
+    }
+
+    fn matched(&self, idx: usize, kind: IssuerMatchKind, confidence: f32) -> IssuerMatch {
+        IssuerMatch {
+            info: self.issuers[idx].info.clone(),
+            kind,
+            confidence,
+        }
     }
 
     fn load_domains(file: &str) -> Vec<String> {
@@ -47,8 +252,8 @@ impl TOTPIssuerMapper {
             .collect()
     }
 
-    fn get_issuer_info(infos: &[String]) -> HashMap<String, IssuerInfo> {
-        let mut map = HashMap::new();
+    fn get_issuer_info(infos: &[String]) -> Vec<(String, IssuerInfo)> {
+        let mut issuers = Vec::new();
 
         for info in infos {
             // Extract parts before TLD
@@ -57,23 +262,121 @@ impl TOTPIssuerMapper {
                 continue;
             }
 
-            let main_name = components[0].to_lowercase();
+            let main_name = components[0].to_string();
             let domain = components[1].to_string();
             let icon_url = components[2].to_string();
 
-            map.insert(
-                main_name.clone(),
-                IssuerInfo {
-                    domain: domain.to_string(),
-                    icon_url,
-                },
-            );
+            issuers.push((main_name, IssuerInfo { domain, icon_url }));
         }
 
-        map
+        issuers
     }
 }
 
+/// Lowercases, removes diacritics, qualifiers in brackets such as `(root)`, punctuation and legal entity suffixes
+fn normalize(value: &str) -> String {
+    let folded = fold(value);
+    let mut without_qualifiers = String::with_capacity(folded.len());
+    let mut depth = 0usize;
+    for c in folded.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => without_qualifiers.push(c),
+            _ => {}
+        }
+    }
+
+    let mut tokens: Vec<&str> = without_qualifiers
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .collect();
+    while tokens.len() > 1 && tokens.last().is_some_and(|token| IGNORED_SUFFIXES.contains(token)) {
+        tokens.pop();
+    }
+    tokens.join(" ")
+}
+
+fn tokenize(normalized: &str) -> Vec<Vec<char>> {
+    normalized
+        .split(' ')
+        .filter(|t| !t.is_empty())
+        .map(|t| t.chars().collect())
+        .collect()
+}
+
+/// Dice coefficient of both token lists, where tokens of 5 or more characters match with one typo
+fn token_similarity(query: &[Vec<char>], candidate: &[Vec<char>]) -> f32 {
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+
+    let mut used = vec![false; candidate.len()];
+    let mut matched = 0;
+    for token in query {
+        let found = candidate.iter().enumerate().find(|(idx, other)| {
+            if used[*idx] {
+                return false;
+            }
+            if token == *other {
+                return true;
+            }
+            token.len() >= 5 && other.len() >= 5 && within_one_edit(token, other)
+        });
+        if let Some((idx, _)) = found {
+            used[idx] = true;
+            matched += 1;
+        }
+    }
+
+    (2 * matched) as f32 / (query.len() + candidate.len()) as f32
+}
+
+/// Whether both words are equal but for one insertion, deletion, substitution or transposition of adjacent characters
+fn within_one_edit(a: &[char], b: &[char]) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+
+    let prefix = short.iter().zip(long).take_while(|(x, y)| x == y).count();
+    if prefix == short.len() {
+        return true;
+    }
+    if short.len() == long.len() {
+        let substitution = short[prefix + 1..] == long[prefix + 1..];
+        let transposition = prefix + 1 < short.len()
+            && short[prefix] == long[prefix + 1]
+            && short[prefix + 1] == long[prefix]
+            && short[prefix + 2..] == long[prefix + 2..];
+        substitution || transposition
+    } else {
+        short[prefix..] == long[prefix + 1..]
+    }
+}
+
+/// Host of a URL or bare domain. Values with spaces or without dots are not considered domains.
+fn host_of(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value.contains(char::is_whitespace) || !value.contains('.') {
+        return None;
+    }
+
+    let parsed = match url::Url::parse(value) {
+        Ok(parsed) if parsed.has_host() => parsed,
+        _ => url::Url::parse(&format!("https://{value}")).ok()?,
+    };
+    let host = parsed.domain()?.trim_end_matches('.').to_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+fn root_domain(host: &str) -> Option<String> {
+    DEFAULT_PROVIDER
+        .effective_tld_plus_one(host)
+        .ok()
+        .map(|root| root.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +414,105 @@ mod tests {
         let infos = mapper.lookup("non existing domain");
         assert!(infos.is_none());
     }
+
+    #[test]
+    fn matches_normalized_names() {
+        let mapper = TOTPIssuerMapper::new();
+
+        let google = mapper.find("Google LLC").expect("should match");
+        assert_eq!("google.com", google.info.domain);
+        assert_eq!(IssuerMatchKind::Normalized, google.kind);
+
+        let aws = mapper.find("Amazon Web Services (root)").expect("should match");
+        assert_eq!("aws.amazon.com", aws.info.domain);
+        assert_eq!(IssuerMatchKind::Normalized, aws.kind);
+
+        let exact = mapper.find("GitHub").expect("should match");
+        assert_eq!(IssuerMatchKind::Exact, exact.kind);
+        assert_eq!(EXACT_CONFIDENCE, exact.confidence);
+    }
+
+    #[test]
+    fn matches_domains_and_urls() {
+        let mapper = TOTPIssuerMapper::new();
+
+        for value in [
+            "google.com",
+            "accounts.google.com",
+            "https://accounts.google.com/signin?hl=en",
+        ] {
+            let found = mapper.find(value).unwrap_or_else(|| panic!("should match {value}"));
+            assert_eq!("google.com", found.info.domain, "{value}");
+            assert_eq!(IssuerMatchKind::Domain, found.kind);
+        }
+
+        // The subdomain of an issuer matches it instead of the owner of the root domain
+        let aws = mapper
+            .find_by_url("https://aws.amazon.com/console")
+            .expect("should match");
+        assert_eq!("aws.amazon.com", aws.info.domain);
+        assert_eq!(DOMAIN_CONFIDENCE, aws.confidence);
+
+        assert!(mapper.find_by_url("https://unknown-issuer.example").is_none());
+        assert!(mapper.find_by_url("not a url").is_none());
+    }
+
+    #[test]
+    fn serves_icons_from_bundle() {
+        let mut mapper = TOTPIssuerMapper::new();
+        assert!(mapper.icon("GitHub").is_none());
+
+        let mut builder = crate::IconBundleBuilder::new(ISSUER_FILE, "image/webp");
+        builder.add("github", "github.com", vec![1, 2, 3]);
+        builder.add("google", "google.com", vec![4, 5, 6]);
+        mapper = TOTPIssuerMapper::with_icon_bundle(&builder.build().unwrap()).expect("should load bundle");
+
+        let icon = mapper.icon("GitHub").expect("should have icon");
+        assert_eq!("image/webp", icon.mime_type);
+        assert_eq!(vec![1, 2, 3], icon.data);
+        assert_eq!(vec![4, 5, 6], mapper.icon("Google LLC").unwrap().data);
+        assert_eq!(
+            vec![4, 5, 6],
+            mapper.icon_for_url("https://accounts.google.com").unwrap().data
+        );
+        assert!(mapper.icon("aws.amazon").is_none());
+        assert!(mapper.icon("non existing domain").is_none());
+    }
+
+    #[test]
+    fn matches_similar_names() {
+        let mapper = TOTPIssuerMapper::new();
+
+        let found = mapper.find("Dropbx").expect("should match");
+        assert_eq!("dropbox.com", found.info.domain);
+        assert_eq!(IssuerMatchKind::Fuzzy, found.kind);
+        assert!(found.confidence < NORMALIZED_CONFIDENCE);
+        assert!(found.confidence >= MIN_CONFIDENCE);
+    }
+
+    #[test]
+    fn detects_single_edits() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        for (a, b) in [
+            ("dropbox", "dropbox"),
+            ("dropbox", "dropbx"),
+            ("dropbox", "drobpox"),
+            ("dropbox", "dropbix"),
+        ] {
+            assert!(within_one_edit(&chars(a), &chars(b)), "{a} {b}");
+            assert!(within_one_edit(&chars(b), &chars(a)), "{b} {a}");
+        }
+        for (a, b) in [("dropbox", "drpbx"), ("dropbox", "dorpbix"), ("dropbox", "xdropboxx")] {
+            assert!(!within_one_edit(&chars(a), &chars(b)), "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!("amazon web services", normalize("Amazon Web Services (root)"));
+        assert_eq!("google", normalize("Google, LLC."));
+        assert_eq!("societe generale", normalize("Société Générale SA"));
+        // A name made only of a suffix is kept
+        assert_eq!("co", normalize("Co"));
+    }
 }
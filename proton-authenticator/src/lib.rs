@@ -7,42 +7,67 @@ pub mod log;
 #[cfg_attr(test, macro_use)]
 pub mod test_utils;
 
+pub mod backup;
 pub mod crypto;
 pub mod entry;
 pub mod generator;
+pub mod icon_bundle;
+pub mod import_planner;
 pub mod issuer_mapper;
 pub mod operations;
 pub mod ordering;
 pub mod parser;
 #[cfg(feature = "qr")]
 pub mod qr;
+pub mod search;
 pub mod steam;
 
 mod client;
 mod utils;
 
+pub use backup::{
+    BackupError, BackupFileInfo, BackupManifest, BackupProtection, BackupProtectionInfo, BackupRetentionPolicy,
+    BackupSnapshot, backup_file_name, backups_to_prune, create_backup, list_backups, read_backup_manifest,
+    restore_backup, verify_backup,
+};
 pub use client::{AuthenticatorClient, AuthenticatorCodeResponse, AuthenticatorError};
 pub use entry::{
     AuthenticatorEntry, AuthenticatorEntryContent, AuthenticatorEntryError, AuthenticatorEntrySteamCreateParameters,
     AuthenticatorEntryTotpCreateParameters, AuthenticatorEntryTotpParameters, AuthenticatorEntryType,
-    AuthenticatorEntryUpdateContents, decrypt_entries, encrypt_entries,
+    AuthenticatorEntryUpdateContents, EntryUsage, KdfAlgorithm, KdfParams, KdfProfile, calibrate_kdf_params,
+    decrypt_entries, decrypt_usage, encrypt_entries, encrypt_usage, find_entry_key, merge_usage, reencrypt_entries,
 };
 
 #[cfg(any(feature = "uniffi", feature = "wasm"))]
 pub use entry::{AuthenticatorCodeResponseModel, AuthenticatorEntryModel};
-pub use issuer_mapper::{IssuerInfo, TOTPIssuerMapper};
+pub use icon_bundle::{
+    IconBundle, IconBundleBuilder, IconBundleEntry, IconBundleError, IconBundleManifest, IssuerIcon,
+};
+pub use issuer_mapper::{IssuerInfo, IssuerMatch, IssuerMatchKind, TOTPIssuerMapper};
 pub use log::{LogLevel, Logger, emit_log_message, register_authenticator_logger};
 pub use parser::aegis::{parse_aegis_json, parse_aegis_txt};
+pub use parser::andotp::{parse_andotp_file, parse_andotp_json};
+pub use parser::authy::parse_authy_json;
 pub use parser::bitwarden::{parse_bitwarden_csv, parse_bitwarden_json};
+pub use parser::detect::{
+    DetectedFormat, DetectedImportError, DetectedImportResult, ImportFormat, detect_and_parse, detect_import_format,
+};
 pub use parser::ente::{parse_ente_encrypted, parse_ente_txt};
+pub use parser::freeotp::{parse_freeotp_backup, parse_freeotp_plus_json, parse_freeotp_xml};
 pub use parser::google::parse_google_authenticator_totp;
+pub use parser::keepass::{parse_keepass_csv, parse_keepass_kdbx};
 pub use parser::lastpass::parse_lastpass_json;
+pub use parser::microsoft::{parse_microsoft_authenticator_csv, parse_microsoft_authenticator_json};
+pub use parser::otpauth::parse_otpauth_db;
 pub use parser::pass::parse_pass_zip;
 pub use parser::proton_authenticator::{
     parse_proton_authenticator_export, parse_proton_authenticator_export_with_password,
 };
+pub use parser::raivo::{parse_raivo_json, parse_raivo_zip};
+pub use parser::steam::{SteamMaFile, parse_steam_mafile, parse_steam_mafile_encrypted, parse_steam_mafiles};
 pub use parser::twofas::parse_2fas_file;
 pub use parser::{ImportError, ImportResult, ThirdPartyImportError};
+pub use search::{EntrySearchIndex, SearchField, SearchResult};
 
 pub fn library_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
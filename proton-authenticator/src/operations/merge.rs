@@ -0,0 +1,140 @@
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MergedField {
+    Name,
+    Issuer,
+    Note,
+    Params,
+    Tags,
+    Favourite,
+}
+
+pub struct MergeResult {
+    pub entry: AuthenticatorEntry,
+    /// Fields that were modified on both sides and had to be resolved by last-writer-wins
+    pub conflicts: Vec<MergedField>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Side {
+    Local,
+    Remote,
+}
+
+/// Three-way merge of the local and remote versions of an entry against the last synced version.
+/// Fields modified on only one side take that side's value. When a field was modified on both sides
+/// the most recent version wins.
+pub fn merge_entries(
+    base: &AuthenticatorEntry,
+    local: &AuthenticatorEntry,
+    remote: &AuthenticatorEntry,
+    local_is_newer: bool,
+) -> MergeResult {
+    let mut conflicts = Vec::new();
+    let newest = if local_is_newer { Side::Local } else { Side::Remote };
+    let mut choose = |field: MergedField, base_changed_local: bool, base_changed_remote: bool, equal: bool| match (
+        base_changed_local,
+        base_changed_remote,
+    ) {
+        (true, false) => Side::Local,
+        (false, _) => Side::Remote,
+        (true, true) if equal => Side::Remote,
+        (true, true) => {
+            conflicts.push(field);
+            newest
+        }
+    };
+
+    let params_side = choose(
+        MergedField::Params,
+        !same_params(base, local),
+        !same_params(base, remote),
+        same_params(local, remote),
+    );
+    let name_side = choose(
+        MergedField::Name,
+        base.name() != local.name(),
+        base.name() != remote.name(),
+        local.name() == remote.name(),
+    );
+    let issuer_side = choose(
+        MergedField::Issuer,
+        base.issuer() != local.issuer(),
+        base.issuer() != remote.issuer(),
+        local.issuer() == remote.issuer(),
+    );
+    let note_side = choose(
+        MergedField::Note,
+        note(base) != note(local),
+        note(base) != note(remote),
+        note(local) == note(remote),
+    );
+    let tags_side = choose(
+        MergedField::Tags,
+        base.tags != local.tags,
+        base.tags != remote.tags,
+        local.tags == remote.tags,
+    );
+    let favourite_side = choose(
+        MergedField::Favourite,
+        base.favourite != local.favourite,
+        base.favourite != remote.favourite,
+        local.favourite == remote.favourite,
+    );
+
+    let side = |side: Side| match side {
+        Side::Local => local,
+        Side::Remote => remote,
+    };
+
+    let mut entry = side(params_side).clone();
+    entry.id = local.id.clone();
+    if name_side != params_side {
+        copy_name(&mut entry, side(name_side));
+    }
+    if issuer_side != params_side {
+        copy_issuer(&mut entry, side(issuer_side));
+    }
+    entry.note = side(note_side).note.clone();
+    entry.tags = side(tags_side).tags.clone();
+    entry.favourite = side(favourite_side).favourite;
+
+    MergeResult { entry, conflicts }
+}
+
+fn copy_name(entry: &mut AuthenticatorEntry, from: &AuthenticatorEntry) {
+    match (&mut entry.content, &from.content) {
+        (AuthenticatorEntryContent::Totp(totp), AuthenticatorEntryContent::Totp(from)) => {
+            totp.label = from.label.clone();
+        }
+        (AuthenticatorEntryContent::Totp(totp), _) => totp.label = Some(from.name()),
+        (AuthenticatorEntryContent::Steam(steam), _) => steam.set_name(Some(from.name())),
+    }
+}
+
+fn copy_issuer(entry: &mut AuthenticatorEntry, from: &AuthenticatorEntry) {
+    // Steam entries always have the same issuer
+    if let (AuthenticatorEntryContent::Totp(totp), AuthenticatorEntryContent::Totp(from)) =
+        (&mut entry.content, &from.content)
+    {
+        totp.issuer = from.issuer.clone();
+    }
+}
+
+fn note(entry: &AuthenticatorEntry) -> &str {
+    entry.note.as_deref().unwrap_or_default()
+}
+
+fn same_params(a: &AuthenticatorEntry, b: &AuthenticatorEntry) -> bool {
+    match (&a.content, &b.content) {
+        (AuthenticatorEntryContent::Totp(a), AuthenticatorEntryContent::Totp(b)) => {
+            a.secret == b.secret
+                && a.get_algorithm() == b.get_algorithm()
+                && a.get_digits() == b.get_digits()
+                && a.get_period() == b.get_period()
+        }
+        (AuthenticatorEntryContent::Steam(a), AuthenticatorEntryContent::Steam(b)) => a.secret == b.secret,
+        _ => false,
+    }
+}
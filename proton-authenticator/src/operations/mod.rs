@@ -0,0 +1,926 @@
+use crate::AuthenticatorEntry;
+use crate::utils::list_to_map;
+use std::collections::HashSet;
+
+mod merge;
+mod planner;
+
+pub use merge::{MergeResult, MergedField, merge_entries};
+pub use planner::{LocalSyncEntry, OrderUpdate, RemoteSyncEntry, SyncPlan, plan_sync};
+
+#[derive(Clone, Copy)]
+pub enum AuthenticatorEntryState {
+    Synced,
+    PendingSync,
+    PendingToDelete,
+}
+
+#[derive(Clone)]
+pub struct LocalEntry {
+    pub entry: AuthenticatorEntry,
+    pub state: AuthenticatorEntryState,
+    /// id of the entry on the server; `None` == the entry was created locally and never pushed
+    pub remote_id: Option<String>,
+    /// `modify_time` the server knew about when the entry was fetched
+    pub modify_time: i64,
+    /// local changes that could not yet be pushed; `None` == no offline edits
+    pub local_modify_time: Option<i64>,
+    /// last version that was synced with the server, used to merge offline edits field by field
+    pub base: Option<AuthenticatorEntry>,
+}
+
+#[derive(Clone)]
+pub struct RemoteEntry {
+    pub remote_id: String,
+    pub revision: u32,
+    pub entry: AuthenticatorEntry,
+    /// last-modified (time‐millis since Unix epoch) as returned by the server
+    pub modify_time: i64,
+    /// tombstone: when the entry was deleted on the server; `None` == entry is alive
+    pub delete_time: Option<i64>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum AuthenticatorOperation {
+    // Update local copy of the entry
+    Upsert,
+    // Delete local copy
+    DeleteLocal,
+    // Delete local copy and make api request to delete remote
+    DeleteLocalAndRemote,
+    // Create remote version
+    Push,
+    // Both sides changed different fields. Update local copy with the merged entry and push it
+    Merge,
+    // Both sides changed the same fields, which were resolved by last-writer-wins. Same as `Merge`,
+    // but the user may want to review the result
+    Conflict,
+    // Deleted remotely after being edited locally. The user should choose between deleting the local
+    // copy or pushing it again
+    RemoteDeletedLocalEdited,
+    // Deleted locally but edited remotely afterwards. The user should choose between restoring the
+    // remote version locally or deleting it remotely
+    LocalDeletedRemoteEdited,
+}
+
+#[derive(Debug)]
+pub struct EntryOperation {
+    pub remote_id: Option<String>,
+    pub revision: Option<u32>,
+    pub entry: AuthenticatorEntry,
+    pub operation: AuthenticatorOperation,
+}
+
+/// Calculates the operations to perform given the complete list of remote entries. Local entries
+/// missing from the remote list are considered deleted remotely.
+pub fn calculate_operations_to_perform(remote: Vec<RemoteEntry>, local: Vec<LocalEntry>) -> Vec<EntryOperation> {
+    calculate_operations(remote, local, true)
+}
+
+/// Same as [calculate_operations_to_perform] but for a partial list of remote entries (a page or a
+/// filtered listing). Local entries missing from the remote list are left untouched unless they
+/// were created locally.
+pub fn calculate_operations_for_partial_listing(
+    remote: Vec<RemoteEntry>,
+    local: Vec<LocalEntry>,
+) -> Vec<EntryOperation> {
+    calculate_operations(remote, local, false)
+}
+
+fn calculate_operations(
+    remote: Vec<RemoteEntry>,
+    local: Vec<LocalEntry>,
+    complete_listing: bool,
+) -> Vec<EntryOperation> {
+    let local_entries = list_to_map(local, |e| e.entry.id.to_string());
+    let mut remote_entry_ids = HashSet::new();
+    let mut ops = Vec::new();
+
+    // Remote present
+    for remote_entry in remote {
+        let remote_entry_id = remote_entry.entry.id.to_string();
+
+        if remote_entry.delete_time.is_some() {
+            if let Some(local_entry) = local_entries.get(&remote_entry_id) {
+                ops.push(tombstone_operation(&remote_entry, local_entry));
+            }
+            remote_entry_ids.insert(remote_entry_id);
+            continue;
+        }
+
+        match local_entries.get(&remote_entry_id) {
+            // Also exists locally
+            Some(local_entry) => match local_entry.state {
+                // Synced – check if there are changes
+                AuthenticatorEntryState::Synced => {
+                    if !local_entry.entry.eq(&remote_entry.entry) {
+                        ops.push(EntryOperation {
+                            remote_id: Some(remote_entry.remote_id.clone()),
+                            revision: Some(remote_entry.revision),
+                            entry: remote_entry.entry.clone(),
+                            operation: AuthenticatorOperation::Upsert,
+                        });
+                    }
+                }
+
+                // Pending offline update
+                AuthenticatorEntryState::PendingSync => {
+                    // First check if there is actually a difference
+                    if local_entry.entry.eq(&remote_entry.entry) {
+                        // No difference. Just upsert the local one to make sure the local->remote
+                        // link is performed
+                        ops.push(EntryOperation {
+                            remote_id: Some(remote_entry.remote_id.clone()),
+                            revision: Some(remote_entry.revision),
+                            entry: remote_entry.entry.clone(),
+                            operation: AuthenticatorOperation::Upsert,
+                        });
+                    } else if let Some(base) = &local_entry.base {
+                        // We know the last synced version, so merge the changes field by field
+                        let local_time = local_entry.local_modify_time.unwrap_or(local_entry.modify_time);
+                        let merged = merge_entries(
+                            base,
+                            &local_entry.entry,
+                            &remote_entry.entry,
+                            local_time >= remote_entry.modify_time,
+                        );
+
+                        let operation = if !merged.conflicts.is_empty() {
+                            AuthenticatorOperation::Conflict
+                        } else if merged.entry.eq(&remote_entry.entry) {
+                            AuthenticatorOperation::Upsert
+                        } else if merged.entry.eq(&local_entry.entry) {
+                            AuthenticatorOperation::Push
+                        } else {
+                            AuthenticatorOperation::Merge
+                        };
+                        ops.push(EntryOperation {
+                            remote_id: Some(remote_entry.remote_id.clone()),
+                            revision: Some(remote_entry.revision),
+                            entry: merged.entry,
+                            operation,
+                        });
+                    } else {
+                        // There is actually a difference. Check the timestamps
+                        let same_timestamp = remote_entry.modify_time == local_entry.modify_time;
+                        match (same_timestamp, local_entry.local_modify_time) {
+                            // Equal mtime + offline edits -> push local version
+                            (true, Some(_)) => ops.push(EntryOperation {
+                                remote_id: Some(remote_entry.remote_id.clone()),
+                                revision: Some(remote_entry.revision),
+                                entry: local_entry.entry.clone(),
+                                operation: AuthenticatorOperation::Push,
+                            }),
+
+                            // Equal mtime + no offline edits, but we are sure there is a
+                            // difference in content -> remote wins so we avoid extra calls, upsert
+                            (true, None) => {
+                                ops.push(EntryOperation {
+                                    remote_id: Some(remote_entry.remote_id.clone()),
+                                    revision: Some(remote_entry.revision),
+                                    entry: remote_entry.entry.clone(),
+                                    operation: AuthenticatorOperation::Upsert,
+                                });
+                            }
+
+                            // Different mtime + no offline edits -> remote wins
+                            (false, None) => ops.push(EntryOperation {
+                                remote_id: Some(remote_entry.remote_id.clone()),
+                                revision: Some(remote_entry.revision),
+                                entry: remote_entry.entry.clone(),
+                                operation: AuthenticatorOperation::Upsert,
+                            }),
+
+                            // Different mtime + offline edits -> compare times, most recent wins
+                            _ => {
+                                let local_time = local_entry.local_modify_time.unwrap_or(local_entry.modify_time);
+                                if remote_entry.modify_time > local_time {
+                                    // Remote is newer, use remote version
+                                    ops.push(EntryOperation {
+                                        remote_id: Some(remote_entry.remote_id.clone()),
+                                        revision: Some(remote_entry.revision),
+                                        entry: remote_entry.entry.clone(),
+                                        operation: AuthenticatorOperation::Upsert,
+                                    });
+                                } else {
+                                    // Local is newer or equal, push local version
+                                    ops.push(EntryOperation {
+                                        remote_id: Some(remote_entry.remote_id.clone()),
+                                        revision: Some(remote_entry.revision),
+                                        entry: local_entry.entry.clone(),
+                                        operation: AuthenticatorOperation::Push,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Pending deletion
+                AuthenticatorEntryState::PendingToDelete => {
+                    // Any remote edit the local copy never saw conflicts with the deletion, whatever the clocks say
+                    let last_synced = local_entry.base.as_ref().unwrap_or(&local_entry.entry);
+                    let edited_remotely =
+                        remote_entry.modify_time != local_entry.modify_time && !remote_entry.entry.eq(last_synced);
+                    let operation = if edited_remotely {
+                        AuthenticatorOperation::LocalDeletedRemoteEdited
+                    } else {
+                        AuthenticatorOperation::DeleteLocalAndRemote
+                    };
+                    ops.push(EntryOperation {
+                        remote_id: Some(remote_entry.remote_id.clone()),
+                        revision: Some(remote_entry.revision),
+                        entry: remote_entry.entry.clone(),
+                        operation,
+                    })
+                }
+            },
+
+            // Only on the server ─> store it locally
+            None => ops.push(EntryOperation {
+                remote_id: Some(remote_entry.remote_id.clone()),
+                revision: Some(remote_entry.revision),
+                entry: remote_entry.entry.clone(),
+                operation: AuthenticatorOperation::Upsert,
+            }),
+        };
+
+        remote_entry_ids.insert(remote_entry_id);
+    }
+
+    // Only available locally
+    for (local_id, local_entry) in local_entries.iter() {
+        if !remote_entry_ids.contains(local_id) {
+            match local_entry.state {
+                // We cannot know if it was deleted remotely or just not part of the listing
+                AuthenticatorEntryState::Synced | AuthenticatorEntryState::PendingToDelete if !complete_listing => {}
+                // Already exists remotely, pushing it would create a duplicate
+                AuthenticatorEntryState::PendingSync
+                    if !complete_listing && (local_entry.remote_id.is_some() || local_entry.base.is_some()) => {}
+                AuthenticatorEntryState::Synced => ops.push(EntryOperation {
+                    remote_id: None,
+                    revision: None,
+                    entry: local_entry.entry.clone(),
+                    operation: AuthenticatorOperation::DeleteLocal,
+                }),
+                AuthenticatorEntryState::PendingSync => ops.push(EntryOperation {
+                    remote_id: None,
+                    revision: None,
+                    entry: local_entry.entry.clone(),
+                    operation: AuthenticatorOperation::Push,
+                }),
+                AuthenticatorEntryState::PendingToDelete => ops.push(EntryOperation {
+                    remote_id: None,
+                    revision: None,
+                    entry: local_entry.entry.clone(),
+                    operation: AuthenticatorOperation::DeleteLocal,
+                }),
+            }
+        }
+    }
+
+    ops
+}
+
+fn tombstone_operation(remote_entry: &RemoteEntry, local_entry: &LocalEntry) -> EntryOperation {
+    // Any unsynced local edit conflicts with the deletion, as the clocks of both sides can't be trusted
+    let last_synced = local_entry.base.as_ref().unwrap_or(&remote_entry.entry);
+    let edited_locally =
+        matches!(local_entry.state, AuthenticatorEntryState::PendingSync) && !local_entry.entry.eq(last_synced);
+    let operation = if edited_locally {
+        AuthenticatorOperation::RemoteDeletedLocalEdited
+    } else {
+        AuthenticatorOperation::DeleteLocal
+    };
+
+    EntryOperation {
+        remote_id: Some(remote_entry.remote_id.clone()),
+        revision: Some(remote_entry.revision),
+        entry: local_entry.entry.clone(),
+        operation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthenticatorEntryContent;
+    use rand::rngs::ThreadRng;
+
+    const NOW: i64 = 1_700_000_000; // 2023-11-14T06:13:20Z
+    const LATE: i64 = NOW + 1_000; // a bit later
+    const VERY_LATE: i64 = NOW + 2_000; // even later
+
+    fn local_entry_with_entry_state_and_times(
+        entry: AuthenticatorEntry,
+        state: AuthenticatorEntryState,
+        modify_time: i64,
+        local_modify_time: Option<i64>,
+    ) -> LocalEntry {
+        LocalEntry {
+            entry,
+            state,
+            remote_id: None,
+            modify_time,
+            local_modify_time,
+            base: None,
+        }
+    }
+
+    fn local_entry_with_state(state: AuthenticatorEntryState) -> LocalEntry {
+        local_entry_with_entry_state_and_times(random_entry(), state, NOW, None)
+    }
+
+    fn local_entry_with_entry_and_state(entry: AuthenticatorEntry, state: AuthenticatorEntryState) -> LocalEntry {
+        local_entry_with_entry_state_and_times(entry, state, NOW, None)
+    }
+
+    fn remote_entry_with_id_and_time(id: String, modify_time: i64) -> RemoteEntry {
+        RemoteEntry {
+            remote_id: id,
+            revision: 0,
+            entry: random_entry(),
+            modify_time,
+            delete_time: None,
+        }
+    }
+
+    fn remote_entry() -> RemoteEntry {
+        remote_entry_with_id_and_time(random_id(), NOW)
+    }
+
+    fn modify_entry(entry: &AuthenticatorEntry) -> AuthenticatorEntry {
+        let mut cloned = entry.clone();
+        match &mut cloned.content {
+            AuthenticatorEntryContent::Totp(totp) => totp.issuer = Some(random_id()),
+            AuthenticatorEntryContent::Steam(steam) => steam.name = Some(random_id()),
+        }
+
+        cloned
+    }
+
+    fn random_id() -> String {
+        use rand::RngCore;
+
+        let mut res = String::new();
+        let dict = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        for _ in 0..10 {
+            let idx = ThreadRng::default().next_u32() as usize % dict.chars().count();
+            res.push(dict.chars().nth(idx).unwrap());
+        }
+
+        res
+    }
+
+    fn random_entry() -> AuthenticatorEntry {
+        AuthenticatorEntry::from_uri(
+            "otpauth://totp/myissuer%3Amylabel?period=15&digits=8&algorithm=SHA256&secret=MYSECRET&issuer=myissuer",
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn can_handle_empty_lists() {
+        let res = calculate_operations_to_perform(vec![], vec![]);
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn remote_not_in_local_returns_insert_operation() {
+        let remote_entry = remote_entry();
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![]);
+        assert_eq!(1, res.len());
+
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Upsert));
+        assert_eq!(remote_entry.entry.id, res[0].entry.id);
+        assert_eq!(Some(remote_entry.remote_id), res[0].remote_id);
+    }
+
+    #[test]
+    fn local_synced_not_in_remote_returns_delete_local_operation() {
+        let local_entry = local_entry_with_state(AuthenticatorEntryState::Synced);
+        let res = calculate_operations_to_perform(vec![], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+
+        assert!(matches!(res[0].operation, AuthenticatorOperation::DeleteLocal));
+        assert_eq!(local_entry.entry.id, res[0].entry.id);
+    }
+
+    #[test]
+    fn local_pending_not_in_remote_returns_push_operation() {
+        let local_entry = local_entry_with_state(AuthenticatorEntryState::PendingSync);
+        let res = calculate_operations_to_perform(vec![], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Push));
+        assert_eq!(local_entry.entry.id, res[0].entry.id);
+    }
+
+    #[test]
+    fn local_pending_to_delete_not_in_remote_returns_delete_local() {
+        let local_entry = local_entry_with_state(AuthenticatorEntryState::PendingToDelete);
+        let res = calculate_operations_to_perform(vec![], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+
+        assert!(matches!(res[0].operation, AuthenticatorOperation::DeleteLocal));
+        assert_eq!(local_entry.entry.id, res[0].entry.id);
+    }
+
+    #[test]
+    fn remote_in_local_with_no_changes_returns_nothing() {
+        let remote_entry = remote_entry();
+        let local_entry = local_entry_with_entry_and_state(remote_entry.entry.clone(), AuthenticatorEntryState::Synced);
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry.clone()]);
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn remote_in_local_synced_with_remote_changes_returns_upsert() {
+        let remote_entry = remote_entry();
+        let local_entry =
+            local_entry_with_entry_and_state(modify_entry(&remote_entry.entry), AuthenticatorEntryState::Synced);
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Upsert));
+
+        // Remote content is returned
+        assert_eq!(remote_entry.entry.content, res[0].entry.content);
+        assert_eq!(Some(remote_entry.remote_id), res[0].remote_id);
+    }
+
+    #[test]
+    fn remote_in_local_pending_to_sync_with_changes_returns_upsert_when_remote_newer() {
+        let remote_entry = remote_entry_with_id_and_time(random_id(), LATE); // Remote is newer
+        let local_entry = LocalEntry {
+            entry: modify_entry(&remote_entry.entry),
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: Some(NOW), // Local edit at same time as original modify_time
+            base: None,
+        };
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Upsert));
+        assert_eq!(Some(remote_entry.remote_id), res[0].remote_id);
+        assert_eq!(remote_entry.entry.content, res[0].entry.content); // Remote content wins
+    }
+
+    #[test]
+    fn remote_in_local_pending_to_delete_returns_delete_remote_local() {
+        let remote_entry = remote_entry();
+        let local_entry =
+            local_entry_with_entry_and_state(remote_entry.entry.clone(), AuthenticatorEntryState::PendingToDelete);
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+
+        assert!(matches!(res[0].operation, AuthenticatorOperation::DeleteLocalAndRemote));
+        assert_eq!(Some(remote_entry.remote_id), res[0].remote_id);
+    }
+
+    #[test]
+    fn pending_sync_same_mtime_but_offline_edit_pushes() {
+        let remote_entry = remote_entry(); // mtime == NOW
+        let local_auth_entry = modify_entry(&remote_entry.entry); // local changes
+
+        let local_entry = LocalEntry {
+            entry: local_auth_entry.clone(),
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: Some(VERY_LATE), // unsynced edit
+            base: None,
+        };
+
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry]);
+        assert_eq!(1, res.len());
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Push));
+        // local version is the one pushed
+        assert_eq!(local_auth_entry.content, res[0].entry.content);
+    }
+
+    #[test]
+    fn pending_sync_remote_newer_no_local_edit_upserts() {
+        let remote_entry = remote_entry_with_id_and_time(random_id(), LATE); // LATE > NOW
+
+        let local_entry = LocalEntry {
+            entry: remote_entry.entry.clone(), // same content, just older timestamp
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: None,
+            base: None,
+        };
+
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry]);
+        assert_eq!(1, res.len());
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Upsert));
+        // remote wins
+        assert_eq!(remote_entry.modify_time, LATE);
+    }
+
+    #[test]
+    fn pending_sync_both_modified_local_newer_wins() {
+        let remote_entry = remote_entry_with_id_and_time(random_id(), LATE); // remote changed
+        let local_entry = LocalEntry {
+            entry: modify_entry(&remote_entry.entry), // also edited offline
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: Some(VERY_LATE), // Local is newer than remote
+            base: None,
+        };
+
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Push));
+        assert_eq!(local_entry.entry.content, res[0].entry.content); // Local content wins
+    }
+
+    #[test]
+    fn pending_sync_both_modified_remote_newer_wins() {
+        let remote_entry = remote_entry_with_id_and_time(random_id(), VERY_LATE); // remote is newer
+        let local_entry = LocalEntry {
+            entry: modify_entry(&remote_entry.entry), // also edited offline
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: Some(LATE), // Local is older than remote
+            base: None,
+        };
+
+        let res = calculate_operations_to_perform(vec![remote_entry.clone()], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Upsert));
+        assert_eq!(remote_entry.entry.content, res[0].entry.content); // Remote content wins
+    }
+
+    #[test]
+    fn local_equals_remote_triggers_upsert() {
+        let id = random_id();
+        let remote = remote_entry_with_id_and_time(id.clone(), NOW);
+        let local_entry = LocalEntry {
+            entry: remote.entry.clone(),
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: None,
+            base: None,
+        };
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+        assert!(matches!(res[0].operation, AuthenticatorOperation::Upsert));
+        assert_eq!(local_entry.entry.content, res[0].entry.content);
+        assert_eq!(remote.entry.content, res[0].entry.content);
+        assert_eq!(Some(id), res[0].remote_id)
+    }
+
+    #[test]
+    fn local_differs_from_remote_triggers_upsert() {
+        let id = random_id();
+        let remote = remote_entry_with_id_and_time(id.clone(), NOW);
+        let local_entry = LocalEntry {
+            entry: modify_entry(&remote.entry), // Different content
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: None,
+            base: None,
+        };
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local_entry.clone()]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::Upsert, res[0].operation);
+        assert_eq!(remote.entry.content, res[0].entry.content); // Remote content is stored
+        assert_eq!(Some(id), res[0].remote_id)
+    }
+
+    fn totp_entry(id: &str, label: &str, issuer: &str, note: Option<&str>) -> AuthenticatorEntry {
+        AuthenticatorEntry::from_uri_and_id(
+            &format!("otpauth://totp/{label}?secret=MYSECRET&issuer={issuer}"),
+            note.map(|n| n.to_string()),
+            id.to_string(),
+        )
+        .unwrap()
+    }
+
+    fn pending_with_base(entry: AuthenticatorEntry, base: AuthenticatorEntry, local_modify_time: i64) -> LocalEntry {
+        LocalEntry {
+            entry,
+            state: AuthenticatorEntryState::PendingSync,
+            remote_id: None,
+            modify_time: NOW,
+            local_modify_time: Some(local_modify_time),
+            base: Some(base),
+        }
+    }
+
+    fn remote_with_entry(entry: AuthenticatorEntry, modify_time: i64) -> RemoteEntry {
+        RemoteEntry {
+            remote_id: random_id(),
+            revision: 1,
+            entry,
+            modify_time,
+            delete_time: None,
+        }
+    }
+
+    #[test]
+    fn merges_tags_and_favourite() {
+        let id = random_id();
+        let base = totp_entry(&id, "label", "issuer", None);
+        let mut remote_entry = base.clone();
+        remote_entry.favourite = true;
+        let mut local_entry = base.clone();
+        local_entry.tags = vec!["Work".to_string()];
+
+        let res = calculate_operations_to_perform(
+            vec![remote_with_entry(remote_entry, LATE)],
+            vec![pending_with_base(local_entry, base, NOW)],
+        );
+        assert_eq!(AuthenticatorOperation::Merge, res[0].operation);
+        assert_eq!(vec!["Work".to_string()], res[0].entry.tags);
+        assert!(res[0].entry.favourite);
+    }
+
+    #[test]
+    fn merges_changes_on_different_fields() {
+        let id = random_id();
+        let base = totp_entry(&id, "label", "issuer", None);
+        // Renamed remotely, note edited locally
+        let remote = remote_with_entry(totp_entry(&id, "renamed", "issuer", None), LATE);
+        let local = pending_with_base(totp_entry(&id, "label", "issuer", Some("my note")), base, NOW);
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::Merge, res[0].operation);
+        assert_eq!(Some(remote.remote_id), res[0].remote_id);
+        assert_eq!(Some(1), res[0].revision);
+        assert_eq!(id, res[0].entry.id);
+        assert_eq!("renamed", res[0].entry.name());
+        assert_eq!(Some("my note".to_string()), res[0].entry.note);
+    }
+
+    #[test]
+    fn merge_takes_remote_when_only_remote_changed() {
+        let id = random_id();
+        let base = totp_entry(&id, "label", "issuer", None);
+        let remote = remote_with_entry(totp_entry(&id, "label", "new issuer", None), NOW);
+        // Local copy was touched but ends up equal to the base
+        let local = pending_with_base(base.clone(), base, LATE);
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::Upsert, res[0].operation);
+        assert_eq!(remote.entry, res[0].entry);
+    }
+
+    #[test]
+    fn merge_pushes_when_only_local_changed() {
+        let id = random_id();
+        let base = totp_entry(&id, "label", "issuer", None);
+        let remote = remote_with_entry(base.clone(), LATE);
+        let local_entry = totp_entry(&id, "label", "issuer", Some("note"));
+        let local = pending_with_base(local_entry.clone(), base, NOW);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::Push, res[0].operation);
+        assert_eq!(local_entry, res[0].entry);
+    }
+
+    #[test]
+    fn merge_reports_conflict_when_same_field_changed() {
+        let id = random_id();
+        let base = totp_entry(&id, "label", "issuer", None);
+        let remote = remote_with_entry(totp_entry(&id, "remote", "remote issuer", None), LATE);
+        let local = pending_with_base(totp_entry(&id, "local", "issuer", Some("note")), base.clone(), NOW);
+
+        // Remote is newer, so it wins the name. Non conflicting changes are kept
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local.clone()]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::Conflict, res[0].operation);
+        assert_eq!("remote", res[0].entry.name());
+        assert_eq!("remote issuer", res[0].entry.issuer());
+        assert_eq!(Some("note".to_string()), res[0].entry.note);
+
+        // Local is newer, so it wins the name
+        let local = pending_with_base(local.entry, base, VERY_LATE);
+        let res = calculate_operations_to_perform(vec![remote], vec![local]);
+        assert_eq!(AuthenticatorOperation::Conflict, res[0].operation);
+        assert_eq!("local", res[0].entry.name());
+        assert_eq!("remote issuer", res[0].entry.issuer());
+    }
+
+    #[test]
+    fn merge_does_not_conflict_when_both_sides_made_the_same_change() {
+        let id = random_id();
+        let base = totp_entry(&id, "label", "issuer", None);
+        let remote = remote_with_entry(totp_entry(&id, "renamed", "issuer", None), LATE);
+        let local = pending_with_base(totp_entry(&id, "renamed", "issuer", Some("note")), base, NOW);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![local.clone()]);
+        assert_eq!(AuthenticatorOperation::Push, res[0].operation);
+        assert_eq!(local.entry, res[0].entry);
+    }
+
+    #[test]
+    fn merges_params_independently() {
+        let id = random_id();
+        let base = totp_entry(&id, "label", "issuer", None);
+        let remote_entry = AuthenticatorEntry::from_uri_and_id(
+            "otpauth://totp/label?secret=OTHERSECRET&issuer=issuer&digits=8",
+            None,
+            id.clone(),
+        )
+        .unwrap();
+        let remote = remote_with_entry(remote_entry, LATE);
+        let local = pending_with_base(totp_entry(&id, "local", "issuer", None), base, NOW);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![local]);
+        assert_eq!(AuthenticatorOperation::Merge, res[0].operation);
+        assert_eq!("local", res[0].entry.name());
+        assert_eq!("OTHERSECRET", res[0].entry.secret());
+        assert_eq!(8, res[0].entry.get_totp_parameters().unwrap().digits);
+    }
+
+    fn tombstone(remote_id: String, entry: AuthenticatorEntry, delete_time: i64) -> RemoteEntry {
+        RemoteEntry {
+            remote_id,
+            revision: 2,
+            entry,
+            modify_time: delete_time,
+            delete_time: Some(delete_time),
+        }
+    }
+
+    #[test]
+    fn remote_tombstone_deletes_synced_local_entry() {
+        let local = local_entry_with_state(AuthenticatorEntryState::Synced);
+        let remote = tombstone(random_id(), local.entry.clone(), LATE);
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local.clone()]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::DeleteLocal, res[0].operation);
+        assert_eq!(Some(remote.remote_id), res[0].remote_id);
+        assert_eq!(local.entry, res[0].entry);
+    }
+
+    #[test]
+    fn remote_tombstone_for_unknown_entry_is_ignored() {
+        let remote = tombstone(random_id(), random_entry(), LATE);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![]);
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn remote_tombstone_deletes_pending_local_entry_without_changes() {
+        let entry = random_entry();
+        let local = local_entry_with_entry_state_and_times(
+            entry.clone(),
+            AuthenticatorEntryState::PendingSync,
+            NOW,
+            Some(LATE),
+        );
+        let remote = tombstone(random_id(), entry, VERY_LATE);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::DeleteLocal, res[0].operation);
+    }
+
+    #[test]
+    fn local_edit_before_remote_tombstone_is_reported() {
+        let entry = random_entry();
+        let edited = modify_entry(&entry);
+        // The local clock is behind, so the unsynced edit looks older than the deletion
+        let mut local = local_entry_with_entry_state_and_times(
+            edited.clone(),
+            AuthenticatorEntryState::PendingSync,
+            NOW,
+            Some(LATE),
+        );
+        local.remote_id = Some(random_id());
+        local.base = Some(entry.clone());
+        let remote = tombstone(random_id(), entry, VERY_LATE);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::RemoteDeletedLocalEdited, res[0].operation);
+        assert_eq!(edited, res[0].entry);
+    }
+
+    #[test]
+    fn local_edit_after_remote_tombstone_is_reported() {
+        let entry = random_entry();
+        let edited = modify_entry(&entry);
+        let local = local_entry_with_entry_state_and_times(
+            edited.clone(),
+            AuthenticatorEntryState::PendingSync,
+            NOW,
+            Some(VERY_LATE),
+        );
+        let remote = tombstone(random_id(), entry, LATE);
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::RemoteDeletedLocalEdited, res[0].operation);
+        assert_eq!(Some(remote.remote_id), res[0].remote_id);
+        assert_eq!(Some(2), res[0].revision);
+        assert_eq!(edited, res[0].entry);
+    }
+
+    #[test]
+    fn remote_edit_after_local_deletion_is_reported() {
+        let entry = random_entry();
+        let local = local_entry_with_entry_and_state(entry.clone(), AuthenticatorEntryState::PendingToDelete);
+        let remote = remote_with_entry(modify_entry(&entry), VERY_LATE);
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::LocalDeletedRemoteEdited, res[0].operation);
+        assert_eq!(remote.entry, res[0].entry);
+    }
+
+    #[test]
+    fn remote_edit_before_local_deletion_is_reported() {
+        // The remote edit was never synced locally, even if the deletion happened later
+        let entry = random_entry();
+        let local = local_entry_with_entry_and_state(entry.clone(), AuthenticatorEntryState::PendingToDelete);
+        let remote = remote_with_entry(modify_entry(&entry), LATE);
+
+        let res = calculate_operations_to_perform(vec![remote.clone()], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::LocalDeletedRemoteEdited, res[0].operation);
+        assert_eq!(remote.entry, res[0].entry);
+    }
+
+    #[test]
+    fn remote_touched_without_changes_after_local_deletion_deletes_everywhere() {
+        let entry = random_entry();
+        let local = local_entry_with_entry_and_state(entry.clone(), AuthenticatorEntryState::PendingToDelete);
+        let remote = remote_with_entry(entry, LATE);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![local]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::DeleteLocalAndRemote, res[0].operation);
+    }
+
+    #[test]
+    fn unchanged_remote_after_local_deletion_deletes_everywhere() {
+        let entry = random_entry();
+        let local = local_entry_with_entry_and_state(entry.clone(), AuthenticatorEntryState::PendingToDelete);
+        // Remote has the same modify time as the local copy: it was not edited
+        let remote = remote_with_entry(entry, NOW);
+
+        let res = calculate_operations_to_perform(vec![remote], vec![local]);
+        assert_eq!(AuthenticatorOperation::DeleteLocalAndRemote, res[0].operation);
+    }
+
+    #[test]
+    fn partial_listing_does_not_delete_missing_local_entries() {
+        let synced = local_entry_with_state(AuthenticatorEntryState::Synced);
+        let pending_delete = local_entry_with_state(AuthenticatorEntryState::PendingToDelete);
+        let pending_sync = local_entry_with_state(AuthenticatorEntryState::PendingSync);
+
+        let res = calculate_operations_for_partial_listing(vec![], vec![synced, pending_delete, pending_sync.clone()]);
+        assert_eq!(1, res.len());
+        assert_eq!(AuthenticatorOperation::Push, res[0].operation);
+        assert_eq!(pending_sync.entry, res[0].entry);
+    }
+
+    #[test]
+    fn partial_listing_does_not_push_offline_edits_of_unlisted_entries() {
+        let mut edited = local_entry_with_state(AuthenticatorEntryState::PendingSync);
+        edited.remote_id = Some(random_id());
+        edited.local_modify_time = Some(LATE);
+        let base = random_entry();
+        let edited_with_base = pending_with_base(random_entry(), base, LATE);
+
+        let res = calculate_operations_for_partial_listing(vec![], vec![edited.clone(), edited_with_base.clone()]);
+        assert!(res.is_empty());
+
+        // Once the complete listing confirms they are gone, they are pushed again
+        let res = calculate_operations_to_perform(vec![], vec![edited, edited_with_base]);
+        assert_eq!(2, res.len());
+        assert!(res.iter().all(|op| op.operation == AuthenticatorOperation::Push));
+    }
+
+    #[test]
+    fn partial_listing_applies_listed_entries() {
+        let local = local_entry_with_state(AuthenticatorEntryState::Synced);
+        let remote = tombstone(random_id(), local.entry.clone(), LATE);
+        let new_remote = remote_entry();
+
+        let res = calculate_operations_for_partial_listing(vec![remote, new_remote.clone()], vec![local]);
+        assert_eq!(2, res.len());
+        assert_eq!(AuthenticatorOperation::DeleteLocal, res[0].operation);
+        assert_eq!(AuthenticatorOperation::Upsert, res[1].operation);
+        assert_eq!(new_remote.entry, res[1].entry);
+    }
+}
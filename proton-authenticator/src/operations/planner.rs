@@ -0,0 +1,356 @@
+use super::{AuthenticatorEntryState, AuthenticatorOperation, EntryOperation, LocalEntry, RemoteEntry};
+use crate::ordering::{is_valid_key, key_between};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone)]
+pub struct LocalSyncEntry {
+    pub local: LocalEntry,
+    pub order_key: String,
+    /// last time `order_key` was modified locally (time-millis since Unix epoch)
+    pub order_modify_time: i64,
+}
+
+#[derive(Clone)]
+pub struct RemoteSyncEntry {
+    pub remote: RemoteEntry,
+    pub order_key: String,
+    /// last time `order_key` was modified as returned by the server
+    pub order_modify_time: i64,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct OrderUpdate {
+    pub entry_id: String,
+    pub order_key: String,
+    /// The local copy needs to store the new key
+    pub update_local: bool,
+    /// The remote copy needs to be sent the new key
+    pub update_remote: bool,
+}
+
+#[derive(Debug)]
+pub struct SyncPlan {
+    pub operations: Vec<EntryOperation>,
+    pub order_updates: Vec<OrderUpdate>,
+    /// Ids of the entries that remain after applying the plan, in display order
+    pub ordered_ids: Vec<String>,
+}
+
+struct OrderCandidate {
+    id: String,
+    key: String,
+}
+
+/// Calculates the content operations and the order updates in a single pass, so both agree on which
+/// entries survive the sync. Order conflicts are resolved by last-writer-wins on the order key, and
+/// only the entries whose key changed get an update.
+/// `complete_listing` should be false when `remote` is only a page or a filtered listing.
+pub fn plan_sync(remote: Vec<RemoteSyncEntry>, local: Vec<LocalSyncEntry>, complete_listing: bool) -> SyncPlan {
+    let local_orders: HashMap<String, (String, i64)> = local
+        .iter()
+        .map(|e| (e.local.entry.id.clone(), (e.order_key.clone(), e.order_modify_time)))
+        .collect();
+    let remote_orders: HashMap<String, (String, i64)> = remote
+        .iter()
+        .filter(|e| e.remote.delete_time.is_none())
+        .map(|e| (e.remote.entry.id.clone(), (e.order_key.clone(), e.order_modify_time)))
+        .collect();
+    let visible_local: Vec<String> = local
+        .iter()
+        .filter(|e| !matches!(e.local.state, AuthenticatorEntryState::PendingToDelete))
+        .map(|e| e.local.entry.id.clone())
+        .collect();
+
+    let operations = super::calculate_operations(
+        remote.into_iter().map(|e| e.remote).collect(),
+        local.into_iter().map(|e| e.local).collect(),
+        complete_listing,
+    );
+    let deleted: HashSet<&str> = operations
+        .iter()
+        .filter(|op| {
+            matches!(
+                op.operation,
+                AuthenticatorOperation::DeleteLocal | AuthenticatorOperation::DeleteLocalAndRemote
+            )
+        })
+        .map(|op| op.entry.id.as_str())
+        .collect();
+    let pushed: HashSet<&str> = operations
+        .iter()
+        .filter(|op| op.operation == AuthenticatorOperation::Push)
+        .map(|op| op.entry.id.as_str())
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut candidates: Vec<OrderCandidate> = visible_local
+        .iter()
+        .chain(remote_orders.keys())
+        .filter(|id| !deleted.contains(id.as_str()) && seen.insert(id.as_str()))
+        .map(|id| {
+            let key = match (local_orders.get(id), remote_orders.get(id)) {
+                (Some((local_key, local_time)), Some((_, remote_time))) if local_time > remote_time => local_key,
+                (_, Some((remote_key, _))) => remote_key,
+                (Some((local_key, _)), None) => local_key,
+                (None, None) => unreachable!("candidates come from local or remote entries"),
+            };
+            OrderCandidate {
+                id: id.clone(),
+                key: key.clone(),
+            }
+        })
+        .collect();
+
+    // Ties are broken by id, so every device ends up with the same order
+    candidates.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.id.cmp(&b.id)));
+    assign_unique_keys(&mut candidates);
+
+    let order_updates = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let update_local = local_orders.get(&candidate.id).map(|(key, _)| key) != Some(&candidate.key);
+            // The server order of entries missing from a partial listing is unknown, so it is only sent along
+            // with the entries being created
+            let update_remote = match remote_orders.get(&candidate.id) {
+                Some((key, _)) => key != &candidate.key,
+                None => complete_listing || pushed.contains(candidate.id.as_str()),
+            };
+            (update_local || update_remote).then(|| OrderUpdate {
+                entry_id: candidate.id.clone(),
+                order_key: candidate.key.clone(),
+                update_local,
+                update_remote,
+            })
+        })
+        .collect();
+
+    SyncPlan {
+        operations,
+        order_updates,
+        ordered_ids: candidates.into_iter().map(|c| c.id).collect(),
+    }
+}
+
+// Replaces duplicated or invalid keys with a key between the previous and the next usable ones
+fn assign_unique_keys(candidates: &mut [OrderCandidate]) {
+    let mut previous: Option<String> = None;
+    for idx in 0..candidates.len() {
+        let key = &candidates[idx].key;
+        let usable = is_valid_key(key) && previous.as_ref().is_none_or(|prev| prev < key);
+        if !usable {
+            let next = candidates[idx + 1..]
+                .iter()
+                .map(|c| c.key.as_str())
+                .find(|k| is_valid_key(k) && previous.as_deref().is_none_or(|prev| prev < *k));
+            candidates[idx].key =
+                key_between(previous.as_deref(), next).expect("keys are valid and sorted by construction");
+        }
+        previous = Some(candidates[idx].key.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthenticatorEntry;
+
+    const NOW: i64 = 1_700_000_000;
+    const LATE: i64 = NOW + 1_000;
+
+    fn entry(id: &str) -> AuthenticatorEntry {
+        AuthenticatorEntry::from_uri_and_id(
+            "otpauth://totp/label?secret=MYSECRET&issuer=issuer",
+            None,
+            id.to_string(),
+        )
+        .unwrap()
+    }
+
+    fn local(id: &str, state: AuthenticatorEntryState, key: &str, order_modify_time: i64) -> LocalSyncEntry {
+        LocalSyncEntry {
+            local: LocalEntry {
+                entry: entry(id),
+                state,
+                remote_id: None,
+                modify_time: NOW,
+                local_modify_time: None,
+                base: None,
+            },
+            order_key: key.to_string(),
+            order_modify_time,
+        }
+    }
+
+    fn synced(id: &str, key: &str, order_modify_time: i64) -> LocalSyncEntry {
+        local(id, AuthenticatorEntryState::Synced, key, order_modify_time)
+    }
+
+    fn remote(id: &str, key: &str, order_modify_time: i64) -> RemoteSyncEntry {
+        RemoteSyncEntry {
+            remote: RemoteEntry {
+                remote_id: format!("remote-{id}"),
+                revision: 1,
+                entry: entry(id),
+                modify_time: NOW,
+                delete_time: None,
+            },
+            order_key: key.to_string(),
+            order_modify_time,
+        }
+    }
+
+    #[test]
+    fn nothing_to_do_when_in_sync() {
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW), remote("b", "B", NOW)],
+            vec![synced("a", "A", NOW), synced("b", "B", NOW)],
+            true,
+        );
+        assert!(plan.operations.is_empty());
+        assert!(plan.order_updates.is_empty());
+        assert_eq!(vec!["a", "b"], plan.ordered_ids);
+    }
+
+    #[test]
+    fn local_move_only_updates_the_moved_entry_remotely() {
+        // "c" was moved between "a" and "b" locally
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW), remote("b", "B", NOW), remote("c", "C", NOW)],
+            vec![synced("a", "A", NOW), synced("b", "B", NOW), synced("c", "AV", LATE)],
+            true,
+        );
+        assert!(plan.operations.is_empty());
+        assert_eq!(
+            vec![OrderUpdate {
+                entry_id: "c".to_string(),
+                order_key: "AV".to_string(),
+                update_local: false,
+                update_remote: true,
+            }],
+            plan.order_updates
+        );
+        assert_eq!(vec!["a", "c", "b"], plan.ordered_ids);
+    }
+
+    #[test]
+    fn newer_remote_order_wins() {
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW), remote("b", "0V", LATE)],
+            vec![synced("a", "A", NOW), synced("b", "C", NOW)],
+            true,
+        );
+        assert_eq!(1, plan.order_updates.len());
+        assert_eq!("0V", plan.order_updates[0].order_key);
+        assert!(plan.order_updates[0].update_local);
+        assert!(!plan.order_updates[0].update_remote);
+        assert_eq!(vec!["b", "a"], plan.ordered_ids);
+    }
+
+    #[test]
+    fn new_remote_entry_is_upserted_with_its_order() {
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW), remote("b", "B", NOW)],
+            vec![synced("a", "A", NOW)],
+            true,
+        );
+        assert_eq!(1, plan.operations.len());
+        assert_eq!(AuthenticatorOperation::Upsert, plan.operations[0].operation);
+        assert_eq!(
+            vec![OrderUpdate {
+                entry_id: "b".to_string(),
+                order_key: "B".to_string(),
+                update_local: true,
+                update_remote: false,
+            }],
+            plan.order_updates
+        );
+    }
+
+    #[test]
+    fn deleted_entries_are_not_ordered() {
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW)],
+            vec![
+                synced("a", "A", NOW),
+                synced("b", "B", NOW),
+                local("c", AuthenticatorEntryState::PendingToDelete, "C", NOW),
+            ],
+            true,
+        );
+        assert_eq!(2, plan.operations.len());
+        assert!(plan.order_updates.is_empty());
+        assert_eq!(vec!["a"], plan.ordered_ids);
+    }
+
+    #[test]
+    fn partial_listing_keeps_unlisted_entries_ordered() {
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW)],
+            vec![synced("a", "A", NOW), synced("b", "0V", NOW)],
+            false,
+        );
+        assert!(plan.operations.is_empty());
+        // The server already has the order of the entries in other pages
+        assert!(plan.order_updates.is_empty());
+        assert_eq!(vec!["b", "a"], plan.ordered_ids);
+    }
+
+    #[test]
+    fn partial_listing_sends_order_of_created_entries() {
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW)],
+            vec![
+                synced("a", "A", NOW),
+                synced("b", "0V", NOW),
+                local("c", AuthenticatorEntryState::PendingSync, "V", NOW),
+            ],
+            false,
+        );
+        assert_eq!(1, plan.operations.len());
+        assert_eq!(AuthenticatorOperation::Push, plan.operations[0].operation);
+        assert_eq!(1, plan.order_updates.len());
+        assert_eq!("c", plan.order_updates[0].entry_id);
+        assert!(!plan.order_updates[0].update_local);
+        assert!(plan.order_updates[0].update_remote);
+        assert_eq!(vec!["b", "a", "c"], plan.ordered_ids);
+    }
+
+    #[test]
+    fn colliding_keys_are_made_unique() {
+        // Both devices appended an entry at the end at the same time
+        let plan = plan_sync(
+            vec![remote("a", "A", NOW), remote("x", "V", NOW)],
+            vec![
+                synced("a", "A", NOW),
+                local("w", AuthenticatorEntryState::PendingSync, "V", NOW),
+            ],
+            true,
+        );
+        assert_eq!(2, plan.operations.len());
+        assert_eq!(vec!["a", "w", "x"], plan.ordered_ids);
+
+        let w = plan.order_updates.iter().find(|u| u.entry_id == "w").unwrap();
+        assert_eq!("V", w.order_key);
+        assert!(!w.update_local);
+        assert!(w.update_remote);
+        let x = plan.order_updates.iter().find(|u| u.entry_id == "x").unwrap();
+        assert!("V" < x.order_key.as_str());
+        assert!(x.update_local);
+        assert!(x.update_remote);
+    }
+
+    #[test]
+    fn invalid_keys_are_replaced() {
+        let plan = plan_sync(
+            vec![remote("a", "", NOW), remote("b", "B", NOW)],
+            vec![synced("a", "", NOW), synced("b", "B", NOW)],
+            true,
+        );
+        assert_eq!(1, plan.order_updates.len());
+        let update = &plan.order_updates[0];
+        assert_eq!("a", update.entry_id);
+        assert!(is_valid_key(&update.order_key));
+        assert!(update.order_key.as_str() < "B");
+        assert!(update.update_local && update.update_remote);
+    }
+}
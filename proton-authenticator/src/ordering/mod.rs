@@ -1,7 +1,12 @@
-use crate::AuthenticatorEntry;
+use crate::search::fold;
+use crate::{AuthenticatorEntry, EntryUsage};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
+mod order_key;
+
+pub use order_key::{OrderKeyError, initial_keys, is_valid_key, key_between};
+
 #[derive(Clone)]
 pub struct EntryWithOrder {
     pub entry: AuthenticatorEntry,
@@ -47,6 +52,79 @@ pub fn reorder_items(local_items: &[EntryWithOrder], remote_items: &[EntryWithOr
     merged
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EntrySortMode {
+    /// Order set by the user
+    Manual,
+    /// Most recently used first. Entries that were never used go last, in manual order
+    RecentlyUsed,
+    /// By name, then issuer
+    Alphabetical,
+    /// By issuer, then name
+    Issuer,
+}
+
+/// Sorts the entries for display. Unlike [`reorder_items`], the `order` of the items is left untouched, so the manual
+/// order is preserved when switching back to [`EntrySortMode::Manual`].
+pub fn sort_entries(items: &[EntryWithOrder], usage: &[EntryUsage], mode: EntrySortMode) -> Vec<EntryWithOrder> {
+    let last_used: HashMap<&str, i64> = usage
+        .iter()
+        .filter_map(|u| u.last_used.map(|last_used| (u.entry_id.as_str(), last_used)))
+        .collect();
+
+    let mut keyed: Vec<(SortKey, &EntryWithOrder)> = items
+        .iter()
+        .map(|item| {
+            let key = match mode {
+                EntrySortMode::Manual => SortKey::Manual,
+                EntrySortMode::RecentlyUsed => SortKey::RecentlyUsed(last_used.get(item.entry.id.as_str()).copied()),
+                EntrySortMode::Alphabetical => SortKey::Text(fold(&item.entry.name()), fold(&item.entry.issuer())),
+                EntrySortMode::Issuer => SortKey::Text(fold(&item.entry.issuer()), fold(&item.entry.name())),
+            };
+            (key, item)
+        })
+        .collect();
+
+    keyed.sort_by(|(a_key, a), (b_key, b)| {
+        a_key
+            .cmp(b_key)
+            .then_with(|| a.order.cmp(&b.order))
+            .then_with(|| a.modify_time.cmp(&b.modify_time))
+    });
+
+    keyed.into_iter().map(|(_, item)| item.clone()).collect()
+}
+
+#[derive(Eq, PartialEq)]
+enum SortKey {
+    Manual,
+    RecentlyUsed(Option<i64>),
+    Text(String, String),
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            // Most recent first, never used last
+            (SortKey::RecentlyUsed(a), SortKey::RecentlyUsed(b)) => match (a, b) {
+                (Some(a), Some(b)) => b.cmp(a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            (SortKey::Text(a1, a2), SortKey::Text(b1, b2)) => a1.cmp(b1).then_with(|| a2.cmp(b2)),
+            // All the keys of a sort share the same variant
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,6 +222,58 @@ mod tests {
         assert_continuous_order(&result);
     }
 
+    fn make_named(id: &str, issuer: &str, label: &str, order: i32) -> EntryWithOrder {
+        let uri = format!("otpauth://totp/{label}?secret=MYSECRET&issuer={issuer}");
+        EntryWithOrder {
+            entry: AuthenticatorEntry::from_uri_and_id(&uri, None, id.to_string()).unwrap(),
+            order,
+            modify_time: 0,
+        }
+    }
+
+    fn ids(items: &[EntryWithOrder]) -> Vec<&str> {
+        items.iter().map(|item| item.entry.id.as_str()).collect()
+    }
+
+    #[test]
+    fn sorts_entries_by_mode() {
+        let items = vec![
+            make_named("a", "Github", "zed", 2),
+            make_named("b", "amazon", "Bob", 0),
+            make_named("c", "Dropbox", "alice", 1),
+            make_named("d", "Ébay", "carol", 3),
+        ];
+        let usage = vec![
+            EntryUsage {
+                entry_id: "d".to_string(),
+                last_used: Some(100),
+                use_count: 1,
+            },
+            EntryUsage {
+                entry_id: "a".to_string(),
+                last_used: Some(200),
+                use_count: 4,
+            },
+            EntryUsage::new("c".to_string()),
+        ];
+
+        let manual = sort_entries(&items, &usage, EntrySortMode::Manual);
+        assert_eq!(vec!["b", "c", "a", "d"], ids(&manual));
+
+        // Never used entries keep their manual order after the used ones
+        let recent = sort_entries(&items, &usage, EntrySortMode::RecentlyUsed);
+        assert_eq!(vec!["a", "d", "b", "c"], ids(&recent));
+
+        let alphabetical = sort_entries(&items, &usage, EntrySortMode::Alphabetical);
+        assert_eq!(vec!["c", "b", "d", "a"], ids(&alphabetical));
+
+        let issuer = sort_entries(&items, &usage, EntrySortMode::Issuer);
+        assert_eq!(vec!["b", "c", "d", "a"], ids(&issuer));
+
+        // The manual order is not rewritten
+        assert_eq!(2, recent[0].order);
+    }
+
     // Remote-only items must remain unchanged except for order.
     #[test]
     fn handles_remote_only_items() {
@@ -0,0 +1,201 @@
+//! Fractional order keys.
+//!
+//! Entries are sorted by comparing their keys as plain strings, and a new key can always be generated
+//! between any two existing keys. Moving an entry only needs a new key for that entry, instead of
+//! rewriting the order of every entry after it.
+
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const BASE: usize = DIGITS.len();
+
+#[derive(Clone, Debug, proton_pass_derive::Error)]
+pub enum OrderKeyError {
+    InvalidKey(String),
+    KeysNotOrdered,
+}
+
+/// Generates a key that sorts strictly between `before` and `after`.
+/// `None` stands for the start of the list for `before` and for the end of the list for `after`.
+pub fn key_between(before: Option<&str>, after: Option<&str>) -> Result<String, OrderKeyError> {
+    if let Some(key) = before {
+        validate(key)?;
+    }
+    if let Some(key) = after {
+        validate(key)?;
+    }
+    if let (Some(before), Some(after)) = (before, after)
+        && before >= after
+    {
+        return Err(OrderKeyError::KeysNotOrdered);
+    }
+
+    let before = before.map(|k| k.as_bytes()).unwrap_or_default();
+    let key = midpoint(before, after.map(|k| k.as_bytes()));
+    Ok(String::from_utf8(key).expect("order keys only contain ASCII digits"))
+}
+
+/// Generates `count` evenly spaced keys, all with the same length.
+/// Used to assign keys to a whole list at once, e.g. when migrating from numeric orders.
+pub fn initial_keys(count: usize) -> Vec<String> {
+    let mut width = 1;
+    let mut capacity = BASE;
+    while capacity <= count {
+        width += 1;
+        capacity = capacity.saturating_mul(BASE);
+    }
+
+    let step = capacity / (count + 1);
+    (1..=count).map(|idx| encode(idx * step, width)).collect()
+}
+
+fn encode(mut value: usize, width: usize) -> String {
+    let mut digits = vec![DIGITS[0]; width];
+    for digit in digits.iter_mut().rev() {
+        *digit = DIGITS[value % BASE];
+        value /= BASE;
+    }
+    // Trailing zeros don't change the position of the key, and a valid key must not end with one
+    while digits.len() > 1 && digits.last() == Some(&DIGITS[0]) {
+        digits.pop();
+    }
+    String::from_utf8(digits).expect("order keys only contain ASCII digits")
+}
+
+/// Whether the key can be used as an order key
+pub fn is_valid_key(key: &str) -> bool {
+    validate(key).is_ok()
+}
+
+fn validate(key: &str) -> Result<(), OrderKeyError> {
+    let valid = !key.is_empty() && key.bytes().all(|b| DIGITS.contains(&b)) && !key.ends_with(DIGITS[0] as char);
+    if valid {
+        Ok(())
+    } else {
+        Err(OrderKeyError::InvalidKey(key.to_string()))
+    }
+}
+
+fn digit_value(digit: u8) -> usize {
+    DIGITS.iter().position(|d| *d == digit).unwrap_or_default()
+}
+
+// `before` < `after`, `after` == None means "end of the list", neither of them ends with a zero digit
+fn midpoint(before: &[u8], after: Option<&[u8]>) -> Vec<u8> {
+    if let Some(after) = after {
+        // Skip the common prefix, padding `before` with zeros
+        let common = after
+            .iter()
+            .enumerate()
+            .take_while(|(idx, digit)| before.get(*idx).copied().unwrap_or(DIGITS[0]) == **digit)
+            .count();
+        if common > 0 {
+            let mut key = after[..common].to_vec();
+            let before = before.get(common..).unwrap_or_default();
+            key.extend(midpoint(before, Some(&after[common..])));
+            return key;
+        }
+    }
+
+    let digit_before = before.first().map(|d| digit_value(*d)).unwrap_or(0);
+    let digit_after = after.map(|a| digit_value(a[0])).unwrap_or(BASE);
+    if digit_after - digit_before > 1 {
+        return vec![DIGITS[(digit_before + digit_after).div_ceil(2)]];
+    }
+
+    match after {
+        // The first digit of `after` is enough, as it's followed by more digits
+        Some(after) if after.len() > 1 => vec![after[0]],
+        _ => {
+            let mut key = vec![DIGITS[digit_before]];
+            key.extend(midpoint(before.get(1..).unwrap_or_default(), None));
+            key
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn between(before: Option<&str>, after: Option<&str>) -> String {
+        let key = key_between(before, after).unwrap();
+        if let Some(before) = before {
+            assert!(before < key.as_str(), "{before} should be before {key}");
+        }
+        if let Some(after) = after {
+            assert!(key.as_str() < after, "{key} should be before {after}");
+        }
+        key
+    }
+
+    #[test]
+    fn generates_key_for_empty_list() {
+        assert_eq!("V", between(None, None));
+    }
+
+    #[test]
+    fn generates_keys_at_the_edges() {
+        between(None, Some("V"));
+        between(Some("V"), None);
+        between(None, Some("01"));
+        between(Some("zzz"), None);
+    }
+
+    #[test]
+    fn generates_keys_between_adjacent_keys() {
+        between(Some("A"), Some("B"));
+        between(Some("A"), Some("A1"));
+        between(Some("A1"), Some("A2"));
+        between(Some("Az"), Some("B"));
+        between(Some("A"), Some("B01"));
+    }
+
+    #[test]
+    fn repeated_insertions_keep_order() {
+        let mut keys = vec![between(None, None)];
+        // Always insert right after the first key, the worst case for key growth
+        for _ in 0..200 {
+            let key = between(Some(&keys[0]), keys.get(1).map(|k| k.as_str()));
+            keys.insert(1, key);
+        }
+        let mut sorted = keys.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(keys, sorted);
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        assert!(matches!(key_between(Some(""), None), Err(OrderKeyError::InvalidKey(_))));
+        assert!(matches!(
+            key_between(Some("A0"), None),
+            Err(OrderKeyError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            key_between(None, Some("A-")),
+            Err(OrderKeyError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            key_between(Some("B"), Some("A")),
+            Err(OrderKeyError::KeysNotOrdered)
+        ));
+        assert!(matches!(
+            key_between(Some("B"), Some("B")),
+            Err(OrderKeyError::KeysNotOrdered)
+        ));
+    }
+
+    #[test]
+    fn initial_keys_are_sorted_and_valid() {
+        for count in [0, 1, 2, 61, 62, 63, 1000] {
+            let keys = initial_keys(count);
+            assert_eq!(count, keys.len());
+            for window in keys.windows(2) {
+                assert!(window[0] < window[1]);
+            }
+            for key in &keys {
+                validate(key).unwrap();
+                assert!(key.len() <= 2);
+            }
+        }
+    }
+}
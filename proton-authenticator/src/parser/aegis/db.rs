@@ -5,6 +5,7 @@ use crate::steam::SteamTotp;
 use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
 use proton_pass_totp::algorithm::Algorithm;
 use proton_pass_totp::totp::TOTP;
+use std::collections::HashMap;
 
 #[derive(Clone, serde::Deserialize, serde::Serialize)]
 pub struct TotpInfo {
@@ -75,10 +76,18 @@ pub enum DbEntry {
     },
 }
 
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct AegisGroup {
+    pub uuid: String,
+    pub name: String,
+}
+
 #[derive(Clone, serde::Deserialize, serde::Serialize)]
 pub struct AegisDbRoot {
     pub version: i64,
     pub entries: Vec<DbEntry>,
+    #[serde(default)]
+    pub groups: Vec<AegisGroup>,
 }
 
 #[derive(Clone, serde::Deserialize, serde::Serialize)]
@@ -133,12 +142,15 @@ impl TryFrom<TotpEntry> for AuthenticatorEntry {
         } else {
             Some(trimmed_note.to_string())
         };
+        let favourite = entry.favorite;
         let totp = TOTP::try_from(entry)?;
 
         Ok(AuthenticatorEntry {
             content: AuthenticatorEntryContent::Totp(totp),
             note,
             id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite,
         })
     }
 }
@@ -166,17 +178,28 @@ impl TryFrom<SteamEntry> for AuthenticatorEntry {
             content: AuthenticatorEntryContent::Steam(steam),
             note,
             id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite: entry.favorite,
         })
     }
 }
 
+// Entries reference their groups by uuid
+fn group_names(groups: &HashMap<String, String>, uuids: &[String]) -> Vec<String> {
+    uuids.iter().filter_map(|uuid| groups.get(uuid).cloned()).collect()
+}
+
 pub fn parse_aegis_db(db: AegisDbRoot) -> Result<ImportResult, AegisImportError> {
+    let groups: HashMap<String, String> = db.groups.into_iter().map(|g| (g.uuid, g.name)).collect();
     let mut entries = Vec::new();
     let mut errors = Vec::new();
     for (idx, entry) in db.entries.into_iter().enumerate() {
         match entry {
             DbEntry::Totp { entry: totp } => match AuthenticatorEntry::try_from(totp.clone()) {
-                Ok(entry) => entries.push(entry),
+                Ok(mut entry) => {
+                    entry.set_tags(group_names(&groups, &totp.groups));
+                    entries.push(entry)
+                }
                 Err(e) => {
                     warn!("Error importing entry {}: {:?}", totp.name, e);
                     errors.push(ImportError {
@@ -186,7 +209,10 @@ pub fn parse_aegis_db(db: AegisDbRoot) -> Result<ImportResult, AegisImportError>
                 }
             },
             DbEntry::Steam { entry: steam } => match AuthenticatorEntry::try_from(steam.clone()) {
-                Ok(entry) => entries.push(entry),
+                Ok(mut entry) => {
+                    entry.set_tags(group_names(&groups, &steam.groups));
+                    entries.push(entry)
+                }
                 Err(e) => {
                     warn!("Error importing entry {}: {:?}", steam.name, e);
                     errors.push(ImportError {
@@ -5,6 +5,7 @@ use aes_gcm::aead::consts::U16;
 use aes_gcm::aead::{AeadInPlace, KeyInit, generic_array::GenericArray};
 use base64::Engine;
 use scrypt::{Params as ScryptParams, scrypt};
+use zeroize::Zeroizing;
 
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct KeyParams {
@@ -60,8 +61,8 @@ pub fn decrypt_aegis_encrypted_backup(input: &str, password: &str) -> Result<Aeg
     })?;
 
     // Our derived key length should be 32 bytes for AES-256.
-    let mut derived_key = [0u8; 32];
-    scrypt(password.as_bytes(), &salt_bytes, &params, &mut derived_key).map_err(|e| {
+    let mut derived_key = Zeroizing::new([0u8; 32]);
+    scrypt(password.as_bytes(), &salt_bytes, &params, derived_key.as_mut_slice()).map_err(|e| {
         warn!("Error creating scrypt key: {e:?}");
         AegisImportError::BadPassword
     })?;
@@ -88,10 +89,10 @@ pub fn decrypt_aegis_encrypted_backup(input: &str, password: &str) -> Result<Aeg
     let slot_tag: GenericArray<u8, U16> = GenericArray::clone_from_slice(&slot_tag_bytes);
 
     // Create the AES-256-GCM instance from the derived key
-    let cipher = Aes256Gcm::new(GenericArray::from_slice(&derived_key));
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(derived_key.as_slice()));
 
     // Copy encrypted bytes into a buffer we can decrypt in place
-    let mut master_key_ciphertext = encrypted_master_key.clone();
+    let mut master_key_ciphertext = Zeroizing::new(encrypted_master_key.clone());
 
     // Decrypt in place, providing the tag separately
     cipher
@@ -99,7 +100,7 @@ pub fn decrypt_aegis_encrypted_backup(input: &str, password: &str) -> Result<Aeg
             slot_nonce,
             // optional associated data:
             b"",
-            &mut master_key_ciphertext,
+            master_key_ciphertext.as_mut_slice(),
             aes_gcm::Tag::from_slice(&slot_tag),
         )
         .map_err(|e| {
@@ -130,17 +131,17 @@ pub fn decrypt_aegis_encrypted_backup(input: &str, password: &str) -> Result<Aeg
     let db_tag: GenericArray<u8, U16> = GenericArray::clone_from_slice(&db_tag_bytes);
 
     // 5.3 Create a new AES-256-GCM instance, but this time with the decrypted “master key”:
-    let master_key = &master_key_ciphertext; // from step 4
+    let master_key = master_key_ciphertext.as_slice(); // from step 4
     let db_cipher = Aes256Gcm::new(GenericArray::from_slice(master_key));
 
     // Copy the ciphertext to a mutable buffer for in-place decryption
-    let mut db_ciphertext_mut = db_ciphertext.clone();
+    let mut db_ciphertext_mut = Zeroizing::new(db_ciphertext.clone());
 
     db_cipher
         .decrypt_in_place_detached(
             db_nonce,
             b"", // no additional authenticated data
-            &mut db_ciphertext_mut,
+            db_ciphertext_mut.as_mut_slice(),
             aes_gcm::Tag::from_slice(&db_tag),
         )
         .map_err(|e| {
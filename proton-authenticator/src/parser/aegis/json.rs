@@ -56,6 +56,21 @@ mod test {
         assert_eq!(res.errors.len(), 0);
     }
 
+    #[test]
+    fn can_import_groups_and_favourites() {
+        let contents = get_file_contents("aegis/aegis-json-unencrypted-with-groups.json");
+        let res = parse_aegis_json(&contents, None).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(3, res.entries.len());
+
+        assert_eq!(vec!["Work".to_string(), "Cloud".to_string()], res.entries[0].tags);
+        assert!(res.entries[0].favourite);
+        // Unknown group uuids are ignored
+        assert_eq!(vec!["Cloud".to_string()], res.entries[1].tags);
+        assert!(!res.entries[1].favourite);
+        assert!(res.entries[2].tags.is_empty());
+    }
+
     #[test]
     fn steam_entry_imported_from_aegis_json_generates_correct_code() {
         let contents = get_file_contents("aegis/aegis-json-unencrypted.json");
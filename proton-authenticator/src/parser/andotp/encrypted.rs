@@ -0,0 +1,84 @@
+use super::AndOtpImportError;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hmac::Hmac;
+use pbkdf2::pbkdf2;
+use sha1::Sha1;
+use zeroize::Zeroizing;
+
+// Header layout: [iterations: u32 BE][salt: 12 bytes][nonce: 12 bytes][ciphertext + 16 bytes GCM tag]
+const ITERATIONS_LENGTH: usize = 4;
+const SALT_LENGTH: usize = 12;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+
+// andOTP uses 140k-160k iterations. Anything way above that is most likely a corrupted file
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+pub fn decrypt_andotp_backup(input: &[u8], password: &str) -> Result<Zeroizing<Vec<u8>>, AndOtpImportError> {
+    let header_length = ITERATIONS_LENGTH + SALT_LENGTH + NONCE_LENGTH;
+    if input.len() < header_length + TAG_LENGTH {
+        warn!("andOTP encrypted backup is too short");
+        return Err(AndOtpImportError::BadContent);
+    }
+
+    let (iterations_bytes, rest) = input.split_at(ITERATIONS_LENGTH);
+    let (salt, rest) = rest.split_at(SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+
+    let mut iterations_buf = [0u8; ITERATIONS_LENGTH];
+    iterations_buf.copy_from_slice(iterations_bytes);
+    let iterations = u32::from_be_bytes(iterations_buf);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        warn!("andOTP encrypted backup has an invalid iteration count: {iterations}");
+        return Err(AndOtpImportError::BadContent);
+    }
+
+    let mut derived_key = Zeroizing::new([0u8; KEY_LENGTH]);
+    pbkdf2::<Hmac<Sha1>>(password.as_bytes(), salt, iterations, derived_key.as_mut_slice()).map_err(|e| {
+        warn!("Error deriving andOTP backup key: {e:?}");
+        AndOtpImportError::UnableToDecrypt
+    })?;
+
+    let cipher = Aes256Gcm::new_from_slice(derived_key.as_slice()).map_err(|e| {
+        warn!("Error creating Aes256Gcm: {e:?}");
+        AndOtpImportError::UnableToDecrypt
+    })?;
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map(Zeroizing::new)
+        .map_err(|e| {
+            warn!("Error decrypting andOTP encrypted backup: {e:?}");
+            AndOtpImportError::BadPassword
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents_raw;
+
+    #[test]
+    fn can_decrypt_backup() {
+        let input = get_file_contents_raw("andotp/andotp_accounts.json.aes");
+        let decrypted = decrypt_andotp_backup(&input, "test").expect("should be able to decrypt");
+        let as_str = std::str::from_utf8(&decrypted).expect("should be utf8");
+        assert!(as_str.starts_with('['));
+    }
+
+    #[test]
+    fn invalid_password_returns_error() {
+        let input = get_file_contents_raw("andotp/andotp_accounts.json.aes");
+        let err = decrypt_andotp_backup(&input, "invalid").expect_err("should not be able to decrypt");
+        assert!(matches!(err, AndOtpImportError::BadPassword));
+    }
+
+    #[test]
+    fn truncated_backup_returns_error() {
+        let input = get_file_contents_raw("andotp/andotp_accounts.json.aes");
+        let err = decrypt_andotp_backup(&input[..30], "test").expect_err("should not be able to decrypt");
+        assert!(matches!(err, AndOtpImportError::BadContent));
+    }
+}
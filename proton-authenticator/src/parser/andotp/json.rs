@@ -0,0 +1,277 @@
+use super::AndOtpImportError;
+use super::encrypted::decrypt_andotp_backup;
+use crate::parser::validation::{validate_digits, validate_period};
+use crate::parser::{ImportError, ImportResult};
+use crate::steam::SteamTotp;
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use proton_pass_totp::algorithm::Algorithm;
+use proton_pass_totp::totp::TOTP;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+struct AndOtpEntry {
+    secret: String,
+    #[serde(default)]
+    issuer: Option<String>,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    digits: Option<u32>,
+    #[serde(rename = "type")]
+    otp_type: String,
+    #[serde(default)]
+    algorithm: Option<String>,
+    #[serde(default)]
+    period: Option<u32>,
+}
+
+impl AndOtpEntry {
+    fn display_name(&self) -> String {
+        match (&self.issuer, &self.label) {
+            (Some(issuer), _) if !issuer.is_empty() => issuer.to_string(),
+            (_, Some(label)) => label.to_string(),
+            _ => "".to_string(),
+        }
+    }
+
+    // Older andOTP versions did not have an issuer field and stored `Issuer:label` as the label
+    fn issuer_and_label(&self) -> (String, String) {
+        let label = self.label.clone().unwrap_or_default().trim().to_string();
+        match &self.issuer {
+            Some(issuer) if !issuer.trim().is_empty() => {
+                let label = if label.is_empty() {
+                    issuer.trim().to_string()
+                } else {
+                    label
+                };
+                (issuer.trim().to_string(), label)
+            }
+            _ => match label.split_once(':') {
+                Some((issuer, account)) if !issuer.trim().is_empty() && !account.trim().is_empty() => {
+                    (issuer.trim().to_string(), account.trim().to_string())
+                }
+                // Label as fallback issuer
+                _ => (label.clone(), label),
+            },
+        }
+    }
+}
+
+impl TryFrom<AndOtpEntry> for AuthenticatorEntry {
+    type Error = AndOtpImportError;
+
+    fn try_from(entry: AndOtpEntry) -> Result<Self, Self::Error> {
+        let content = match entry.otp_type.to_uppercase().as_str() {
+            "TOTP" => {
+                let name = entry.display_name();
+                let digits = entry.digits.unwrap_or(6);
+                let period = entry.period.unwrap_or(30);
+
+                if let Some(digits_error) = validate_digits(digits, &name) {
+                    return Err(AndOtpImportError::InvalidConfig(digits_error));
+                }
+
+                if let Some(period_error) = validate_period(period, &name) {
+                    return Err(AndOtpImportError::InvalidConfig(period_error));
+                }
+
+                let algorithm = match &entry.algorithm {
+                    Some(algo) => match Algorithm::try_from(algo.as_str()) {
+                        Ok(a) => Some(a),
+                        Err(_) => {
+                            warn!("Unsupported algorithm for andOTP entry: {algo}");
+                            return Err(AndOtpImportError::Unsupported);
+                        }
+                    },
+                    None => None,
+                };
+
+                let (issuer, label) = entry.issuer_and_label();
+                AuthenticatorEntryContent::Totp(TOTP {
+                    label: Some(label),
+                    secret: entry.secret,
+                    issuer: Some(issuer),
+                    algorithm,
+                    digits: Some(digits as u8),
+                    period: Some(period as u16),
+                })
+            }
+            "STEAM" => {
+                let mut steam = SteamTotp::new(&entry.secret).map_err(|e| {
+                    warn!("Error parsing andOTP Steam secret: {e:?}");
+                    AndOtpImportError::BadContent
+                })?;
+                let (_, label) = entry.issuer_and_label();
+                if !label.is_empty() {
+                    steam.set_name(Some(label));
+                }
+                AuthenticatorEntryContent::Steam(steam)
+            }
+            other => {
+                // Can be a HOTP or MOTP entry
+                warn!("Unsupported andOTP entry type: {other}");
+                return Err(AndOtpImportError::Unsupported);
+            }
+        };
+
+        Ok(AuthenticatorEntry {
+            content,
+            note: None,
+            id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite: false,
+        })
+    }
+}
+
+pub fn parse_andotp_json(input: &str) -> Result<ImportResult, AndOtpImportError> {
+    let parsed: Vec<serde_json::Value> = serde_json::from_str(input).map_err(|e| {
+        warn!("Error parsing andOTP backup: {e:?}");
+        AndOtpImportError::BadContent
+    })?;
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, value) in parsed.into_iter().enumerate() {
+        let entry = match serde_json::from_value::<AndOtpEntry>(value) {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(ImportError {
+                    context: format!("Error parsing entry {idx}"),
+                    message: format!("Malformed entry: {e}"),
+                });
+                continue;
+            }
+        };
+
+        let name = entry.display_name();
+        match AuthenticatorEntry::try_from(entry) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error parsing entry {idx}"),
+                message: format!("Error parsing entry {name}: {e:?}"),
+            }),
+        }
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+/// Parses either a plain andOTP JSON backup or an encrypted `.json.aes` one.
+pub fn parse_andotp_file(input: &[u8], password: Option<String>) -> Result<ImportResult, AndOtpImportError> {
+    if serde_json::from_slice::<serde_json::Value>(input).is_ok() {
+        let as_str = std::str::from_utf8(input).map_err(|_| AndOtpImportError::BadContent)?;
+        return parse_andotp_json(as_str);
+    }
+
+    let password = match password {
+        Some(password) => password,
+        None => {
+            warn!("Tried to import andOTP encrypted backup without a password");
+            return Err(AndOtpImportError::MissingPassword);
+        }
+    };
+
+    let decrypted = decrypt_andotp_backup(input, &password)?;
+    let decrypted_str = std::str::from_utf8(&decrypted).map_err(|e| {
+        warn!("Error reading decrypted andOTP backup: {e:?}");
+        AndOtpImportError::BadContent
+    })?;
+
+    parse_andotp_json(decrypted_str)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{get_file_contents, get_file_contents_raw};
+
+    fn check_accounts(entries: &[AuthenticatorEntry]) {
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!("Proton", entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", entries[0].name());
+
+        assert_eq!("Amazon", entries[1].issuer());
+        assert_eq!("some@account.test", entries[1].name());
+        let params = entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA256, params.algorithm);
+        assert_eq!(8, params.digits);
+        assert_eq!(15, params.period);
+
+        assert!(matches!(entries[2].content, AuthenticatorEntryContent::Steam(_)));
+        assert_eq!("SteamAccount", entries[2].name());
+
+        // Legacy label with issuer prefix
+        assert_eq!("Legacy", entries[3].issuer());
+        assert_eq!("legacy@test.email", entries[3].name());
+    }
+
+    #[test]
+    fn can_import_plain_backup() {
+        let input = get_file_contents("andotp/andotp_accounts.json");
+        let res = parse_andotp_json(&input).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        check_accounts(&res.entries);
+    }
+
+    #[test]
+    fn can_import_plain_backup_as_file() {
+        let input = get_file_contents_raw("andotp/andotp_accounts.json");
+        let res = parse_andotp_file(&input, None).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        check_accounts(&res.entries);
+    }
+
+    #[test]
+    fn can_import_encrypted_backup() {
+        let input = get_file_contents_raw("andotp/andotp_accounts.json.aes");
+        let password = get_file_contents("andotp/password");
+        let res = parse_andotp_file(&input, Some(password)).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        check_accounts(&res.entries);
+    }
+
+    #[test]
+    fn steam_entry_generates_correct_code() {
+        let input = get_file_contents("andotp/andotp_accounts.json");
+        let res = parse_andotp_json(&input).expect("should be able to parse");
+
+        let code = crate::AuthenticatorClient::generate_code(&res.entries[2], 1742298317).expect("should generate");
+        assert_eq!("VKFDN", code.current_code);
+    }
+
+    #[test]
+    fn encrypted_backup_without_password_returns_error() {
+        let input = get_file_contents_raw("andotp/andotp_accounts.json.aes");
+        let err = parse_andotp_file(&input, None).expect_err("should return an error");
+        assert!(matches!(err, AndOtpImportError::MissingPassword));
+    }
+
+    #[test]
+    fn encrypted_backup_with_wrong_password_returns_error() {
+        let input = get_file_contents_raw("andotp/andotp_accounts.json.aes");
+        let err = parse_andotp_file(&input, Some("invalid".to_string())).expect_err("should return an error");
+        assert!(matches!(err, AndOtpImportError::BadPassword));
+    }
+
+    #[test]
+    fn skips_unsupported_entries() {
+        let input = get_file_contents("andotp/andotp_with_unsupported_entries.json");
+        let res = parse_andotp_json(&input).expect("should be able to parse");
+
+        assert_eq!(res.entries.len(), 1);
+        assert_eq!("Valid", res.entries[0].issuer());
+
+        assert_eq!(res.errors.len(), 4);
+        assert!(res.errors[0].message.contains("Unsupported"));
+        assert!(res.errors[1].message.contains("Unsupported"));
+        assert!(res.errors[2].message.contains("digits"));
+        assert!(res.errors[3].message.contains("Malformed"));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_andotp_json(r#"{"entries": []}"#).expect_err("should fail");
+        assert!(matches!(err, AndOtpImportError::BadContent));
+    }
+}
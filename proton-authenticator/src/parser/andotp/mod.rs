@@ -0,0 +1,29 @@
+use crate::parser::ThirdPartyImportError;
+
+mod encrypted;
+mod json;
+
+#[derive(Clone, Debug)]
+pub enum AndOtpImportError {
+    BadContent,
+    Unsupported,
+    BadPassword,
+    MissingPassword,
+    UnableToDecrypt,
+    InvalidConfig(String),
+}
+
+impl From<AndOtpImportError> for ThirdPartyImportError {
+    fn from(e: AndOtpImportError) -> Self {
+        match e {
+            AndOtpImportError::BadContent => Self::BadContent,
+            AndOtpImportError::Unsupported => Self::BadContent,
+            AndOtpImportError::BadPassword => Self::BadPassword,
+            AndOtpImportError::MissingPassword => Self::MissingPassword,
+            AndOtpImportError::UnableToDecrypt => Self::DecryptionFailed,
+            AndOtpImportError::InvalidConfig(_) => Self::BadContent,
+        }
+    }
+}
+
+pub use json::{parse_andotp_file, parse_andotp_json};
@@ -0,0 +1,332 @@
+use crate::parser::authy::AuthyImportError;
+use crate::parser::validation::validate_digits;
+use crate::parser::{ImportError, ImportResult};
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use proton_pass_totp::algorithm::Algorithm;
+use proton_pass_totp::totp::TOTP;
+
+// Authy tokens are regular otpauth accounts. Exports always carry `digits` for them, so the fallback
+// is only used for hand-made dumps and mirrors the otpauth URI default rather than the Authy app one
+const TOKEN_DEFAULT_DIGITS: u32 = 6;
+const TOKEN_PERIOD: u16 = 30;
+
+// Authy apps (Twitch, SendGrid...) use hex seeds, 7 digits and a 10 second period
+const APP_DEFAULT_DIGITS: u32 = 7;
+const APP_PERIOD: u16 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AuthyEntryKind {
+    Token,
+    App,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+struct AuthyToken {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(rename = "originalName", alias = "original_name", default)]
+    original_name: Option<String>,
+    #[serde(rename = "decryptedSeed", alias = "decrypted_seed", default)]
+    decrypted_seed: Option<String>,
+    #[serde(default)]
+    digits: Option<u32>,
+    #[serde(default)]
+    issuer: Option<String>,
+    #[serde(rename = "accountType", alias = "account_type", default)]
+    account_type: Option<String>,
+}
+
+impl AuthyToken {
+    fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| self.original_name.clone())
+            .unwrap_or_default()
+    }
+
+    fn kind(&self, default: AuthyEntryKind) -> AuthyEntryKind {
+        match self.account_type.as_deref() {
+            Some("authy") => AuthyEntryKind::App,
+            _ => default,
+        }
+    }
+
+    fn issuer_and_label(&self) -> (String, String) {
+        let name = non_empty(self.name.clone());
+        let original_name = non_empty(self.original_name.clone());
+
+        // Authy keeps the otpauth label as `originalName`, which usually has the `Issuer:account` shape
+        let (split_issuer, split_label) = match original_name.as_deref().or(name.as_deref()) {
+            Some(value) => match value.split_once(':') {
+                Some((issuer, label)) if !issuer.trim().is_empty() && !label.trim().is_empty() => {
+                    (Some(issuer.trim().to_string()), Some(label.trim().to_string()))
+                }
+                _ => (None, Some(value.trim().to_string())),
+            },
+            None => (None, None),
+        };
+
+        let label = split_label.unwrap_or_default();
+        let issuer = non_empty(self.issuer.clone())
+            .or(split_issuer)
+            .or(name)
+            .unwrap_or_else(|| label.clone());
+
+        // Name as fallback label
+        if label.is_empty() {
+            (issuer.clone(), issuer)
+        } else {
+            (issuer, label)
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn normalize_seed(seed: &str, kind: AuthyEntryKind) -> Result<String, AuthyImportError> {
+    let cleaned: String = seed.chars().filter(|c| !c.is_whitespace()).collect();
+    if cleaned.is_empty() {
+        return Err(AuthyImportError::MissingSeed);
+    }
+
+    match kind {
+        AuthyEntryKind::Token => Ok(cleaned.to_uppercase()),
+        AuthyEntryKind::App => {
+            let raw = hex::decode(&cleaned).map_err(|e| {
+                warn!("Error decoding Authy app seed: {e:?}");
+                AuthyImportError::InvalidSeed
+            })?;
+            Ok(base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &raw))
+        }
+    }
+}
+
+fn parse_token(token: AuthyToken, kind: AuthyEntryKind) -> Result<AuthenticatorEntry, AuthyImportError> {
+    let kind = token.kind(kind);
+    let seed = match &token.decrypted_seed {
+        Some(seed) => normalize_seed(seed, kind)?,
+        None => return Err(AuthyImportError::MissingSeed),
+    };
+
+    let (default_digits, period) = match kind {
+        AuthyEntryKind::Token => (TOKEN_DEFAULT_DIGITS, TOKEN_PERIOD),
+        AuthyEntryKind::App => (APP_DEFAULT_DIGITS, APP_PERIOD),
+    };
+    let digits = token.digits.unwrap_or(default_digits);
+    if let Some(digits_error) = validate_digits(digits, &token.display_name()) {
+        return Err(AuthyImportError::InvalidConfig(digits_error));
+    }
+
+    let (issuer, label) = token.issuer_and_label();
+    Ok(AuthenticatorEntry {
+        content: AuthenticatorEntryContent::Totp(TOTP {
+            label: Some(label),
+            secret: seed,
+            issuer: Some(issuer),
+            algorithm: Some(Algorithm::SHA1),
+            digits: Some(digits as u8),
+            period: Some(period),
+        }),
+        note: None,
+        id: AuthenticatorEntry::generate_id(),
+        tags: Vec::new(),
+        favourite: false,
+    })
+}
+
+fn parse_list(
+    values: Vec<serde_json::Value>,
+    kind: AuthyEntryKind,
+    entries: &mut Vec<AuthenticatorEntry>,
+    errors: &mut Vec<ImportError>,
+) {
+    let context_name = match kind {
+        AuthyEntryKind::Token => "entry",
+        AuthyEntryKind::App => "app",
+    };
+
+    for (idx, value) in values.into_iter().enumerate() {
+        let token = match serde_json::from_value::<AuthyToken>(value) {
+            Ok(token) => token,
+            Err(e) => {
+                warn!("Error parsing Authy {context_name} {idx}: {e:?}");
+                errors.push(ImportError {
+                    context: format!("Error in {context_name} {idx}"),
+                    message: format!("Malformed {context_name}: {e}"),
+                });
+                continue;
+            }
+        };
+
+        let name = token.display_name();
+        match parse_token(token, kind) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in {context_name} {idx}"),
+                message: format!("Error importing {context_name} '{name}': {e:?}"),
+            }),
+        }
+    }
+}
+
+fn get_list(
+    obj: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Result<Vec<serde_json::Value>, AuthyImportError> {
+    match obj.get(key) {
+        Some(serde_json::Value::Array(values)) => Ok(values.clone()),
+        Some(serde_json::Value::Null) | None => Ok(Vec::new()),
+        Some(_) => {
+            warn!("Authy export has an invalid '{key}' field");
+            Err(AuthyImportError::BadContent)
+        }
+    }
+}
+
+/// Parses the JSON token dumps produced by the common Authy export scripts. Accepts either a plain
+/// list of tokens or an object holding `tokens` and `apps` lists.
+pub fn parse_authy_json(input: &str) -> Result<ImportResult, AuthyImportError> {
+    let parsed: serde_json::Value = serde_json::from_str(input).map_err(|e| {
+        warn!("Error parsing Authy export: {e:?}");
+        AuthyImportError::BadContent
+    })?;
+
+    let (tokens, apps) = match parsed {
+        serde_json::Value::Array(tokens) => (tokens, Vec::new()),
+        serde_json::Value::Object(obj) => {
+            if !obj.contains_key("tokens") && !obj.contains_key("apps") {
+                warn!("Authy export does not contain tokens nor apps");
+                return Err(AuthyImportError::BadContent);
+            }
+            (get_list(&obj, "tokens")?, get_list(&obj, "apps")?)
+        }
+        _ => return Err(AuthyImportError::BadContent),
+    };
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    parse_list(tokens, AuthyEntryKind::Token, &mut entries, &mut errors);
+    parse_list(apps, AuthyEntryKind::App, &mut entries, &mut errors);
+
+    Ok(ImportResult { entries, errors })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents;
+
+    fn check_totp(entry: &AuthenticatorEntry, issuer: &str, label: &str, secret: &str, digits: u8, period: u16) {
+        assert_eq!(issuer, entry.issuer());
+        assert_eq!(label, entry.name());
+        assert_eq!(secret, entry.secret());
+
+        let params = entry.get_totp_parameters().expect("should have totp parameters");
+        assert_eq!(Algorithm::SHA1, params.algorithm);
+        assert_eq!(digits, params.digits);
+        assert_eq!(period, params.period);
+    }
+
+    #[test]
+    fn can_import_token_list() {
+        let input = get_file_contents("authy/authy_tokens.json");
+        let res = parse_authy_json(&input).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(res.entries.len(), 3);
+
+        check_totp(&res.entries[0], "Google", "some@test.email", "JBSWY3DPEHPK3PXP", 6, 30);
+        check_totp(
+            &res.entries[1],
+            "Proton",
+            "sometestaccount@proton.me",
+            "JBSWY3DPEHPK3PXQ",
+            8,
+            30,
+        );
+        // Name as fallback issuer
+        check_totp(
+            &res.entries[2],
+            "Amazon",
+            "Amazon",
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ",
+            7,
+            30,
+        );
+    }
+
+    #[test]
+    fn can_import_tokens_and_apps() {
+        let input = get_file_contents("authy/authy_tokens_and_apps.json");
+        let res = parse_authy_json(&input).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(res.entries.len(), 3);
+
+        check_totp(&res.entries[0], "GitHub", "Test-acc", "JBSWY3DPEHPK3PXP", 6, 30);
+        check_totp(&res.entries[1], "Twitch", "Twitch", "AERUKZ4JVPG66AJDIVTYTK6N54", 7, 10);
+        // Apps without digits use the Authy default
+        check_totp(
+            &res.entries[2],
+            "SendGrid",
+            "SendGrid",
+            "73OLVGDWKQZBB7W4XKMHMVBSCA",
+            7,
+            10,
+        );
+    }
+
+    #[test]
+    fn tokens_without_digits_use_the_otpauth_default() {
+        let input = r#"[{"name": "GitHub:Test-acc", "decryptedSeed": "JBSWY3DPEHPK3PXP"}]"#;
+        let res = parse_authy_json(input).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(res.entries.len(), 1);
+
+        check_totp(&res.entries[0], "GitHub", "Test-acc", "JBSWY3DPEHPK3PXP", 6, 30);
+    }
+
+    #[test]
+    fn app_entries_generate_codes() {
+        let input = get_file_contents("authy/authy_tokens_and_apps.json");
+        let res = parse_authy_json(&input).expect("should be able to parse");
+
+        let code = crate::AuthenticatorClient::generate_code(&res.entries[1], 1742298622).expect("should generate");
+        assert_eq!(7, code.current_code.len());
+    }
+
+    #[test]
+    fn reports_malformed_entries() {
+        let input = get_file_contents("authy/authy_malformed.json");
+        let res = parse_authy_json(&input).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 1);
+        assert_eq!("Valid", res.entries[0].name());
+
+        assert_eq!(res.errors.len(), 4);
+        assert_eq!("Error in entry 1", res.errors[0].context);
+        assert!(res.errors[0].message.contains("MissingSeed"));
+        assert_eq!("Error in entry 2", res.errors[1].context);
+        assert!(res.errors[1].message.contains("digits"));
+        assert!(res.errors[2].message.contains("Malformed"));
+        assert!(res.errors[3].message.contains("Malformed"));
+    }
+
+    #[test]
+    fn invalid_app_seed_returns_error() {
+        let input = r#"{"apps": [{"name": "App", "decryptedSeed": "not hex"}]}"#;
+        let res = parse_authy_json(input).expect("should be able to parse");
+        assert!(res.entries.is_empty());
+        assert_eq!(res.errors.len(), 1);
+        assert_eq!("Error in app 0", res.errors[0].context);
+        assert!(res.errors[0].message.contains("InvalidSeed"));
+    }
+
+    #[test]
+    fn fails_on_unknown_content() {
+        let err = parse_authy_json(r#"{"entries": []}"#).expect_err("should fail");
+        assert!(matches!(err, AuthyImportError::BadContent));
+
+        let err = parse_authy_json("not json").expect_err("should fail");
+        assert!(matches!(err, AuthyImportError::BadContent));
+    }
+}
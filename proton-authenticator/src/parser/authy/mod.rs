@@ -0,0 +1,24 @@
+use crate::parser::ThirdPartyImportError;
+
+mod json;
+
+#[derive(Clone, Debug)]
+pub enum AuthyImportError {
+    BadContent,
+    MissingSeed,
+    InvalidSeed,
+    InvalidConfig(String),
+}
+
+impl From<AuthyImportError> for ThirdPartyImportError {
+    fn from(e: AuthyImportError) -> Self {
+        match e {
+            AuthyImportError::BadContent => Self::BadContent,
+            AuthyImportError::MissingSeed => Self::BadContent,
+            AuthyImportError::InvalidSeed => Self::BadContent,
+            AuthyImportError::InvalidConfig(_) => Self::BadContent,
+        }
+    }
+}
+
+pub use json::parse_authy_json;
@@ -17,15 +17,19 @@ pub fn parse_bitwarden_csv(input: &str) -> Result<ImportResult, BitwardenImportE
         }
     };
 
-    let totp_idx = find_header_index(headers, "login_totp")?;
-    let name_idx = find_header_index(headers, "name")?;
+    let columns = Columns {
+        totp: find_header_index(headers, "login_totp")?,
+        name: find_header_index(headers, "name")?,
+        folder: headers.iter().position(|h| h == "folder"),
+        favorite: headers.iter().position(|h| h == "favorite"),
+    };
 
     let mut entries = Vec::new();
     let mut errors = Vec::new();
     for (idx, result) in csv_reader.records().enumerate() {
         match result {
-            Ok(record) => match record.get(totp_idx) {
-                Some(r) => parse_line(&record, r, &mut entries, &mut errors, idx, name_idx),
+            Ok(record) => match record.get(columns.totp) {
+                Some(r) => parse_line(&record, r, &mut entries, &mut errors, idx, &columns),
                 None => {
                     let formatted = format!("{record:?}");
                     let concealed = conceal(&formatted);
@@ -45,6 +49,13 @@ pub fn parse_bitwarden_csv(input: &str) -> Result<ImportResult, BitwardenImportE
     Ok(ImportResult { entries, errors })
 }
 
+struct Columns {
+    totp: usize,
+    name: usize,
+    folder: Option<usize>,
+    favorite: Option<usize>,
+}
+
 fn find_header_index(headers: &StringRecord, header: &str) -> Result<usize, BitwardenImportError> {
     match headers.iter().position(|h| h == header) {
         Some(idx) => Ok(idx),
@@ -61,12 +72,13 @@ fn parse_line(
     entries: &mut Vec<AuthenticatorEntry>,
     errors: &mut Vec<ImportError>,
     idx: usize,
-    name_idx: usize,
+    columns: &Columns,
 ) {
+    let entry_count = entries.len();
     if uri.starts_with("otpauth://") {
-        parse_totp_line(uri, record, entries, errors, idx, name_idx);
+        parse_totp_line(uri, record, entries, errors, idx, columns.name);
     } else if uri.starts_with("steam://") {
-        parse_steam_line(record, uri, entries, errors, idx, name_idx)
+        parse_steam_line(record, uri, entries, errors, idx, columns.name)
     } else {
         let concealed = conceal(uri);
         errors.push(ImportError {
@@ -74,6 +86,14 @@ fn parse_line(
             message: format!("Unknown URI format: {concealed}"),
         });
     }
+
+    if entries.len() > entry_count
+        && let Some(entry) = entries.last_mut()
+    {
+        let column = |idx: Option<usize>| idx.and_then(|idx| record.get(idx)).map(str::trim).unwrap_or_default();
+        entry.set_tags(vec![column(columns.folder).to_string()]);
+        entry.favourite = column(columns.favorite) == "1";
+    }
 }
 
 fn parse_totp_line(
@@ -99,6 +119,8 @@ fn parse_totp_line(
                 content: AuthenticatorEntryContent::Totp(totp),
                 note: None,
                 id: AuthenticatorEntry::generate_id(),
+                tags: Vec::new(),
+                favourite: false,
             });
         }
         Err(e) => {
@@ -129,6 +151,8 @@ fn parse_steam_line(
                 content: AuthenticatorEntryContent::Steam(steam),
                 note: None,
                 id: AuthenticatorEntry::generate_id(),
+                tags: Vec::new(),
+                favourite: false,
             });
         }
         Err(e) => {
@@ -216,6 +240,17 @@ mod test {
         check_label(&entries[2], "Code1");
     }
 
+    #[test]
+    fn imports_folders_and_favourites() {
+        let input = "folder,favorite,type,name,login_totp\nWork,1,login,First,otpauth://totp/?secret=NDSKXJHDJNDBDBBB&issuer=First\n,,login,Second,otpauth://totp/?secret=NDSKXJHDJNDBDBBB&issuer=Second";
+        let res = parse_bitwarden_csv(input).expect("Should be able to parse the CSV");
+        assert!(res.errors.is_empty());
+        assert_eq!(vec!["Work".to_string()], res.entries[0].tags);
+        assert!(res.entries[0].favourite);
+        assert!(res.entries[1].tags.is_empty());
+        assert!(!res.entries[1].favourite);
+    }
+
     #[test]
     fn steam_entry_imported_from_bitwarden_csv_generates_correct_code() {
         let input = get_file_contents("bitwarden/bitwarden.csv");
@@ -16,12 +16,24 @@ struct Struct {
     pub name: String,
     pub notes: Option<String>,
     pub login: Login,
+    #[serde(default, rename = "folderId")]
+    pub folder_id: Option<String>,
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct Folder {
+    pub id: String,
+    pub name: String,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 struct Root {
     pub encrypted: bool,
     pub items: Vec<Struct>,
+    #[serde(default)]
+    pub folders: Vec<Folder>,
 }
 
 impl TryFrom<Struct> for AuthenticatorEntry {
@@ -34,24 +46,22 @@ impl TryFrom<Struct> for AuthenticatorEntry {
         };
         let content = if totp.contains("://") {
             // Imported from Bitwarden Authenticator
-            let parsed = AuthenticatorEntryContent::from_uri(totp).map_err(|_| BitwardenImportError::Unsupported)?;
+            let mut parsed =
+                AuthenticatorEntryContent::from_uri(totp).map_err(|_| BitwardenImportError::Unsupported)?;
 
-            match parsed {
-                AuthenticatorEntryContent::Totp(mut totp) => {
+            match &mut parsed {
+                AuthenticatorEntryContent::Totp(totp) => {
                     if totp.label.is_none() && !value.name.is_empty() {
                         totp.label = Some(value.name);
                     }
-
-                    AuthenticatorEntryContent::Totp(totp)
                 }
-                AuthenticatorEntryContent::Steam(mut steam) => {
+                AuthenticatorEntryContent::Steam(steam) => {
                     if steam.name.is_none() && !value.name.is_empty() {
                         steam.name = Some(value.name);
                     }
-
-                    AuthenticatorEntryContent::Steam(steam)
                 }
             }
+            parsed
         } else {
             // Probably imported from bitwarden main app export
             let mut totp = TOTP::from_uri(totp).map_err(|_| BitwardenImportError::Unsupported)?;
@@ -76,6 +86,8 @@ impl TryFrom<Struct> for AuthenticatorEntry {
             content,
             note: value.notes,
             id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite: value.favorite,
         })
     }
 }
@@ -89,8 +101,16 @@ pub fn parse_bitwarden_json(input: &str) -> Result<ImportResult, BitwardenImport
     let mut entries = Vec::new();
     let mut errors = Vec::new();
     for (idx, item) in parsed.items.into_iter().enumerate() {
+        let folder = item
+            .folder_id
+            .as_ref()
+            .and_then(|id| parsed.folders.iter().find(|f| &f.id == id))
+            .map(|f| f.name.to_string());
         match AuthenticatorEntry::try_from(item) {
-            Ok(entry) => entries.push(entry),
+            Ok(mut entry) => {
+                entry.set_tags(folder.into_iter().collect());
+                entries.push(entry)
+            }
             Err(e) => errors.push(ImportError {
                 context: format!("Error in entry {idx}"),
                 message: format!("{e:?}"),
@@ -115,6 +135,17 @@ mod test {
         assert_eq!(name, entry.label.clone().expect("Should have label"));
     }
 
+    #[test]
+    fn imports_folders_and_favourites() {
+        let input = r#"{"encrypted": false, "folders": [{"id": "f1", "name": "Work"}], "items": [{"id": "1", "name": "First", "folderId": "f1", "favorite": true, "login": {"totp": "otpauth://totp/First?secret=MYSECRET"}}, {"id": "2", "name": "Second", "folderId": null, "login": {"totp": "otpauth://totp/Second?secret=MYSECRET"}}]}"#;
+        let res = parse_bitwarden_json(input).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(vec!["Work".to_string()], res.entries[0].tags);
+        assert!(res.entries[0].favourite);
+        assert!(res.entries[1].tags.is_empty());
+        assert!(!res.entries[1].favourite);
+    }
+
     #[test]
     fn can_parse_content() {
         let input = get_file_contents("bitwarden/bitwarden.json");
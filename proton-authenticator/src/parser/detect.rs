@@ -0,0 +1,476 @@
+use crate::parser::{ImportResult, ThirdPartyImportError};
+use proton_pass_derive::ffi_type;
+use serde_json::{Map, Value};
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const KDBX_MAGIC: &[u8] = &[0x03, 0xD9, 0xA2, 0x9A];
+const BPLIST_MAGIC: &[u8] = b"bplist00";
+const JAVA_SERIALIZATION_MAGIC: &[u8] = &[0xAC, 0xED, 0x00, 0x05];
+const PNG_MAGIC: &[u8] = b"\x89PNG";
+const JPEG_MAGIC: &[u8] = &[0xFF, 0xD8, 0xFF];
+const GIF_MAGIC: &[u8] = b"GIF8";
+const BMP_MAGIC: &[u8] = b"BM";
+const RIFF_MAGIC: &[u8] = b"RIFF";
+const WEBP_MAGIC: &[u8] = b"WEBP";
+
+const RAIVO_EXPORT_FILE_NAME: &[u8] = b"raivo-otp-export.json";
+// Offset of the general purpose flags inside a zip local file header
+const ZIP_FLAGS_OFFSET: usize = 6;
+const ZIP_ENCRYPTED_FLAG: u8 = 0x01;
+
+// [iterations: u32 BE][salt: 12 bytes][nonce: 12 bytes][GCM tag: 16 bytes]
+const ANDOTP_MIN_LENGTH: usize = 44;
+const ANDOTP_MAX_ITERATIONS: u32 = 10_000_000;
+
+#[ffi_type(web_name = "WasmImportFormat")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImportFormat {
+    Aegis,
+    AndOtp,
+    Authy,
+    BitwardenCsv,
+    BitwardenJson,
+    EnteEncrypted,
+    EnteTxt,
+    FreeOtpBackup,
+    FreeOtpPlus,
+    FreeOtpXml,
+    GoogleAuthenticator,
+    KeePassCsv,
+    KeePassKdbx,
+    LastPass,
+    MicrosoftAuthenticatorCsv,
+    MicrosoftAuthenticatorJson,
+    OtpAuth,
+    OtpAuthUriList,
+    ProtonAuthenticator,
+    ProtonPass,
+    QrImage,
+    Raivo,
+    RaivoZip,
+    SteamMaFile,
+    TwoFas,
+}
+
+#[ffi_type(web_name = "WasmDetectedImportFormat")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DetectedFormat {
+    pub format: ImportFormat,
+    pub password_required: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct DetectedImportResult {
+    pub format: ImportFormat,
+    pub result: ImportResult,
+}
+
+#[derive(Clone, Debug, proton_pass_derive::Error)]
+pub enum DetectedImportError {
+    UnknownFormat,
+    Import(ImportFormat, ThirdPartyImportError),
+}
+
+impl DetectedFormat {
+    fn new(format: ImportFormat, password_required: bool) -> Self {
+        Self {
+            format,
+            password_required,
+        }
+    }
+}
+
+/// Sniffs the contents of an export file to find out which app produced it.
+pub fn detect_import_format(input: &[u8]) -> Option<DetectedFormat> {
+    if let Some(detected) = detect_binary_format(input) {
+        return Some(detected);
+    }
+
+    match std::str::from_utf8(input) {
+        Ok(text) => detect_text_format(text),
+        Err(_) => detect_andotp_encrypted(input),
+    }
+}
+
+/// Detects the format of the input and imports it with the matching parser.
+pub fn detect_and_parse(input: &[u8], password: Option<String>) -> Result<DetectedImportResult, DetectedImportError> {
+    let detected = detect_import_format(input).ok_or_else(|| {
+        warn!("Could not detect the import format");
+        DetectedImportError::UnknownFormat
+    })?;
+
+    let format = detected.format;
+    if detected.password_required && password.is_none() {
+        return Err(DetectedImportError::Import(
+            format,
+            ThirdPartyImportError::MissingPassword,
+        ));
+    }
+
+    let result = parse_format(format, input, password).map_err(|e| DetectedImportError::Import(format, e))?;
+    Ok(DetectedImportResult { format, result })
+}
+
+fn parse_format(
+    format: ImportFormat,
+    input: &[u8],
+    password: Option<String>,
+) -> Result<ImportResult, ThirdPartyImportError> {
+    let required_password = || password.clone().ok_or(ThirdPartyImportError::MissingPassword);
+    let text = || std::str::from_utf8(input).map_err(|_| ThirdPartyImportError::BadContent);
+
+    match format {
+        ImportFormat::Aegis => Ok(super::aegis::parse_aegis_json(text()?, password.clone())?),
+        ImportFormat::AndOtp => Ok(super::andotp::parse_andotp_file(input, password.clone())?),
+        ImportFormat::Authy => Ok(super::authy::parse_authy_json(text()?)?),
+        ImportFormat::BitwardenCsv => Ok(super::bitwarden::parse_bitwarden_csv(text()?)?),
+        ImportFormat::BitwardenJson => Ok(super::bitwarden::parse_bitwarden_json(text()?)?),
+        ImportFormat::EnteEncrypted => Ok(super::ente::parse_ente_encrypted(text()?, &required_password()?)?),
+        ImportFormat::EnteTxt => Ok(super::ente::parse_ente_txt(text()?)?),
+        ImportFormat::FreeOtpBackup => Ok(super::freeotp::parse_freeotp_backup(input, password.clone())?),
+        ImportFormat::FreeOtpPlus => Ok(super::freeotp::parse_freeotp_plus_json(text()?)?),
+        ImportFormat::FreeOtpXml => Ok(super::freeotp::parse_freeotp_xml(text()?)?),
+        ImportFormat::GoogleAuthenticator => Ok(super::google::parse_google_authenticator_totp(text()?.trim())?),
+        ImportFormat::KeePassCsv => Ok(super::keepass::parse_keepass_csv(text()?)?),
+        ImportFormat::KeePassKdbx => Ok(super::keepass::parse_keepass_kdbx(input, &required_password()?)?),
+        ImportFormat::LastPass => Ok(super::lastpass::parse_lastpass_json(text()?)?),
+        ImportFormat::MicrosoftAuthenticatorCsv => Ok(super::microsoft::parse_microsoft_authenticator_csv(text()?)?),
+        ImportFormat::MicrosoftAuthenticatorJson => Ok(super::microsoft::parse_microsoft_authenticator_json(text()?)?),
+        ImportFormat::OtpAuth => Ok(super::otpauth::parse_otpauth_db(input, password.clone())?),
+        ImportFormat::OtpAuthUriList => Ok(super::aegis::parse_aegis_txt(text()?)?),
+        ImportFormat::ProtonAuthenticator => match &password {
+            Some(password) => {
+                super::proton_authenticator::parse_proton_authenticator_export_with_password(text()?, password)
+            }
+            None => super::proton_authenticator::parse_proton_authenticator_export(text()?),
+        },
+        ImportFormat::ProtonPass => Ok(super::pass::parse_pass_zip(input)?),
+        ImportFormat::QrImage => parse_qr_image(input),
+        ImportFormat::Raivo => Ok(super::raivo::parse_raivo_json(text()?)?),
+        ImportFormat::RaivoZip => Ok(super::raivo::parse_raivo_zip(input, password.clone())?),
+        ImportFormat::SteamMaFile => Ok(super::steam::parse_steam_mafile(text()?)?),
+        ImportFormat::TwoFas => Ok(super::twofas::parse_2fas_file(text()?, password.clone())?),
+    }
+}
+
+#[cfg(feature = "qr")]
+fn parse_qr_image(input: &[u8]) -> Result<ImportResult, ThirdPartyImportError> {
+    let result = crate::qr::import_from_qr_images(&[input.to_vec()]);
+    if result.entries.is_empty() && !result.errors.is_empty() {
+        warn!("Could not import any entry from the QR image");
+        return Err(ThirdPartyImportError::BadContent);
+    }
+    Ok(result)
+}
+
+#[cfg(not(feature = "qr"))]
+fn parse_qr_image(_: &[u8]) -> Result<ImportResult, ThirdPartyImportError> {
+    warn!("QR code scanning is not available");
+    Err(ThirdPartyImportError::BadContent)
+}
+
+fn detect_binary_format(input: &[u8]) -> Option<DetectedFormat> {
+    if input.starts_with(ZIP_MAGIC) {
+        let is_raivo = input
+            .windows(RAIVO_EXPORT_FILE_NAME.len())
+            .any(|w| w == RAIVO_EXPORT_FILE_NAME);
+        return Some(if is_raivo {
+            let encrypted = input
+                .get(ZIP_FLAGS_OFFSET)
+                .is_some_and(|flags| flags & ZIP_ENCRYPTED_FLAG != 0);
+            DetectedFormat::new(ImportFormat::RaivoZip, encrypted)
+        } else {
+            DetectedFormat::new(ImportFormat::ProtonPass, false)
+        });
+    }
+
+    if input.starts_with(KDBX_MAGIC) {
+        return Some(DetectedFormat::new(ImportFormat::KeePassKdbx, true));
+    }
+    if input.starts_with(BPLIST_MAGIC) {
+        return Some(DetectedFormat::new(ImportFormat::OtpAuth, true));
+    }
+    if input.starts_with(JAVA_SERIALIZATION_MAGIC) {
+        return Some(DetectedFormat::new(ImportFormat::FreeOtpBackup, true));
+    }
+
+    let is_webp = input.starts_with(RIFF_MAGIC) && input.get(8..12) == Some(WEBP_MAGIC);
+    let is_image = [PNG_MAGIC, JPEG_MAGIC, GIF_MAGIC, BMP_MAGIC]
+        .iter()
+        .any(|magic| input.starts_with(magic));
+    if is_image || is_webp {
+        return Some(DetectedFormat::new(ImportFormat::QrImage, false));
+    }
+
+    None
+}
+
+fn detect_andotp_encrypted(input: &[u8]) -> Option<DetectedFormat> {
+    if input.len() < ANDOTP_MIN_LENGTH {
+        return None;
+    }
+
+    let mut iterations = [0u8; 4];
+    iterations.copy_from_slice(&input[..4]);
+    match u32::from_be_bytes(iterations) {
+        1..=ANDOTP_MAX_ITERATIONS => Some(DetectedFormat::new(ImportFormat::AndOtp, true)),
+        _ => None,
+    }
+}
+
+fn detect_text_format(input: &str) -> Option<DetectedFormat> {
+    let trimmed = input.trim_start_matches('\u{feff}').trim();
+
+    if trimmed.starts_with("otpauth-migration://") {
+        return Some(DetectedFormat::new(ImportFormat::GoogleAuthenticator, false));
+    }
+
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return match serde_json::from_str::<Value>(trimmed) {
+            Ok(Value::Object(obj)) => detect_json_object_format(&obj),
+            Ok(Value::Array(list)) => detect_json_list_format(&list),
+            _ => None,
+        };
+    }
+
+    if trimmed.starts_with('<') {
+        return trimmed
+            .contains("<map")
+            .then(|| DetectedFormat::new(ImportFormat::FreeOtpXml, false));
+    }
+
+    let first_line = trimmed.lines().next()?.trim();
+    if first_line.starts_with("otpauth://") {
+        // Ente stores the entry metadata in a custom query parameter
+        let format = if trimmed.contains("codeDisplay=") {
+            ImportFormat::EnteTxt
+        } else {
+            ImportFormat::OtpAuthUriList
+        };
+        return Some(DetectedFormat::new(format, false));
+    }
+
+    detect_csv_format(first_line)
+}
+
+fn detect_json_object_format(obj: &Map<String, Value>) -> Option<DetectedFormat> {
+    let has = |key: &str| obj.contains_key(key);
+
+    let detected = if has("header") && has("db") {
+        // The db is a base64 string when the vault is encrypted
+        DetectedFormat::new(ImportFormat::Aegis, obj.get("db").is_some_and(Value::is_string))
+    } else if has("services") || has("servicesEncrypted") {
+        let encrypted = obj
+            .get("servicesEncrypted")
+            .and_then(Value::as_str)
+            .is_some_and(|s| !s.is_empty());
+        DetectedFormat::new(ImportFormat::TwoFas, encrypted)
+    } else if has("shared_secret") && (has("revocation_code") || has("account_name")) {
+        DetectedFormat::new(ImportFormat::SteamMaFile, false)
+    } else if has("kdfParams") && has("encryptedData") {
+        DetectedFormat::new(ImportFormat::EnteEncrypted, true)
+    } else if has("items") && has("encrypted") {
+        let encrypted = obj.get("encrypted").and_then(Value::as_bool).unwrap_or(false);
+        DetectedFormat::new(ImportFormat::BitwardenJson, encrypted)
+    } else if has("accounts") {
+        DetectedFormat::new(ImportFormat::LastPass, false)
+    } else if has("tokens") && has("tokenOrder") {
+        DetectedFormat::new(ImportFormat::FreeOtpPlus, false)
+    } else if has("tokens") || has("apps") {
+        DetectedFormat::new(ImportFormat::Authy, false)
+    } else if has("version") && has("entries") {
+        DetectedFormat::new(ImportFormat::ProtonAuthenticator, false)
+    } else if has("version") && has("salt") && has("content") {
+        DetectedFormat::new(ImportFormat::ProtonAuthenticator, true)
+    } else {
+        return None;
+    };
+
+    Some(detected)
+}
+
+fn detect_json_list_format(list: &[Value]) -> Option<DetectedFormat> {
+    let first = list.first()?.as_object()?;
+    let has = |key: &str| first.contains_key(key);
+
+    let format = if has("oath_secret_key") {
+        ImportFormat::MicrosoftAuthenticatorJson
+    } else if has("kind") && has("timer") {
+        ImportFormat::Raivo
+    } else if has("decryptedSeed") || has("decrypted_seed") || has("encrypted_seed") || has("encryptedSeed") {
+        ImportFormat::Authy
+    } else if has("secret") && has("type") {
+        ImportFormat::AndOtp
+    } else {
+        return None;
+    };
+
+    Some(DetectedFormat::new(format, false))
+}
+
+fn detect_csv_format(header: &str) -> Option<DetectedFormat> {
+    let columns: Vec<String> = header
+        .split(',')
+        .map(|c| c.trim().trim_matches('"').to_lowercase())
+        .collect();
+    let has = |name: &str| columns.iter().any(|c| c == name);
+
+    let format = if has("login_totp") {
+        ImportFormat::BitwardenCsv
+    } else if has("oath_secret_key") {
+        ImportFormat::MicrosoftAuthenticatorCsv
+    } else if has("title") && has("totp") {
+        ImportFormat::KeePassCsv
+    } else {
+        return None;
+    };
+
+    Some(DetectedFormat::new(format, false))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{get_file_contents, get_file_contents_raw};
+
+    fn entries() -> Vec<crate::AuthenticatorEntry> {
+        vec![
+            crate::AuthenticatorEntry::from_uri(
+                "otpauth://totp/Proton:user?secret=JBSWY3DPEHPK3PXP&issuer=Proton",
+                None,
+            )
+            .expect("should be able to create"),
+            crate::AuthenticatorEntry::from_uri("otpauth://totp/Other:user?secret=GEZDGNBVGY3TQOJQ&issuer=Other", None)
+                .expect("should be able to create"),
+        ]
+    }
+
+    fn check_detected(path: &str, format: ImportFormat, password_required: bool) {
+        let input = get_file_contents_raw(path);
+        let detected = detect_import_format(&input).unwrap_or_else(|| panic!("should detect format of {path}"));
+        assert_eq!(format, detected.format, "wrong format for {path}");
+        assert_eq!(
+            password_required, detected.password_required,
+            "wrong password flag for {path}"
+        );
+    }
+
+    #[test]
+    fn detects_json_formats() {
+        check_detected("aegis/aegis-json-unencrypted.json", ImportFormat::Aegis, false);
+        check_detected("aegis/aegis-json-encrypted-test.json", ImportFormat::Aegis, true);
+        check_detected("2fas/decrypted.2fas", ImportFormat::TwoFas, false);
+        check_detected("2fas/encrypted.2fas", ImportFormat::TwoFas, true);
+        check_detected("ente/encrypted.lowcomplexity.txt", ImportFormat::EnteEncrypted, true);
+        check_detected("bitwarden/bitwarden.json", ImportFormat::BitwardenJson, false);
+        check_detected("lastpass/lastpass.json", ImportFormat::LastPass, false);
+        check_detected("freeotp/freeotp_plus.json", ImportFormat::FreeOtpPlus, false);
+        check_detected("authy/authy_tokens.json", ImportFormat::Authy, false);
+        check_detected("authy/authy_tokens_and_apps.json", ImportFormat::Authy, false);
+        check_detected("andotp/andotp_accounts.json", ImportFormat::AndOtp, false);
+        check_detected("raivo/raivo-otp-export.json", ImportFormat::Raivo, false);
+        check_detected("steam/76561198000000001.maFile", ImportFormat::SteamMaFile, false);
+        check_detected(
+            "microsoft/accounts.json",
+            ImportFormat::MicrosoftAuthenticatorJson,
+            false,
+        );
+    }
+
+    #[test]
+    fn detects_text_formats() {
+        check_detected("aegis/aegis-txt.txt", ImportFormat::OtpAuthUriList, false);
+        check_detected("ente/plain.txt", ImportFormat::EnteTxt, false);
+        check_detected("freeotp/tokens.xml", ImportFormat::FreeOtpXml, false);
+        check_detected("bitwarden/bitwarden.csv", ImportFormat::BitwardenCsv, false);
+        check_detected("keepass/keepassxc.csv", ImportFormat::KeePassCsv, false);
+        check_detected("microsoft/accounts.csv", ImportFormat::MicrosoftAuthenticatorCsv, false);
+    }
+
+    #[test]
+    fn detects_binary_formats() {
+        check_detected("pass/PassExport.zip", ImportFormat::ProtonPass, false);
+        check_detected("raivo/raivo-otp-export.zip", ImportFormat::RaivoZip, true);
+        check_detected("keepass/keepass_argon2_chacha20.kdbx", ImportFormat::KeePassKdbx, true);
+        check_detected("otpauth/backup.otpauthdb", ImportFormat::OtpAuth, true);
+        check_detected("freeotp/externalBackup.xml", ImportFormat::FreeOtpBackup, true);
+        check_detected("andotp/andotp_accounts.json.aes", ImportFormat::AndOtp, true);
+    }
+
+    #[test]
+    fn detects_images() {
+        let input = std::fs::read("test_data/qr/example.png").expect("should be able to read image");
+        let detected = detect_import_format(&input).expect("should detect format");
+        assert_eq!(ImportFormat::QrImage, detected.format);
+    }
+
+    #[test]
+    fn detects_proton_authenticator_exports() {
+        let entries = entries();
+        let plain = crate::entry::export_entries(entries.clone()).expect("should be able to export");
+        let detected = detect_import_format(plain.as_bytes()).expect("should detect format");
+        assert_eq!(DetectedFormat::new(ImportFormat::ProtonAuthenticator, false), detected);
+
+        let encrypted =
+            crate::entry::export_entries_with_password(entries, "password").expect("should be able to export");
+        let detected = detect_import_format(encrypted.as_bytes()).expect("should detect format");
+        assert_eq!(DetectedFormat::new(ImportFormat::ProtonAuthenticator, true), detected);
+
+        let res = detect_and_parse(encrypted.as_bytes(), Some("password".to_string())).expect("should import");
+        assert_eq!(ImportFormat::ProtonAuthenticator, res.format);
+        assert_eq!(2, res.result.entries.len());
+    }
+
+    #[test]
+    fn detects_google_migration_uri() {
+        let entries = entries();
+        let export = crate::entry::export_entries_to_google_migration(entries).expect("should be able to export");
+
+        let res = detect_and_parse(export.payloads[0].as_bytes(), None).expect("should import");
+        assert_eq!(ImportFormat::GoogleAuthenticator, res.format);
+        assert_eq!(2, res.result.entries.len());
+    }
+
+    #[test]
+    fn parses_detected_format() {
+        let input = get_file_contents_raw("aegis/aegis-json-unencrypted.json");
+        let res = detect_and_parse(&input, None).expect("should import");
+        assert_eq!(ImportFormat::Aegis, res.format);
+        assert!(!res.result.entries.is_empty());
+
+        let input = get_file_contents_raw("keepass/keepass_argon2_chacha20.kdbx");
+        let password = get_file_contents("keepass/password");
+        let res = detect_and_parse(&input, Some(password.trim().to_string())).expect("should import");
+        assert_eq!(ImportFormat::KeePassKdbx, res.format);
+        assert!(!res.result.entries.is_empty());
+    }
+
+    #[test]
+    fn requires_password_for_encrypted_formats() {
+        let input = get_file_contents_raw("2fas/encrypted.2fas");
+        let err = detect_and_parse(&input, None).expect_err("should fail");
+        assert!(matches!(
+            err,
+            DetectedImportError::Import(ImportFormat::TwoFas, ThirdPartyImportError::MissingPassword)
+        ));
+
+        let password = get_file_contents("2fas/password");
+        let res = detect_and_parse(&input, Some(password)).expect("should import");
+        assert_eq!(ImportFormat::TwoFas, res.format);
+    }
+
+    #[test]
+    fn reports_parser_errors() {
+        let err = detect_and_parse(br#"{"header": {}, "db": {}}"#, None).expect_err("should fail");
+        assert!(matches!(err, DetectedImportError::Import(ImportFormat::Aegis, _)));
+    }
+
+    #[test]
+    fn fails_on_unknown_format() {
+        let err = detect_and_parse(b"some random text", None).expect_err("should fail");
+        assert!(matches!(err, DetectedImportError::UnknownFormat));
+
+        let err = detect_and_parse(br#"{"some": "json"}"#, None).expect_err("should fail");
+        assert!(matches!(err, DetectedImportError::UnknownFormat));
+    }
+}
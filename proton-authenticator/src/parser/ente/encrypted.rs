@@ -2,6 +2,7 @@ use super::{EnteImportError, chacha_decrypt};
 use crate::parser::ImportResult;
 use argon2::{Algorithm, Argon2, Params, Version};
 use base64::{Engine as _, engine::general_purpose};
+use zeroize::Zeroizing;
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,7 +21,7 @@ struct EnteEncryptedBackup {
     encryption_nonce: String,
 }
 
-fn derive_key(params: &KdfParams, password: &str) -> Result<[u8; 32], EnteImportError> {
+fn derive_key(params: &KdfParams, password: &str) -> Result<Zeroizing<[u8; 32]>, EnteImportError> {
     let salt_bytes = general_purpose::STANDARD.decode(&params.salt).map_err(|e| {
         warn!("Failed to decode ente salt: {e:?}");
         EnteImportError::BadContent
@@ -39,10 +40,10 @@ fn derive_key(params: &KdfParams, password: &str) -> Result<[u8; 32], EnteImport
     })?;
 
     let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
-    let mut key_bytes = [0u8; 32];
+    let mut key_bytes = Zeroizing::new([0u8; 32]);
 
     argon2
-        .hash_password_into(password.as_bytes(), &salt_bytes, &mut key_bytes)
+        .hash_password_into(password.as_bytes(), &salt_bytes, key_bytes.as_mut_slice())
         .map_err(|e| {
             warn!("Failed to hash password: {e:?}");
             EnteImportError::BadPassword
@@ -51,7 +52,7 @@ fn derive_key(params: &KdfParams, password: &str) -> Result<[u8; 32], EnteImport
     Ok(key_bytes)
 }
 
-fn decrypt_data(encrypted_data: &str, encoded_nonce: &str, key: [u8; 32]) -> Result<Vec<u8>, EnteImportError> {
+fn decrypt_data(encrypted_data: &str, encoded_nonce: &str, key: &[u8; 32]) -> Result<Vec<u8>, EnteImportError> {
     let encrypted_data = general_purpose::STANDARD.decode(encrypted_data).map_err(|e| {
         warn!("Failed to decode ente encrypted data: {e:?}");
         EnteImportError::BadContent
@@ -62,10 +63,10 @@ fn decrypt_data(encrypted_data: &str, encoded_nonce: &str, key: [u8; 32]) -> Res
         EnteImportError::BadContent
     })?;
 
-    chacha_decrypt::decrypt_custom_ente_xchacha20_poly1305(&encrypted_data, &key, &nonce_bytes)
+    chacha_decrypt::decrypt_custom_ente_xchacha20_poly1305(&encrypted_data, key, &nonce_bytes)
 }
 
-fn decrypt_backup(input: &str, password: &str) -> Result<String, EnteImportError> {
+fn decrypt_backup(input: &str, password: &str) -> Result<Zeroizing<String>, EnteImportError> {
     let backup: EnteEncryptedBackup = serde_json::from_str(input).map_err(|e| {
         warn!("failed to parse backup JSON: {e:?}");
         EnteImportError::BadContent
@@ -80,13 +81,13 @@ fn decrypt_backup(input: &str, password: &str) -> Result<String, EnteImportError
         e
     })?;
 
-    let decrypted = decrypt_data(&backup.encrypted_data, &backup.encryption_nonce, key_bytes)?;
+    let decrypted = decrypt_data(&backup.encrypted_data, &backup.encryption_nonce, &key_bytes)?;
     let plaintext_str = String::from_utf8(decrypted).map_err(|e| {
         warn!("Failed to decode ente encrypted data: {e:?}");
         EnteImportError::BadContent
     })?;
 
-    Ok(plaintext_str)
+    Ok(Zeroizing::new(plaintext_str))
 }
 
 pub fn parse_ente_encrypted(input: &str, password: &str) -> Result<ImportResult, EnteImportError> {
@@ -20,11 +20,18 @@ pub fn parse_ente_txt(input: &str) -> Result<ImportResult, EnteImportError> {
         if !trimmed.is_empty() {
             match AuthenticatorEntryContent::from_uri(trimmed) {
                 Ok(content) => match sanitize_content(content) {
-                    Ok(sanitized) => entries.push(AuthenticatorEntry {
-                        content: sanitized,
-                        note: None,
-                        id: AuthenticatorEntry::generate_id(),
-                    }),
+                    Ok(sanitized) => {
+                        let display = code_display(trimmed).unwrap_or_default();
+                        let mut entry = AuthenticatorEntry {
+                            content: sanitized,
+                            note: None,
+                            id: AuthenticatorEntry::generate_id(),
+                            tags: Vec::new(),
+                            favourite: display.pinned,
+                        };
+                        entry.set_tags(display.tags);
+                        entries.push(entry)
+                    }
                     Err(e) => errors.push(ImportError {
                         context: format!("Error in line {}", idx + 1),
                         message: format!("Could not process [{line_start}]: {e:?}"),
@@ -45,10 +52,31 @@ pub fn parse_ente_txt(input: &str) -> Result<ImportResult, EnteImportError> {
     }
 }
 
+#[derive(Default, serde::Deserialize)]
+struct CodeDisplay {
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+// Ente stores the tags and the pinned state as JSON in the codeDisplay query param
+fn code_display(uri: &str) -> Option<CodeDisplay> {
+    let parsed = url::Url::parse(uri).ok()?;
+    let (_, value) = parsed.query_pairs().find(|(key, _)| key == "codeDisplay")?;
+    match serde_json::from_str(&value) {
+        Ok(display) => Some(display),
+        Err(e) => {
+            warn!("Error parsing Ente codeDisplay: {e:?}");
+            None
+        }
+    }
+}
+
 // Ente sometimes adds the issuer as a prefix to the label. Make sure to remove it
-fn sanitize_content(content: AuthenticatorEntryContent) -> Result<AuthenticatorEntryContent, EnteImportError> {
-    match content {
-        AuthenticatorEntryContent::Totp(mut totp) => {
+fn sanitize_content(mut content: AuthenticatorEntryContent) -> Result<AuthenticatorEntryContent, EnteImportError> {
+    match &mut content {
+        AuthenticatorEntryContent::Totp(totp) => {
             // Handle issuer prefix removal when both label and issuer exist
             if let (Some(label), Some(issuer)) = (&totp.label, &totp.issuer) {
                 let issuer_prefix = format!("{issuer}:");
@@ -70,11 +98,10 @@ fn sanitize_content(content: AuthenticatorEntryContent) -> Result<AuthenticatorE
                 None => totp.label.clone(),
             };
             totp.issuer = issuer;
-
-            Ok(AuthenticatorEntryContent::Totp(totp))
         }
-        _ => Ok(content),
+        AuthenticatorEntryContent::Steam(_) => {}
     }
+    Ok(content)
 }
 
 #[cfg(test)]
@@ -116,6 +143,17 @@ mod tests {
         assert!(res.errors[0].message.contains("UnsupportedUri"))
     }
 
+    #[test]
+    fn imports_tags_and_pinned_state() {
+        let content = "otpauth://totp/Proton:user?secret=MYSECRET&issuer=Proton&codeDisplay=%7B%22pinned%22%3Atrue%2C%22tags%22%3A%5B%22Work%22%2C%22Mail%22%5D%7D\notpauth://totp/Other:user?secret=MYSECRET&issuer=Other";
+        let res = parse_ente_txt(content).expect("should be able to import");
+        assert!(res.errors.is_empty());
+        assert_eq!(vec!["Work".to_string(), "Mail".to_string()], res.entries[0].tags);
+        assert!(res.entries[0].favourite);
+        assert!(res.entries[1].tags.is_empty());
+        assert!(!res.entries[1].favourite);
+    }
+
     #[test]
     fn can_detect_encrypted_file() {
         let content = get_file_contents("ente/encrypted.lowcomplexity.txt");
@@ -0,0 +1,239 @@
+use super::FreeOtpImportError;
+use super::serialized::read_string_map;
+use super::token::{TOKEN_ORDER_KEY, parse_tokens, sort_tokens};
+use crate::parser::{ImportError, ImportResult};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hmac::Hmac;
+use pbkdf2::pbkdf2;
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+const MASTER_KEY: &str = "masterKey";
+const TOKEN_SUFFIX: &str = "-token";
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+#[derive(Debug, serde::Deserialize)]
+struct EncryptedKey {
+    #[serde(rename = "mCipherText")]
+    cipher_text: Vec<i8>,
+    #[serde(rename = "mParameters")]
+    parameters: Vec<i8>,
+    #[serde(rename = "mToken")]
+    token: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MasterKey {
+    #[serde(rename = "mAlgorithm")]
+    algorithm: String,
+    #[serde(rename = "mEncryptedKey")]
+    encrypted_key: EncryptedKey,
+    #[serde(rename = "mIterations")]
+    iterations: u32,
+    #[serde(rename = "mSalt")]
+    salt: Vec<i8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TokenKey {
+    key: String,
+}
+
+fn to_bytes(value: &[i8]) -> Vec<u8> {
+    value.iter().map(|b| *b as u8).collect()
+}
+
+// The GCM parameters are stored DER encoded: SEQUENCE { OCTET STRING nonce, INTEGER tag length }
+fn nonce_from_parameters(parameters: &[u8]) -> Result<&[u8], FreeOtpImportError> {
+    match parameters {
+        [0x30, _, 0x04, len, rest @ ..] if *len as usize == NONCE_LENGTH && rest.len() >= NONCE_LENGTH => {
+            Ok(&rest[..NONCE_LENGTH])
+        }
+        _ => {
+            warn!("Unsupported FreeOTP GCM parameters");
+            Err(FreeOtpImportError::Unsupported)
+        }
+    }
+}
+
+impl EncryptedKey {
+    fn decrypt(&self, key: &[u8]) -> Result<Vec<u8>, FreeOtpImportError> {
+        let parameters = to_bytes(&self.parameters);
+        let nonce = nonce_from_parameters(&parameters)?;
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| {
+            warn!("Error creating Aes256Gcm: {e:?}");
+            FreeOtpImportError::UnableToDecrypt
+        })?;
+
+        let cipher_text = to_bytes(&self.cipher_text);
+        let payload = Payload {
+            msg: &cipher_text,
+            aad: self.token.as_bytes(),
+        };
+        cipher.decrypt(Nonce::from_slice(nonce), payload).map_err(|e| {
+            warn!("Error decrypting FreeOTP key: {e:?}");
+            FreeOtpImportError::UnableToDecrypt
+        })
+    }
+}
+
+fn decrypt_master_key(master_key: &str, password: &str) -> Result<Zeroizing<Vec<u8>>, FreeOtpImportError> {
+    let master_key: MasterKey = serde_json::from_str(master_key).map_err(|e| {
+        warn!("Error parsing FreeOTP master key: {e:?}");
+        FreeOtpImportError::BadContent
+    })?;
+
+    if !master_key.algorithm.eq_ignore_ascii_case("PBKDF2withHmacSHA512") {
+        warn!("Unsupported FreeOTP key algorithm: {}", master_key.algorithm);
+        return Err(FreeOtpImportError::Unsupported);
+    }
+    if master_key.iterations == 0 || master_key.iterations > MAX_ITERATIONS {
+        warn!(
+            "FreeOTP backup has an invalid iteration count: {}",
+            master_key.iterations
+        );
+        return Err(FreeOtpImportError::BadContent);
+    }
+
+    let mut derived_key = Zeroizing::new([0u8; KEY_LENGTH]);
+    pbkdf2::<Hmac<Sha512>>(
+        password.as_bytes(),
+        &to_bytes(&master_key.salt),
+        master_key.iterations,
+        derived_key.as_mut_slice(),
+    )
+    .map_err(|e| {
+        warn!("Error deriving FreeOTP backup key: {e:?}");
+        FreeOtpImportError::UnableToDecrypt
+    })?;
+
+    // The only way to know if the password is right is being able to decrypt the master key
+    master_key
+        .encrypted_key
+        .decrypt(derived_key.as_slice())
+        .map(Zeroizing::new)
+        .map_err(|e| match e {
+            FreeOtpImportError::UnableToDecrypt => FreeOtpImportError::BadPassword,
+            other => other,
+        })
+}
+
+fn decrypt_secret(value: &str, master_key: &[u8]) -> Result<Vec<u8>, FreeOtpImportError> {
+    let token_key: TokenKey = serde_json::from_str(value).map_err(|_| FreeOtpImportError::BadContent)?;
+    let encrypted_key: EncryptedKey =
+        serde_json::from_str(&token_key.key).map_err(|_| FreeOtpImportError::BadContent)?;
+    encrypted_key.decrypt(master_key)
+}
+
+/// Parses the encrypted backup (`externalBackup.xml`) produced by FreeOTP 2.x. Despite its extension,
+/// the file contains a Java serialized map holding the encrypted master key and one encrypted key per token.
+pub fn parse_freeotp_backup(input: &[u8], password: Option<String>) -> Result<ImportResult, FreeOtpImportError> {
+    let map = read_string_map(input)?;
+
+    let password = match password {
+        Some(password) => password,
+        None => {
+            warn!("Tried to import FreeOTP backup without a password");
+            return Err(FreeOtpImportError::MissingPassword);
+        }
+    };
+
+    let master_key = match map.iter().find(|(key, _)| key == MASTER_KEY) {
+        Some((_, value)) => decrypt_master_key(value, &password)?,
+        None => {
+            warn!("FreeOTP backup does not contain a master key");
+            return Err(FreeOtpImportError::BadContent);
+        }
+    };
+
+    let order = map
+        .iter()
+        .find(|(key, _)| key == TOKEN_ORDER_KEY)
+        .map(|(_, value)| value.as_str());
+
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    for (key, value) in map.iter() {
+        let Some(uuid) = key.strip_suffix(TOKEN_SUFFIX) else {
+            continue;
+        };
+
+        let token = match map.iter().find(|(k, _)| k == uuid) {
+            Some((_, secret_value)) => decrypt_secret(secret_value, &master_key).and_then(|secret| {
+                let value = serde_json::from_str::<serde_json::Value>(value).map_err(|e| {
+                    warn!("Error parsing FreeOTP token {uuid}: {e:?}");
+                    FreeOtpImportError::BadContent
+                })?;
+                Ok((value, Some(secret)))
+            }),
+            None => Err(FreeOtpImportError::MissingSecret),
+        };
+        match token {
+            Ok(token) => tokens.push((uuid.to_string(), token)),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in token {uuid}"),
+                message: format!("Error reading token: {e:?}"),
+            }),
+        }
+    }
+
+    let mut result = parse_tokens(sort_tokens(tokens, order)?);
+    errors.append(&mut result.errors);
+    result.errors = errors;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{get_file_contents, get_file_contents_raw};
+    use proton_pass_totp::algorithm::Algorithm;
+
+    #[test]
+    fn can_import_encrypted_backup() {
+        let input = get_file_contents_raw("freeotp/externalBackup.xml");
+        let password = get_file_contents("freeotp/password");
+        let res = parse_freeotp_backup(&input, Some(password)).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(res.entries.len(), 2);
+
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        assert_eq!("JBSWY3DPEHPK3PXP", res.entries[0].secret());
+
+        assert_eq!("Amazon", res.entries[1].issuer());
+        assert_eq!("some@account.test", res.entries[1].name());
+        assert_eq!("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", res.entries[1].secret());
+        // Token images are not imported
+        assert!(res.entries[1].note.is_none());
+        let params = res.entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA256, params.algorithm);
+        assert_eq!(8, params.digits);
+        assert_eq!(15, params.period);
+    }
+
+    #[test]
+    fn missing_password_returns_error() {
+        let input = get_file_contents_raw("freeotp/externalBackup.xml");
+        let err = parse_freeotp_backup(&input, None).expect_err("should fail");
+        assert!(matches!(err, FreeOtpImportError::MissingPassword));
+    }
+
+    #[test]
+    fn wrong_password_returns_error() {
+        let input = get_file_contents_raw("freeotp/externalBackup.xml");
+        let err = parse_freeotp_backup(&input, Some("invalid".to_string())).expect_err("should fail");
+        assert!(matches!(err, FreeOtpImportError::BadPassword));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let input = get_file_contents_raw("freeotp/tokens.xml");
+        let err = parse_freeotp_backup(&input, Some("test".to_string())).expect_err("should fail");
+        assert!(matches!(err, FreeOtpImportError::BadContent));
+    }
+}
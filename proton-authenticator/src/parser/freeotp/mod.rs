@@ -0,0 +1,36 @@
+use crate::parser::ThirdPartyImportError;
+
+mod backup;
+mod plus;
+mod serialized;
+mod token;
+mod xml;
+
+#[derive(Clone, Debug)]
+pub enum FreeOtpImportError {
+    BadContent,
+    Unsupported,
+    BadPassword,
+    MissingPassword,
+    UnableToDecrypt,
+    MissingSecret,
+    InvalidConfig(String),
+}
+
+impl From<FreeOtpImportError> for ThirdPartyImportError {
+    fn from(e: FreeOtpImportError) -> Self {
+        match e {
+            FreeOtpImportError::BadContent => Self::BadContent,
+            FreeOtpImportError::Unsupported => Self::BadContent,
+            FreeOtpImportError::BadPassword => Self::BadPassword,
+            FreeOtpImportError::MissingPassword => Self::MissingPassword,
+            FreeOtpImportError::UnableToDecrypt => Self::DecryptionFailed,
+            FreeOtpImportError::MissingSecret => Self::BadContent,
+            FreeOtpImportError::InvalidConfig(_) => Self::BadContent,
+        }
+    }
+}
+
+pub use backup::parse_freeotp_backup;
+pub use plus::parse_freeotp_plus_json;
+pub use xml::parse_freeotp_xml;
@@ -0,0 +1,77 @@
+use super::FreeOtpImportError;
+use super::token::parse_tokens;
+use crate::parser::ImportResult;
+
+#[derive(Debug, serde::Deserialize)]
+struct FreeOtpPlusExport {
+    tokens: Vec<serde_json::Value>,
+}
+
+/// Parses the JSON file produced by the FreeOTP+ "Export JSON" option.
+pub fn parse_freeotp_plus_json(input: &str) -> Result<ImportResult, FreeOtpImportError> {
+    let parsed: FreeOtpPlusExport = serde_json::from_str(input).map_err(|e| {
+        warn!("Error parsing FreeOTP+ export: {e:?}");
+        FreeOtpImportError::BadContent
+    })?;
+
+    let tokens = parsed.tokens.into_iter().map(|value| (value, None)).collect();
+    Ok(parse_tokens(tokens))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents;
+    use proton_pass_totp::algorithm::Algorithm;
+
+    #[test]
+    fn can_import_freeotp_plus_json() {
+        let input = get_file_contents("freeotp/freeotp_plus.json");
+        let res = parse_freeotp_plus_json(&input).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 3);
+
+        let proton = &res.entries[0];
+        assert_eq!("Proton", proton.issuer());
+        assert_eq!("sometestaccount@proton.me", proton.name());
+        assert_eq!("JBSWY3DPEHPK3PXP", proton.secret());
+        // Token images are not imported
+        assert!(proton.note.is_none());
+
+        let amazon = &res.entries[1];
+        assert_eq!("Amazon", amazon.issuer());
+        assert_eq!("some@account.test", amazon.name());
+        assert_eq!("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", amazon.secret());
+        let params = amazon.get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA256, params.algorithm);
+        assert_eq!(8, params.digits);
+        assert_eq!(15, params.period);
+        assert!(amazon.note.is_none());
+
+        // Issuer taken from the label
+        let github = &res.entries[2];
+        assert_eq!("GitHub", github.issuer());
+        assert_eq!("octocat", github.name());
+        let params = github.get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA512, params.algorithm);
+        assert_eq!(7, params.digits);
+        assert_eq!(60, params.period);
+    }
+
+    #[test]
+    fn reports_unsupported_entries() {
+        let input = get_file_contents("freeotp/freeotp_plus.json");
+        let res = parse_freeotp_plus_json(&input).expect("should be able to parse");
+
+        assert_eq!(res.errors.len(), 2);
+        assert_eq!("Error in entry 2", res.errors[0].context);
+        assert!(res.errors[0].message.contains("Unsupported"));
+        assert_eq!("Error in entry 3", res.errors[1].context);
+        assert!(res.errors[1].message.contains("Unsupported"));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_freeotp_plus_json(r#"{"entries": []}"#).expect_err("should fail");
+        assert!(matches!(err, FreeOtpImportError::BadContent));
+    }
+}
@@ -0,0 +1,218 @@
+//! Minimal reader for the Java serialization format, only covering what FreeOTP uses for its backups:
+//! a single `java.util.HashMap<String, String>`.
+
+use super::FreeOtpImportError;
+
+const STREAM_MAGIC: [u8; 4] = [0xAC, 0xED, 0x00, 0x05];
+const BASE_HANDLE: u32 = 0x7E0000;
+
+const TC_NULL: u8 = 0x70;
+const TC_REFERENCE: u8 = 0x71;
+const TC_CLASSDESC: u8 = 0x72;
+const TC_OBJECT: u8 = 0x73;
+const TC_STRING: u8 = 0x74;
+const TC_BLOCKDATA: u8 = 0x77;
+const TC_ENDBLOCKDATA: u8 = 0x78;
+const TC_LONGSTRING: u8 = 0x7C;
+
+struct Reader<'a> {
+    input: &'a [u8],
+    position: usize,
+    // Every object gets a handle, we only need to be able to resolve strings
+    handles: Vec<Option<String>>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            position: 0,
+            handles: Vec::new(),
+        }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], FreeOtpImportError> {
+        let end = self.position.checked_add(len).ok_or(FreeOtpImportError::BadContent)?;
+        let bytes = self.input.get(self.position..end).ok_or_else(|| {
+            warn!("Unexpected end of FreeOTP backup");
+            FreeOtpImportError::BadContent
+        })?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, FreeOtpImportError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, FreeOtpImportError> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, FreeOtpImportError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, FreeOtpImportError> {
+        let bytes = self.read_bytes(8)?;
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        Ok(u64::from_be_bytes(buf))
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), FreeOtpImportError> {
+        let value = self.read_u8()?;
+        if value != expected {
+            warn!("Unexpected value in FreeOTP backup: expected {expected:#x}, got {value:#x}");
+            return Err(FreeOtpImportError::BadContent);
+        }
+        Ok(())
+    }
+
+    fn read_utf(&mut self, len: usize) -> Result<String, FreeOtpImportError> {
+        let bytes = self.read_bytes(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| {
+            warn!("Invalid string in FreeOTP backup: {e:?}");
+            FreeOtpImportError::BadContent
+        })
+    }
+
+    fn read_string(&mut self) -> Result<Option<String>, FreeOtpImportError> {
+        match self.read_u8()? {
+            TC_NULL => Ok(None),
+            TC_STRING => {
+                let len = self.read_u16()? as usize;
+                let value = self.read_utf(len)?;
+                self.handles.push(Some(value.clone()));
+                Ok(Some(value))
+            }
+            TC_LONGSTRING => {
+                let len = usize::try_from(self.read_u64()?).map_err(|_| FreeOtpImportError::BadContent)?;
+                let value = self.read_utf(len)?;
+                self.handles.push(Some(value.clone()));
+                Ok(Some(value))
+            }
+            TC_REFERENCE => {
+                let handle = self.read_u32()?.wrapping_sub(BASE_HANDLE) as usize;
+                match self.handles.get(handle) {
+                    Some(Some(value)) => Ok(Some(value.clone())),
+                    _ => {
+                        warn!("Invalid string reference in FreeOTP backup");
+                        Err(FreeOtpImportError::BadContent)
+                    }
+                }
+            }
+            other => {
+                warn!("Unsupported object type in FreeOTP backup: {other:#x}");
+                Err(FreeOtpImportError::Unsupported)
+            }
+        }
+    }
+
+    fn primitive_size(type_code: u8) -> Option<usize> {
+        match type_code {
+            b'B' | b'Z' => Some(1),
+            b'C' | b'S' => Some(2),
+            b'F' | b'I' => Some(4),
+            b'D' | b'J' => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Reads the class descriptor and returns the size of the serialized field values.
+    fn read_class_desc(&mut self) -> Result<usize, FreeOtpImportError> {
+        self.expect(TC_CLASSDESC)?;
+        let name_len = self.read_u16()? as usize;
+        let class_name = self.read_utf(name_len)?;
+        if class_name != "java.util.HashMap" {
+            warn!("Unexpected class in FreeOTP backup: {class_name}");
+            return Err(FreeOtpImportError::BadContent);
+        }
+        self.handles.push(None);
+
+        // serialVersionUID + flags
+        self.read_bytes(9)?;
+
+        let mut fields_size = 0;
+        let field_count = self.read_u16()?;
+        for _ in 0..field_count {
+            let type_code = self.read_u8()?;
+            let field_name_len = self.read_u16()? as usize;
+            self.read_bytes(field_name_len)?;
+            match Self::primitive_size(type_code) {
+                Some(size) => fields_size += size,
+                None => {
+                    warn!("Unsupported field type in FreeOTP backup: {type_code:#x}");
+                    return Err(FreeOtpImportError::Unsupported);
+                }
+            }
+        }
+
+        self.expect(TC_ENDBLOCKDATA)?;
+        // HashMap has no serializable superclass
+        self.expect(TC_NULL)?;
+        Ok(fields_size)
+    }
+}
+
+/// Reads a serialized `HashMap<String, String>`, returning its entries in stream order.
+pub fn read_string_map(input: &[u8]) -> Result<Vec<(String, String)>, FreeOtpImportError> {
+    let mut reader = Reader::new(input);
+    if reader.read_bytes(STREAM_MAGIC.len())? != STREAM_MAGIC {
+        warn!("FreeOTP backup is not a Java serialized stream");
+        return Err(FreeOtpImportError::BadContent);
+    }
+
+    reader.expect(TC_OBJECT)?;
+    let fields_size = reader.read_class_desc()?;
+    reader.handles.push(None);
+
+    // loadFactor + threshold
+    reader.read_bytes(fields_size)?;
+
+    // Capacity + size
+    reader.expect(TC_BLOCKDATA)?;
+    let block_len = reader.read_u8()? as usize;
+    if block_len != 8 {
+        warn!("Unexpected HashMap block size in FreeOTP backup: {block_len}");
+        return Err(FreeOtpImportError::BadContent);
+    }
+    let _capacity = reader.read_u32()?;
+    let size = reader.read_u32()? as usize;
+
+    let mut entries = Vec::new();
+    for _ in 0..size {
+        let key = reader.read_string()?;
+        let value = reader.read_string()?;
+        if let (Some(key), Some(value)) = (key, value) {
+            entries.push((key, value));
+        }
+    }
+
+    reader.expect(TC_ENDBLOCKDATA)?;
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents_raw;
+
+    #[test]
+    fn can_read_backup_map() {
+        let input = get_file_contents_raw("freeotp/externalBackup.xml");
+        let entries = read_string_map(&input).expect("should be able to read");
+        assert_eq!(entries.len(), 6);
+        assert!(entries.iter().any(|(key, _)| key == "masterKey"));
+        assert!(entries.iter().any(|(key, _)| key == "tokenOrder"));
+    }
+
+    #[test]
+    fn fails_on_truncated_input() {
+        let input = get_file_contents_raw("freeotp/externalBackup.xml");
+        let err = read_string_map(&input[..input.len() / 2]).expect_err("should fail");
+        assert!(matches!(err, FreeOtpImportError::BadContent));
+    }
+}
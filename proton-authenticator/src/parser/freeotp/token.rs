@@ -0,0 +1,176 @@
+use super::FreeOtpImportError;
+use crate::parser::validation::{validate_digits, validate_period};
+use crate::parser::{ImportError, ImportResult};
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use proton_pass_totp::algorithm::Algorithm;
+use proton_pass_totp::totp::TOTP;
+
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u32 = 30;
+
+pub(crate) const TOKEN_ORDER_KEY: &str = "tokenOrder";
+
+/// Token representation shared by FreeOTP and FreeOTP+. Secrets are stored as Java (signed) byte arrays.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FreeOtpToken {
+    #[serde(default)]
+    pub algo: Option<String>,
+    #[serde(default)]
+    pub digits: Option<u32>,
+    #[serde(default)]
+    pub issuer_ext: Option<String>,
+    #[serde(default)]
+    pub issuer_int: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub period: Option<u32>,
+    #[serde(default)]
+    pub secret: Option<Vec<i8>>,
+    #[serde(rename = "type", default)]
+    pub otp_type: Option<String>,
+}
+
+impl FreeOtpToken {
+    pub fn display_name(&self) -> String {
+        let (issuer, label) = self.issuer_and_label();
+        if issuer.is_empty() { label } else { issuer }
+    }
+
+    fn issuer_and_label(&self) -> (String, String) {
+        let label = self.label.clone().unwrap_or_default().trim().to_string();
+        let issuer = [&self.issuer_ext, &self.issuer_int]
+            .into_iter()
+            .flatten()
+            .map(|issuer| issuer.trim())
+            .find(|issuer| !issuer.is_empty());
+
+        match issuer {
+            Some(issuer) => {
+                // FreeOTP keeps the `Issuer:label` shape in the label when the otpauth URI had it
+                let label = match label.split_once(':') {
+                    Some((prefix, account)) if prefix.trim() == issuer && !account.trim().is_empty() => {
+                        account.trim().to_string()
+                    }
+                    _ => label,
+                };
+                let label = if label.is_empty() { issuer.to_string() } else { label };
+                (issuer.to_string(), label)
+            }
+            None => match label.split_once(':') {
+                Some((issuer, account)) if !issuer.trim().is_empty() && !account.trim().is_empty() => {
+                    (issuer.trim().to_string(), account.trim().to_string())
+                }
+                // Label as fallback issuer
+                _ => (label.clone(), label),
+            },
+        }
+    }
+
+    pub fn into_entry(self, secret: Option<Vec<u8>>) -> Result<AuthenticatorEntry, FreeOtpImportError> {
+        let secret = match secret {
+            Some(secret) => secret,
+            None => match &self.secret {
+                Some(secret) => secret.iter().map(|b| *b as u8).collect(),
+                None => return Err(FreeOtpImportError::MissingSecret),
+            },
+        };
+        if secret.is_empty() {
+            return Err(FreeOtpImportError::MissingSecret);
+        }
+
+        let name = self.display_name();
+        let (issuer, label) = self.issuer_and_label();
+
+        let content = match self.otp_type.as_deref().map(|t| t.to_uppercase()).as_deref() {
+            Some("TOTP") | None => {
+                let digits = self.digits.unwrap_or(DEFAULT_DIGITS);
+                let period = self.period.unwrap_or(DEFAULT_PERIOD);
+
+                if let Some(digits_error) = validate_digits(digits, &name) {
+                    return Err(FreeOtpImportError::InvalidConfig(digits_error));
+                }
+
+                if let Some(period_error) = validate_period(period, &name) {
+                    return Err(FreeOtpImportError::InvalidConfig(period_error));
+                }
+
+                let algorithm = match &self.algo {
+                    Some(algo) => match Algorithm::try_from(algo.as_str()) {
+                        Ok(a) => Some(a),
+                        Err(_) => {
+                            warn!("Unsupported algorithm for FreeOTP entry: {algo}");
+                            return Err(FreeOtpImportError::Unsupported);
+                        }
+                    },
+                    None => None,
+                };
+
+                AuthenticatorEntryContent::Totp(TOTP {
+                    label: Some(label),
+                    secret: base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &secret),
+                    issuer: Some(issuer),
+                    algorithm,
+                    digits: Some(digits as u8),
+                    period: Some(period as u16),
+                })
+            }
+            Some(other) => {
+                warn!("Unsupported FreeOTP entry type: {other}");
+                return Err(FreeOtpImportError::Unsupported);
+            }
+        };
+
+        Ok(AuthenticatorEntry {
+            content,
+            note: None,
+            id: AuthenticatorEntry::generate_id(),
+            tags: Vec::new(),
+            favourite: false,
+        })
+    }
+}
+
+/// Converts the raw token values into entries. Secrets can be provided separately for the backups that
+/// store them encrypted outside of the token.
+pub(crate) fn parse_tokens(tokens: Vec<(serde_json::Value, Option<Vec<u8>>)>) -> ImportResult {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, (value, secret)) in tokens.into_iter().enumerate() {
+        let token = match serde_json::from_value::<FreeOtpToken>(value) {
+            Ok(token) => token,
+            Err(e) => {
+                errors.push(ImportError {
+                    context: format!("Error in entry {idx}"),
+                    message: format!("Malformed entry: {e}"),
+                });
+                continue;
+            }
+        };
+
+        let name = token.display_name();
+        match token.into_entry(secret) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in entry {idx}"),
+                message: format!("Error importing entry '{name}': {e:?}"),
+            }),
+        }
+    }
+
+    ImportResult { entries, errors }
+}
+
+/// Sorts the token values following the `tokenOrder` list. Tokens missing from it are kept at the end.
+pub(crate) fn sort_tokens<T>(mut tokens: Vec<(String, T)>, order: Option<&str>) -> Result<Vec<T>, FreeOtpImportError> {
+    if let Some(order) = order {
+        let order: Vec<String> = serde_json::from_str(order).map_err(|e| {
+            warn!("Error parsing FreeOTP token order: {e:?}");
+            FreeOtpImportError::BadContent
+        })?;
+        tokens.sort_by_key(|(key, _)| order.iter().position(|o| o == key).unwrap_or(usize::MAX));
+    }
+
+    Ok(tokens.into_iter().map(|(_, token)| token).collect())
+}
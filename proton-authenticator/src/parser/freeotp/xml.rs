@@ -0,0 +1,73 @@
+use super::FreeOtpImportError;
+use super::token::{TOKEN_ORDER_KEY, parse_tokens, sort_tokens};
+use crate::parser::ImportResult;
+
+/// Parses the `tokens.xml` shared preferences file of the original FreeOTP app.
+pub fn parse_freeotp_xml(input: &str) -> Result<ImportResult, FreeOtpImportError> {
+    let document = roxmltree::Document::parse(input).map_err(|e| {
+        warn!("Error parsing FreeOTP xml: {e:?}");
+        FreeOtpImportError::BadContent
+    })?;
+
+    let root = document.root_element();
+    if !root.has_tag_name("map") {
+        warn!("FreeOTP xml root is not a map");
+        return Err(FreeOtpImportError::BadContent);
+    }
+
+    let mut order = None;
+    let mut tokens = Vec::new();
+    for node in root.children().filter(|n| n.has_tag_name("string")) {
+        let (Some(name), Some(value)) = (node.attribute("name"), node.text()) else {
+            continue;
+        };
+
+        if name == TOKEN_ORDER_KEY {
+            order = Some(value);
+            continue;
+        }
+
+        match serde_json::from_str::<serde_json::Value>(value) {
+            Ok(value) => tokens.push((name.to_string(), (value, None))),
+            Err(e) => {
+                warn!("Skipping FreeOTP xml value {name}: {e:?}");
+            }
+        }
+    }
+
+    let tokens = sort_tokens(tokens, order)?;
+    Ok(parse_tokens(tokens))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents;
+
+    #[test]
+    fn can_import_tokens_xml() {
+        let input = get_file_contents("freeotp/tokens.xml");
+        let res = parse_freeotp_xml(&input).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(res.entries.len(), 2);
+
+        // Sorted following the tokenOrder
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        assert_eq!("JBSWY3DPEHPK3PXP", res.entries[0].secret());
+
+        assert_eq!("Amazon", res.entries[1].issuer());
+        assert_eq!("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", res.entries[1].secret());
+        // Token images are not imported
+        assert!(res.entries[1].note.is_none());
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_freeotp_xml("<resources></resources>").expect_err("should fail");
+        assert!(matches!(err, FreeOtpImportError::BadContent));
+
+        let err = parse_freeotp_xml("not xml").expect_err("should fail");
+        assert!(matches!(err, FreeOtpImportError::BadContent));
+    }
+}
@@ -85,6 +85,8 @@ impl TryFrom<google::OtpParameters> for AuthenticatorEntry {
                     }),
                     note: None,
                     id: Self::generate_id(),
+                    tags: Vec::new(),
+                    favourite: false,
                 })
             }
             OtpType::OTP_TYPE_HOTP => {
@@ -158,8 +160,8 @@ mod test {
             _ => panic!("should be a TOTP entry"),
         };
 
-        assert_eq!("MYLABEL", entry.label.expect("should contain a label"));
-        assert_eq!("MYISSUER", entry.issuer.expect("should contain an issuer"));
+        assert_eq!("MYLABEL", entry.label.clone().expect("should contain a label"));
+        assert_eq!("MYISSUER", entry.issuer.clone().expect("should contain an issuer"));
         assert_eq!(Algorithm::SHA256, entry.algorithm.expect("should contain an algorithm"));
         assert_eq!(8, entry.digits.expect("should contain digits"));
 
@@ -0,0 +1,104 @@
+use super::KeePassImportError;
+use super::entry::entry_from_otp_uri;
+use crate::parser::{ImportError, ImportResult};
+
+const TITLE_HEADER: &str = "Title";
+const NOTES_HEADER: &str = "Notes";
+const TOTP_HEADER: &str = "TOTP";
+
+/// Parses a KeePassXC CSV export. Rows with an empty TOTP column are skipped.
+pub fn parse_keepass_csv(input: &str) -> Result<ImportResult, KeePassImportError> {
+    let mut csv_reader = csv::ReaderBuilder::new().flexible(true).from_reader(input.as_bytes());
+
+    let headers = match csv_reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            warn!("KeePass csv does not have headers: {e:?}");
+            return Err(KeePassImportError::BadContent);
+        }
+    };
+
+    let position = |name: &str| headers.iter().position(|h| h == name);
+    let (Some(title_idx), Some(totp_idx)) = (position(TITLE_HEADER), position(TOTP_HEADER)) else {
+        warn!("KeePass csv does not have the {TITLE_HEADER} and {TOTP_HEADER} headers");
+        return Err(KeePassImportError::BadContent);
+    };
+    let notes_idx = position(NOTES_HEADER);
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, result) in csv_reader.records().enumerate() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(ImportError {
+                    context: format!("Error in record {idx}"),
+                    message: format!("Malformed content: {e:?}"),
+                });
+                continue;
+            }
+        };
+
+        let uri = record.get(totp_idx).unwrap_or_default().trim();
+        if uri.is_empty() {
+            continue;
+        }
+
+        let title = record.get(title_idx).unwrap_or_default().trim();
+        let note = notes_idx
+            .and_then(|i| record.get(i))
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty());
+        match entry_from_otp_uri(uri, title, note) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in record {idx}"),
+                message: format!("Error importing entry '{title}': {e:?}"),
+            }),
+        }
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AuthenticatorEntryContent;
+    use crate::test_utils::get_file_contents;
+    use proton_pass_totp::algorithm::Algorithm;
+
+    #[test]
+    fn can_import_csv() {
+        let input = get_file_contents("keepass/keepassxc.csv");
+        let res = parse_keepass_csv(&input).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 4);
+
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        assert_eq!(Some("Main account".to_string()), res.entries[0].note);
+
+        let params = res.entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(8, params.digits);
+        assert_eq!(15, params.period);
+
+        assert!(matches!(res.entries[2].content, AuthenticatorEntryContent::Steam(_)));
+
+        assert_eq!("GitHub", res.entries[3].issuer());
+        let params = res.entries[3].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA256, params.algorithm);
+
+        // HOTP and invalid digits
+        assert_eq!(res.errors.len(), 2);
+        assert_eq!("Error in record 5", res.errors[0].context);
+        assert!(res.errors[0].message.contains("Unsupported"));
+        assert_eq!("Error in record 6", res.errors[1].context);
+        assert!(res.errors[1].message.contains("BadContent"));
+    }
+
+    #[test]
+    fn fails_on_missing_headers() {
+        let err = parse_keepass_csv("Group,Title,Username\nRoot,Proton,test").expect_err("should fail");
+        assert!(matches!(err, KeePassImportError::BadContent));
+    }
+}
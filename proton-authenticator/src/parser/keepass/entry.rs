@@ -0,0 +1,57 @@
+use super::KeePassImportError;
+use crate::steam::SteamTotp;
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use proton_pass_totp::totp::TOTP;
+
+/// Builds an entry out of the `otpauth://` URI KeePassXC stores for an entry.
+/// Steam entries are regular TOTP URIs with an additional `encoder=steam` parameter.
+pub(super) fn entry_from_otp_uri(
+    uri: &str,
+    title: &str,
+    note: Option<String>,
+) -> Result<AuthenticatorEntry, KeePassImportError> {
+    let parsed = url::Url::parse(uri).map_err(|e| {
+        warn!("Error parsing KeePass OTP uri: {e:?}");
+        KeePassImportError::BadContent
+    })?;
+    if parsed.scheme() != "otpauth" {
+        return Err(KeePassImportError::Unsupported);
+    }
+
+    let is_steam = parsed.query_pairs().any(|(k, v)| k == "encoder" && v == "steam");
+    let content = if is_steam {
+        let mut steam = SteamTotp::new_from_otp_uri(&parsed).map_err(|e| {
+            warn!("Error parsing KeePass Steam secret: {e:?}");
+            KeePassImportError::BadContent
+        })?;
+        if !title.is_empty() {
+            steam.set_name(Some(title.to_string()));
+        }
+        AuthenticatorEntryContent::Steam(steam)
+    } else {
+        if parsed.host_str() != Some("totp") {
+            warn!("Unsupported KeePass OTP type: {:?}", parsed.host_str());
+            return Err(KeePassImportError::Unsupported);
+        }
+
+        let mut totp = TOTP::from_uri(uri).map_err(|e| {
+            warn!("Error parsing KeePass TOTP uri: {e:?}");
+            KeePassImportError::BadContent
+        })?;
+        if totp.label.as_deref().unwrap_or_default().is_empty() {
+            totp.label = Some(title.to_string());
+        }
+        if totp.issuer.as_deref().unwrap_or_default().is_empty() {
+            totp.issuer = Some(title.to_string());
+        }
+        AuthenticatorEntryContent::Totp(totp)
+    };
+
+    Ok(AuthenticatorEntry {
+        content,
+        note,
+        id: AuthenticatorEntry::generate_id(),
+        tags: Vec::new(),
+        favourite: false,
+    })
+}
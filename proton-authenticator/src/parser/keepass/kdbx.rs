@@ -0,0 +1,93 @@
+use super::KeePassImportError;
+use super::entry::entry_from_otp_uri;
+use crate::parser::{ImportError, ImportResult};
+
+/// Imports the OTP entries of a password protected KDBX4 database. Entries without OTP settings are skipped.
+pub fn parse_keepass_kdbx(input: &[u8], password: &str) -> Result<ImportResult, KeePassImportError> {
+    let database = proton_pass_kdbx::read_kdbx(input, password).map_err(|e| {
+        warn!("Error reading KeePass database: {e:?}");
+        KeePassImportError::from(e)
+    })?;
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, kdbx_entry) in database.entries.iter().enumerate() {
+        let Some(uri) = kdbx_entry.otp_uri() else {
+            continue;
+        };
+
+        let title = kdbx_entry.title().trim();
+        let note = Some(kdbx_entry.notes().trim().to_string()).filter(|n| !n.is_empty());
+        match entry_from_otp_uri(&uri, title, note) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in entry {idx}"),
+                message: format!("Error importing entry '{title}': {e:?}"),
+            }),
+        }
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AuthenticatorEntryContent;
+    use crate::test_utils::{get_file_contents, get_file_contents_raw};
+    use proton_pass_totp::algorithm::Algorithm;
+
+    #[test]
+    fn can_import_kdbx() {
+        let input = get_file_contents_raw("keepass/keepass_argon2_chacha20.kdbx");
+        let password = get_file_contents("keepass/password");
+        let res = parse_keepass_kdbx(&input, &password).expect("should be able to parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(res.entries.len(), 5);
+
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        assert_eq!(Some("Main account".to_string()), res.entries[0].note);
+
+        // Legacy TOTP Seed / TOTP Settings fields
+        assert_eq!("Amazon", res.entries[1].issuer());
+        let params = res.entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(8, params.digits);
+        assert_eq!(15, params.period);
+
+        assert!(matches!(res.entries[2].content, AuthenticatorEntryContent::Steam(_)));
+        assert_eq!("Steam", res.entries[2].name());
+
+        // KeeOtp settings
+        let params = res.entries[3].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA512, params.algorithm);
+        assert_eq!(7, params.digits);
+        assert_eq!(60, params.period);
+
+        assert_eq!("GitHub", res.entries[4].issuer());
+        assert_eq!("octocat", res.entries[4].name());
+    }
+
+    #[test]
+    fn steam_entry_generates_correct_code() {
+        let input = get_file_contents_raw("keepass/keepass_argon2_chacha20.kdbx");
+        let password = get_file_contents("keepass/password");
+        let res = parse_keepass_kdbx(&input, &password).expect("should be able to parse");
+
+        let code = crate::AuthenticatorClient::generate_code(&res.entries[2], 1742298317).expect("should generate");
+        assert_eq!("VKFDN", code.current_code);
+    }
+
+    #[test]
+    fn wrong_password_returns_error() {
+        let input = get_file_contents_raw("keepass/keepass_argon2_chacha20.kdbx");
+        let err = parse_keepass_kdbx(&input, "invalid").expect_err("should return an error");
+        assert!(matches!(err, KeePassImportError::BadPassword));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_keepass_kdbx(b"invalid", "test").expect_err("should return an error");
+        assert!(matches!(err, KeePassImportError::BadContent));
+    }
+}
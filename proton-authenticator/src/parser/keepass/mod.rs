@@ -0,0 +1,41 @@
+use crate::parser::ThirdPartyImportError;
+use proton_pass_kdbx::KdbxError;
+
+mod csv;
+mod entry;
+mod kdbx;
+
+#[derive(Clone, Debug)]
+pub enum KeePassImportError {
+    BadContent,
+    BadPassword,
+    Unsupported,
+    UnableToDecrypt,
+}
+
+impl From<KeePassImportError> for ThirdPartyImportError {
+    fn from(e: KeePassImportError) -> Self {
+        match e {
+            KeePassImportError::BadContent => Self::BadContent,
+            KeePassImportError::BadPassword => Self::BadPassword,
+            KeePassImportError::Unsupported => Self::BadContent,
+            KeePassImportError::UnableToDecrypt => Self::DecryptionFailed,
+        }
+    }
+}
+
+impl From<KdbxError> for KeePassImportError {
+    fn from(e: KdbxError) -> Self {
+        match e {
+            KdbxError::BadPassword => Self::BadPassword,
+            KdbxError::UnsupportedVersion(_) | KdbxError::UnsupportedCipher | KdbxError::UnsupportedKdf => {
+                Self::Unsupported
+            }
+            KdbxError::CorruptedData => Self::UnableToDecrypt,
+            KdbxError::BadContent | KdbxError::BadXml(_) => Self::BadContent,
+        }
+    }
+}
+
+pub use csv::parse_keepass_csv;
+pub use kdbx::parse_keepass_kdbx;
@@ -62,6 +62,8 @@ impl TryFrom<Account> for AuthenticatorEntry {
                 period: Some(value.time_step),
             }),
             id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite: false,
         })
     }
 }
@@ -0,0 +1,86 @@
+use super::MicrosoftAuthenticatorImportError;
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use proton_pass_totp::algorithm::Algorithm;
+use proton_pass_totp::totp::TOTP;
+
+// Values of the `account_type` column
+const TYPE_TOTP: i64 = 0;
+const TYPE_MICROSOFT: i64 = 1;
+
+const TOTP_DIGITS: u8 = 6;
+const MICROSOFT_DIGITS: u8 = 8;
+const PERIOD: u16 = 30;
+
+/// Row of the `accounts` table of the Microsoft Authenticator `PhoneFactor` database.
+#[derive(Clone, Debug, serde::Deserialize)]
+pub(crate) struct MicrosoftAccount {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub oath_secret_key: Option<String>,
+    pub account_type: i64,
+}
+
+impl MicrosoftAccount {
+    pub fn display_name(&self) -> String {
+        self.name.clone().or_else(|| self.username.clone()).unwrap_or_default()
+    }
+
+    fn issuer_and_label(&self) -> (String, String) {
+        let name = self.name.clone().unwrap_or_default().trim().to_string();
+        let username = self.username.clone().unwrap_or_default().trim().to_string();
+        match (name.is_empty(), username.is_empty()) {
+            // Username as fallback issuer
+            (true, _) => (username.clone(), username),
+            // Name as fallback label
+            (false, true) => (name.clone(), name),
+            (false, false) => (name, username),
+        }
+    }
+}
+
+impl TryFrom<MicrosoftAccount> for AuthenticatorEntry {
+    type Error = MicrosoftAuthenticatorImportError;
+
+    fn try_from(account: MicrosoftAccount) -> Result<Self, Self::Error> {
+        // Personal Microsoft accounts use 8 digit codes, work accounts without a secret rely on push notifications
+        let digits = match account.account_type {
+            TYPE_TOTP => TOTP_DIGITS,
+            TYPE_MICROSOFT => MICROSOFT_DIGITS,
+            other => {
+                warn!("Unsupported Microsoft Authenticator account type: {other}");
+                return Err(MicrosoftAuthenticatorImportError::Unsupported);
+            }
+        };
+
+        let secret: String = account
+            .oath_secret_key
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_uppercase();
+        if secret.is_empty() {
+            return Err(MicrosoftAuthenticatorImportError::MissingSecret);
+        }
+
+        let (issuer, label) = account.issuer_and_label();
+        Ok(AuthenticatorEntry {
+            content: AuthenticatorEntryContent::Totp(TOTP {
+                label: Some(label),
+                secret,
+                issuer: Some(issuer),
+                algorithm: Some(Algorithm::SHA1),
+                digits: Some(digits),
+                period: Some(PERIOD),
+            }),
+            note: None,
+            id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite: false,
+        })
+    }
+}
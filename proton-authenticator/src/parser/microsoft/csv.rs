@@ -0,0 +1,89 @@
+use super::MicrosoftAuthenticatorImportError;
+use super::account::MicrosoftAccount;
+use crate::AuthenticatorEntry;
+use crate::parser::{ImportError, ImportResult};
+
+const REQUIRED_HEADERS: [&str; 2] = ["oath_secret_key", "account_type"];
+
+/// Parses the rows of the `accounts` table exported as CSV with headers (for instance with `sqlite3 -header -csv`).
+pub fn parse_microsoft_authenticator_csv(input: &str) -> Result<ImportResult, MicrosoftAuthenticatorImportError> {
+    let mut csv_reader = csv::ReaderBuilder::new().flexible(true).from_reader(input.as_bytes());
+
+    let headers = match csv_reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            warn!("Microsoft Authenticator csv does not have headers: {e:?}");
+            return Err(MicrosoftAuthenticatorImportError::BadContent);
+        }
+    };
+
+    for header in REQUIRED_HEADERS {
+        if !headers.iter().any(|h| h == header) {
+            warn!("Microsoft Authenticator csv does not have the {header} header");
+            return Err(MicrosoftAuthenticatorImportError::BadContent);
+        }
+    }
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, result) in csv_reader.records().enumerate() {
+        let account = match result.and_then(|record| record.deserialize::<MicrosoftAccount>(Some(&headers))) {
+            Ok(account) => account,
+            Err(e) => {
+                errors.push(ImportError {
+                    context: format!("Error in record {idx}"),
+                    message: format!("Malformed content: {e:?}"),
+                });
+                continue;
+            }
+        };
+
+        let name = account.display_name();
+        match AuthenticatorEntry::try_from(account) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in record {idx}"),
+                message: format!("Error importing entry '{name}': {e:?}"),
+            }),
+        }
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents;
+
+    #[test]
+    fn can_import_csv() {
+        let input = get_file_contents("microsoft/accounts.csv");
+        let res = parse_microsoft_authenticator_csv(&input).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 3);
+
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        let params = res.entries[0].get_totp_parameters().expect("should have parameters");
+        assert_eq!(6, params.digits);
+
+        assert_eq!("Microsoft", res.entries[1].issuer());
+        assert_eq!("some@outlook.com", res.entries[1].name());
+        let params = res.entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(8, params.digits);
+
+        assert_eq!("GitHub", res.entries[2].issuer());
+        assert_eq!("JBSWY3DPEHPK3PXQ", res.entries[2].secret());
+
+        // Work account without a secret
+        assert_eq!(res.errors.len(), 1);
+        assert_eq!("Error in record 2", res.errors[0].context);
+        assert!(res.errors[0].message.contains("Unsupported"));
+    }
+
+    #[test]
+    fn fails_on_missing_headers() {
+        let err = parse_microsoft_authenticator_csv("name,username\nProton,test").expect_err("should fail");
+        assert!(matches!(err, MicrosoftAuthenticatorImportError::BadContent));
+    }
+}
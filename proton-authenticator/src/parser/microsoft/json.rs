@@ -0,0 +1,73 @@
+use super::MicrosoftAuthenticatorImportError;
+use super::account::MicrosoftAccount;
+use crate::AuthenticatorEntry;
+use crate::parser::{ImportError, ImportResult};
+
+/// Parses the rows of the `accounts` table exported as JSON (for instance with `sqlite3 -json`).
+pub fn parse_microsoft_authenticator_json(input: &str) -> Result<ImportResult, MicrosoftAuthenticatorImportError> {
+    let parsed: Vec<serde_json::Value> = serde_json::from_str(input).map_err(|e| {
+        warn!("Error parsing Microsoft Authenticator json: {e:?}");
+        MicrosoftAuthenticatorImportError::BadContent
+    })?;
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, value) in parsed.into_iter().enumerate() {
+        let account = match serde_json::from_value::<MicrosoftAccount>(value) {
+            Ok(account) => account,
+            Err(e) => {
+                errors.push(ImportError {
+                    context: format!("Error in entry {idx}"),
+                    message: format!("Malformed entry: {e}"),
+                });
+                continue;
+            }
+        };
+
+        let name = account.display_name();
+        match AuthenticatorEntry::try_from(account) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in entry {idx}"),
+                message: format!("Error importing entry '{name}': {e:?}"),
+            }),
+        }
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents;
+
+    #[test]
+    fn can_import_json() {
+        let input = get_file_contents("microsoft/accounts.json");
+        let res = parse_microsoft_authenticator_json(&input).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 3);
+
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        assert_eq!("JBSWY3DPEHPK3PXP", res.entries[0].secret());
+
+        // Microsoft accounts use 8 digits
+        assert_eq!("Microsoft", res.entries[1].issuer());
+        let params = res.entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(8, params.digits);
+
+        // Secrets get normalized
+        assert_eq!("JBSWY3DPEHPK3PXQ", res.entries[2].secret());
+
+        assert_eq!(res.errors.len(), 2);
+        assert!(res.errors[0].message.contains("Unsupported"));
+        assert!(res.errors[1].message.contains("Malformed"));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_microsoft_authenticator_json(r#"{"accounts": []}"#).expect_err("should fail");
+        assert!(matches!(err, MicrosoftAuthenticatorImportError::BadContent));
+    }
+}
@@ -0,0 +1,25 @@
+use crate::parser::ThirdPartyImportError;
+
+mod account;
+mod csv;
+mod json;
+
+#[derive(Clone, Debug)]
+pub enum MicrosoftAuthenticatorImportError {
+    BadContent,
+    MissingSecret,
+    Unsupported,
+}
+
+impl From<MicrosoftAuthenticatorImportError> for ThirdPartyImportError {
+    fn from(e: MicrosoftAuthenticatorImportError) -> Self {
+        match e {
+            MicrosoftAuthenticatorImportError::BadContent => Self::BadContent,
+            MicrosoftAuthenticatorImportError::MissingSecret => Self::BadContent,
+            MicrosoftAuthenticatorImportError::Unsupported => Self::BadContent,
+        }
+    }
+}
+
+pub use csv::parse_microsoft_authenticator_csv;
+pub use json::parse_microsoft_authenticator_json;
@@ -1,12 +1,21 @@
 use crate::AuthenticatorEntry;
 
 pub mod aegis;
+pub mod andotp;
+pub mod authy;
 pub mod bitwarden;
+pub mod detect;
 pub mod ente;
+pub mod freeotp;
 pub mod google;
+pub mod keepass;
 pub mod lastpass;
+pub mod microsoft;
+pub mod otpauth;
 pub mod pass;
 pub mod proton_authenticator;
+pub mod raivo;
+pub mod steam;
 pub mod twofas;
 pub mod validation;
 
@@ -0,0 +1,86 @@
+//! Helpers to navigate `NSKeyedArchiver` plists, where objects live in a flat `$objects` list and reference
+//! each other through UIDs.
+
+use super::OtpAuthImportError;
+use plist::{Dictionary, Value};
+
+pub struct KeyedArchive {
+    top: Dictionary,
+    objects: Vec<Value>,
+}
+
+impl KeyedArchive {
+    pub fn parse(input: &[u8]) -> Result<Self, OtpAuthImportError> {
+        let value = Value::from_reader(std::io::Cursor::new(input)).map_err(|e| {
+            warn!("Error parsing OTP Auth plist: {e:?}");
+            OtpAuthImportError::BadContent
+        })?;
+
+        let mut dict = value.into_dictionary().ok_or(OtpAuthImportError::BadContent)?;
+        let objects = dict
+            .remove("$objects")
+            .and_then(Value::into_array)
+            .ok_or(OtpAuthImportError::BadContent)?;
+        let top = dict
+            .remove("$top")
+            .and_then(Value::into_dictionary)
+            .ok_or(OtpAuthImportError::BadContent)?;
+
+        Ok(Self { top, objects })
+    }
+
+    /// Follows the reference if the value is a UID. `$null` references are resolved to `None`.
+    pub fn resolve<'a>(&'a self, value: &'a Value) -> Option<&'a Value> {
+        let value = match value {
+            Value::Uid(uid) => self.objects.get(uid.get() as usize)?,
+            value => value,
+        };
+        match value {
+            Value::String(s) if s == "$null" => None,
+            value => Some(value),
+        }
+    }
+
+    pub fn top(&self, key: &str) -> Option<&Value> {
+        self.resolve(self.top.get(key)?)
+    }
+
+    pub fn class_name<'a>(&'a self, object: &'a Dictionary) -> Option<&'a str> {
+        let class = self.resolve(object.get("$class")?)?.as_dictionary()?;
+        class.get("$classname")?.as_string()
+    }
+
+    /// Returns every object of the given class, in archive order.
+    pub fn objects_of_class(&self, class_name: &str) -> Vec<&Dictionary> {
+        self.objects
+            .iter()
+            .filter_map(Value::as_dictionary)
+            .filter(|object| self.class_name(object) == Some(class_name))
+            .collect()
+    }
+
+    pub fn get<'a>(&'a self, object: &'a Dictionary, key: &str) -> Option<&'a Value> {
+        self.resolve(object.get(key)?)
+    }
+
+    pub fn get_string(&self, object: &Dictionary, key: &str) -> Option<String> {
+        self.get(object, key)?.as_string().map(|s| s.to_string())
+    }
+
+    pub fn get_integer(&self, object: &Dictionary, key: &str) -> Option<i64> {
+        self.get(object, key)?.as_signed_integer()
+    }
+
+    /// Data can either be stored raw or wrapped into an `NSMutableData` object.
+    pub fn get_data(&self, object: &Dictionary, key: &str) -> Option<Vec<u8>> {
+        self.as_data(self.get(object, key)?)
+    }
+
+    pub fn as_data(&self, value: &Value) -> Option<Vec<u8>> {
+        match value {
+            Value::Data(data) => Some(data.clone()),
+            Value::Dictionary(dict) => self.resolve(dict.get("NS.data")?)?.as_data().map(|d| d.to_vec()),
+            _ => None,
+        }
+    }
+}
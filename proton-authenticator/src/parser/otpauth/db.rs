@@ -0,0 +1,216 @@
+use super::OtpAuthImportError;
+use super::archive::KeyedArchive;
+use super::rncryptor;
+use crate::parser::validation::{validate_digits, validate_period};
+use crate::parser::{ImportError, ImportResult};
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use plist::Dictionary;
+use proton_pass_totp::algorithm::Algorithm;
+use proton_pass_totp::totp::TOTP;
+
+const ACCOUNT_CLASS: &str = "ACOTPAccount";
+const WRAPPED_DATA_KEY: &str = "WrappedData";
+
+const TYPE_TOTP: i64 = 2;
+
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u32 = 30;
+
+fn parse_algorithm(value: Option<i64>) -> Result<Option<Algorithm>, OtpAuthImportError> {
+    match value {
+        None | Some(0) => Ok(None),
+        Some(1) => Ok(Some(Algorithm::SHA1)),
+        Some(2) => Ok(Some(Algorithm::SHA256)),
+        Some(3) => Ok(Some(Algorithm::SHA512)),
+        Some(other) => {
+            // 4 is MD5
+            warn!("Unsupported OTP Auth algorithm: {other}");
+            Err(OtpAuthImportError::Unsupported)
+        }
+    }
+}
+
+fn issuer_and_label(archive: &KeyedArchive, account: &Dictionary) -> (String, String) {
+    let label = archive
+        .get_string(account, "label")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let issuer = archive
+        .get_string(account, "issuer")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    if !issuer.is_empty() {
+        let label = if label.is_empty() { issuer.clone() } else { label };
+        return (issuer, label);
+    }
+
+    match label.split_once(':') {
+        Some((issuer, account)) if !issuer.trim().is_empty() && !account.trim().is_empty() => {
+            (issuer.trim().to_string(), account.trim().to_string())
+        }
+        // Label as fallback issuer
+        _ => (label.clone(), label),
+    }
+}
+
+fn parse_account(archive: &KeyedArchive, account: &Dictionary) -> Result<AuthenticatorEntry, OtpAuthImportError> {
+    let account_type = archive.get_integer(account, "type");
+    if account_type != Some(TYPE_TOTP) {
+        warn!("Unsupported OTP Auth account type: {account_type:?}");
+        return Err(OtpAuthImportError::Unsupported);
+    }
+
+    let secret = archive
+        .get_data(account, "secret")
+        .ok_or(OtpAuthImportError::BadContent)?;
+    if secret.is_empty() {
+        return Err(OtpAuthImportError::BadContent);
+    }
+
+    let (issuer, label) = issuer_and_label(archive, account);
+    let digits = archive
+        .get_integer(account, "digits")
+        .map(|d| d as u32)
+        .unwrap_or(DEFAULT_DIGITS);
+    let period = archive
+        .get_integer(account, "period")
+        .map(|p| p as u32)
+        .unwrap_or(DEFAULT_PERIOD);
+
+    if let Some(digits_error) = validate_digits(digits, &issuer) {
+        return Err(OtpAuthImportError::InvalidConfig(digits_error));
+    }
+
+    if let Some(period_error) = validate_period(period, &issuer) {
+        return Err(OtpAuthImportError::InvalidConfig(period_error));
+    }
+
+    let algorithm = parse_algorithm(archive.get_integer(account, "algorithm"))?;
+
+    Ok(AuthenticatorEntry {
+        content: AuthenticatorEntryContent::Totp(TOTP {
+            label: Some(label),
+            secret: base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &secret),
+            issuer: Some(issuer),
+            algorithm,
+            digits: Some(digits as u8),
+            period: Some(period as u16),
+        }),
+        note: None,
+        id: AuthenticatorEntry::generate_id(),
+        tags: Vec::new(),
+        favourite: false,
+    })
+}
+
+fn parse_accounts(archive: &KeyedArchive) -> ImportResult {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, account) in archive.objects_of_class(ACCOUNT_CLASS).into_iter().enumerate() {
+        match parse_account(archive, account) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                let (name, _) = issuer_and_label(archive, account);
+                errors.push(ImportError {
+                    context: format!("Error in entry {idx}"),
+                    message: format!("Error importing entry '{name}': {e:?}"),
+                });
+            }
+        }
+    }
+
+    ImportResult { entries, errors }
+}
+
+/// Parses an encrypted OTP Auth (iOS) `.otpauthdb` backup. The file is a keyed archive holding the
+/// RNCryptor encrypted account archive.
+pub fn parse_otpauth_db(input: &[u8], password: Option<String>) -> Result<ImportResult, OtpAuthImportError> {
+    let outer = KeyedArchive::parse(input)?;
+    let wrapped = match outer.top(WRAPPED_DATA_KEY) {
+        Some(value) => outer.as_data(value).ok_or(OtpAuthImportError::BadContent)?,
+        None => {
+            warn!("OTP Auth backup does not contain wrapped data");
+            return Err(OtpAuthImportError::BadContent);
+        }
+    };
+
+    let password = match password {
+        Some(password) => password,
+        None => {
+            warn!("Tried to import OTP Auth backup without a password");
+            return Err(OtpAuthImportError::MissingPassword);
+        }
+    };
+
+    let decrypted = rncryptor::decrypt(&wrapped, &password)?;
+    let inner = KeyedArchive::parse(&decrypted)?;
+    Ok(parse_accounts(&inner))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{get_file_contents, get_file_contents_raw};
+
+    #[test]
+    fn can_import_backup() {
+        let input = get_file_contents_raw("otpauth/backup.otpauthdb");
+        let password = get_file_contents("otpauth/password");
+        let res = parse_otpauth_db(&input, Some(password)).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 3);
+
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        assert_eq!("JBSWY3DPEHPK3PXP", res.entries[0].secret());
+
+        assert_eq!("Amazon", res.entries[1].issuer());
+        assert_eq!("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", res.entries[1].secret());
+        let params = res.entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA256, params.algorithm);
+        assert_eq!(8, params.digits);
+        assert_eq!(15, params.period);
+
+        // Issuer taken from the label
+        assert_eq!("Legacy", res.entries[2].issuer());
+        assert_eq!("legacy@test.email", res.entries[2].name());
+        let params = res.entries[2].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA512, params.algorithm);
+        assert_eq!(60, params.period);
+    }
+
+    #[test]
+    fn reports_unsupported_entries() {
+        let input = get_file_contents_raw("otpauth/backup.otpauthdb");
+        let password = get_file_contents("otpauth/password");
+        let res = parse_otpauth_db(&input, Some(password)).expect("should be able to parse");
+
+        assert_eq!(res.errors.len(), 2);
+        assert!(res.errors[0].message.contains("Counter"));
+        assert!(res.errors[0].message.contains("Unsupported"));
+        assert!(res.errors[1].message.contains("BadAlgo"));
+        assert!(res.errors[1].message.contains("Unsupported"));
+    }
+
+    #[test]
+    fn missing_password_returns_error() {
+        let input = get_file_contents_raw("otpauth/backup.otpauthdb");
+        let err = parse_otpauth_db(&input, None).expect_err("should fail");
+        assert!(matches!(err, OtpAuthImportError::MissingPassword));
+    }
+
+    #[test]
+    fn wrong_password_returns_error() {
+        let input = get_file_contents_raw("otpauth/backup.otpauthdb");
+        let err = parse_otpauth_db(&input, Some("invalid".to_string())).expect_err("should fail");
+        assert!(matches!(err, OtpAuthImportError::BadPassword));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_otpauth_db(b"not a plist", Some("test".to_string())).expect_err("should fail");
+        assert!(matches!(err, OtpAuthImportError::BadContent));
+    }
+}
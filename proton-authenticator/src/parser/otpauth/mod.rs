@@ -0,0 +1,30 @@
+use crate::parser::ThirdPartyImportError;
+
+mod archive;
+mod db;
+mod rncryptor;
+
+#[derive(Clone, Debug)]
+pub enum OtpAuthImportError {
+    BadContent,
+    BadPassword,
+    MissingPassword,
+    UnableToDecrypt,
+    Unsupported,
+    InvalidConfig(String),
+}
+
+impl From<OtpAuthImportError> for ThirdPartyImportError {
+    fn from(e: OtpAuthImportError) -> Self {
+        match e {
+            OtpAuthImportError::BadContent => Self::BadContent,
+            OtpAuthImportError::BadPassword => Self::BadPassword,
+            OtpAuthImportError::MissingPassword => Self::MissingPassword,
+            OtpAuthImportError::UnableToDecrypt => Self::DecryptionFailed,
+            OtpAuthImportError::Unsupported => Self::BadContent,
+            OtpAuthImportError::InvalidConfig(_) => Self::BadContent,
+        }
+    }
+}
+
+pub use db::parse_otpauth_db;
@@ -0,0 +1,75 @@
+use super::OtpAuthImportError;
+use aes::Aes256;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use pbkdf2::pbkdf2;
+use sha1::Sha1;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+// RNCryptor v3 password based format:
+// [version: 1][options: 1][encryption salt: 8][hmac salt: 8][iv: 16][ciphertext][hmac: 32]
+const VERSION: u8 = 3;
+const OPTION_USES_PASSWORD: u8 = 1;
+const SALT_LENGTH: usize = 8;
+const IV_LENGTH: usize = 16;
+const HMAC_LENGTH: usize = 32;
+const KEY_LENGTH: usize = 32;
+const HEADER_LENGTH: usize = 2 + SALT_LENGTH * 2 + IV_LENGTH;
+const ITERATIONS: u32 = 10_000;
+
+type Aes256CbcDec = cbc::Decryptor<Aes256>;
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LENGTH]>, OtpAuthImportError> {
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    pbkdf2::<Hmac<Sha1>>(password.as_bytes(), salt, ITERATIONS, key.as_mut_slice()).map_err(|e| {
+        warn!("Error deriving RNCryptor key: {e:?}");
+        OtpAuthImportError::UnableToDecrypt
+    })?;
+    Ok(key)
+}
+
+pub fn decrypt(input: &[u8], password: &str) -> Result<Zeroizing<Vec<u8>>, OtpAuthImportError> {
+    if input.len() < HEADER_LENGTH + HMAC_LENGTH {
+        warn!("RNCryptor data is too short");
+        return Err(OtpAuthImportError::BadContent);
+    }
+    if input[0] != VERSION || input[1] != OPTION_USES_PASSWORD {
+        warn!(
+            "Unsupported RNCryptor format: version {} options {}",
+            input[0], input[1]
+        );
+        return Err(OtpAuthImportError::Unsupported);
+    }
+
+    let encryption_salt = &input[2..2 + SALT_LENGTH];
+    let hmac_salt = &input[2 + SALT_LENGTH..2 + SALT_LENGTH * 2];
+    let iv = &input[2 + SALT_LENGTH * 2..HEADER_LENGTH];
+    let (message, expected_hmac) = input.split_at(input.len() - HMAC_LENGTH);
+    let ciphertext = &message[HEADER_LENGTH..];
+
+    let hmac_key = derive_key(password, hmac_salt)?;
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(hmac_key.as_slice()).map_err(|e| {
+        warn!("Error creating RNCryptor hmac: {e:?}");
+        OtpAuthImportError::UnableToDecrypt
+    })?;
+    mac.update(message);
+    // A mismatch means either a tampered file or, much more likely, a wrong password
+    mac.verify_slice(expected_hmac)
+        .map_err(|_| OtpAuthImportError::BadPassword)?;
+
+    let encryption_key = derive_key(password, encryption_salt)?;
+    let decryptor = Aes256CbcDec::new_from_slices(encryption_key.as_slice(), iv).map_err(|e| {
+        warn!("Error creating RNCryptor cipher: {e:?}");
+        OtpAuthImportError::UnableToDecrypt
+    })?;
+
+    decryptor
+        .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+        .map(Zeroizing::new)
+        .map_err(|e| {
+            warn!("Error decrypting RNCryptor data: {e:?}");
+            OtpAuthImportError::UnableToDecrypt
+        })
+}
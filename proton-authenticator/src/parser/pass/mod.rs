@@ -242,6 +242,8 @@ fn create_entry_from_uri(uri: &str, name: &str, note: Option<String>) -> Result<
         content,
         note,
         id: AuthenticatorEntry::generate_id(),
+        tags: Vec::new(),
+        favourite: false,
     })
 }
 
@@ -0,0 +1,199 @@
+use super::RaivoImportError;
+use crate::parser::validation::{validate_digits, validate_period};
+use crate::parser::{ImportError, ImportResult};
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use proton_pass_totp::algorithm::Algorithm;
+use proton_pass_totp::totp::TOTP;
+
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u32 = 30;
+
+// Raivo stores numbers as strings, but be lenient and accept both
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(u32),
+    String(String),
+}
+
+impl NumberOrString {
+    fn as_u32(&self) -> Option<u32> {
+        match self {
+            NumberOrString::Number(n) => Some(*n),
+            NumberOrString::String(s) => s.trim().parse().ok(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+struct RaivoEntry {
+    secret: String,
+    #[serde(default)]
+    issuer: Option<String>,
+    #[serde(default)]
+    account: Option<String>,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    algorithm: Option<String>,
+    #[serde(default)]
+    digits: Option<NumberOrString>,
+    #[serde(default)]
+    timer: Option<NumberOrString>,
+}
+
+impl RaivoEntry {
+    fn issuer_and_label(&self) -> (String, String) {
+        let issuer = self.issuer.clone().unwrap_or_default().trim().to_string();
+        let account = self.account.clone().unwrap_or_default().trim().to_string();
+        match (issuer.is_empty(), account.is_empty()) {
+            // Account as fallback issuer
+            (true, _) => (account.clone(), account),
+            // Issuer as fallback label
+            (false, true) => (issuer.clone(), issuer),
+            (false, false) => (issuer, account),
+        }
+    }
+
+    fn parse_number(value: &Option<NumberOrString>, default: u32, name: &str) -> Result<u32, RaivoImportError> {
+        match value {
+            Some(value) => value
+                .as_u32()
+                .ok_or_else(|| RaivoImportError::InvalidConfig(format!("Invalid number for {name}"))),
+            None => Ok(default),
+        }
+    }
+}
+
+impl TryFrom<RaivoEntry> for AuthenticatorEntry {
+    type Error = RaivoImportError;
+
+    fn try_from(entry: RaivoEntry) -> Result<Self, Self::Error> {
+        if let Some(kind) = &entry.kind
+            && !kind.eq_ignore_ascii_case("TOTP")
+        {
+            warn!("Unsupported Raivo entry kind: {kind}");
+            return Err(RaivoImportError::Unsupported);
+        }
+
+        let (issuer, label) = entry.issuer_and_label();
+        let digits = RaivoEntry::parse_number(&entry.digits, DEFAULT_DIGITS, &issuer)?;
+        let period = RaivoEntry::parse_number(&entry.timer, DEFAULT_PERIOD, &issuer)?;
+
+        if let Some(digits_error) = validate_digits(digits, &issuer) {
+            return Err(RaivoImportError::InvalidConfig(digits_error));
+        }
+
+        if let Some(period_error) = validate_period(period, &issuer) {
+            return Err(RaivoImportError::InvalidConfig(period_error));
+        }
+
+        let algorithm = match &entry.algorithm {
+            Some(algo) => match Algorithm::try_from(algo.as_str()) {
+                Ok(a) => Some(a),
+                Err(_) => {
+                    warn!("Unsupported algorithm for Raivo entry: {algo}");
+                    return Err(RaivoImportError::Unsupported);
+                }
+            },
+            None => None,
+        };
+
+        Ok(AuthenticatorEntry {
+            content: AuthenticatorEntryContent::Totp(TOTP {
+                label: Some(label),
+                secret: entry.secret,
+                issuer: Some(issuer),
+                algorithm,
+                digits: Some(digits as u8),
+                period: Some(period as u16),
+            }),
+            note: None,
+            id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite: false,
+        })
+    }
+}
+
+/// Parses the `raivo-otp-export.json` file contained in the Raivo OTP export archive.
+pub fn parse_raivo_json(input: &str) -> Result<ImportResult, RaivoImportError> {
+    let parsed: Vec<serde_json::Value> = serde_json::from_str(input).map_err(|e| {
+        warn!("Error parsing Raivo export: {e:?}");
+        RaivoImportError::BadContent
+    })?;
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, value) in parsed.into_iter().enumerate() {
+        let entry = match serde_json::from_value::<RaivoEntry>(value) {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(ImportError {
+                    context: format!("Error in entry {idx}"),
+                    message: format!("Malformed entry: {e}"),
+                });
+                continue;
+            }
+        };
+
+        let (name, _) = entry.issuer_and_label();
+        match AuthenticatorEntry::try_from(entry) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ImportError {
+                context: format!("Error in entry {idx}"),
+                message: format!("Error importing entry '{name}': {e:?}"),
+            }),
+        }
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents;
+
+    #[test]
+    fn can_import_raivo_json() {
+        let input = get_file_contents("raivo/raivo-otp-export.json");
+        let res = parse_raivo_json(&input).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 3);
+
+        assert_eq!("Proton", res.entries[0].issuer());
+        assert_eq!("sometestaccount@proton.me", res.entries[0].name());
+        assert_eq!("JBSWY3DPEHPK3PXP", res.entries[0].secret());
+
+        let params = res.entries[1].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA256, params.algorithm);
+        assert_eq!(8, params.digits);
+        assert_eq!(15, params.period);
+
+        // Numeric values and account as fallback issuer
+        assert_eq!("NoIssuer", res.entries[2].issuer());
+        assert_eq!("NoIssuer", res.entries[2].name());
+        let params = res.entries[2].get_totp_parameters().expect("should have parameters");
+        assert_eq!(Algorithm::SHA512, params.algorithm);
+        assert_eq!(7, params.digits);
+        assert_eq!(60, params.period);
+    }
+
+    #[test]
+    fn reports_invalid_entries() {
+        let input = get_file_contents("raivo/raivo-otp-export.json");
+        let res = parse_raivo_json(&input).expect("should be able to parse");
+
+        assert_eq!(res.errors.len(), 2);
+        assert_eq!("Error in entry 2", res.errors[0].context);
+        assert!(res.errors[0].message.contains("Unsupported"));
+        assert_eq!("Error in entry 3", res.errors[1].context);
+        assert!(res.errors[1].message.contains("digits"));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_raivo_json(r#"{"entries": []}"#).expect_err("should fail");
+        assert!(matches!(err, RaivoImportError::BadContent));
+    }
+}
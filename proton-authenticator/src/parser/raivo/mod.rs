@@ -0,0 +1,32 @@
+use crate::parser::ThirdPartyImportError;
+
+mod json;
+mod zip;
+
+#[derive(Clone, Debug)]
+pub enum RaivoImportError {
+    BadContent,
+    BadZip,
+    MissingDataFile,
+    BadPassword,
+    MissingPassword,
+    Unsupported,
+    InvalidConfig(String),
+}
+
+impl From<RaivoImportError> for ThirdPartyImportError {
+    fn from(e: RaivoImportError) -> Self {
+        match e {
+            RaivoImportError::BadContent => Self::BadContent,
+            RaivoImportError::BadZip => Self::BadContent,
+            RaivoImportError::MissingDataFile => Self::BadContent,
+            RaivoImportError::BadPassword => Self::BadPassword,
+            RaivoImportError::MissingPassword => Self::MissingPassword,
+            RaivoImportError::Unsupported => Self::BadContent,
+            RaivoImportError::InvalidConfig(_) => Self::BadContent,
+        }
+    }
+}
+
+pub use json::parse_raivo_json;
+pub use zip::parse_raivo_zip;
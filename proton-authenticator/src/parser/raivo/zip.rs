@@ -0,0 +1,95 @@
+use super::RaivoImportError;
+use super::json::parse_raivo_json;
+use crate::parser::ImportResult;
+use std::io::Read;
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+const EXPORT_FILE_NAME: &str = "raivo-otp-export.json";
+
+/// Parses the password protected ZIP archive produced by the Raivo OTP export.
+pub fn parse_raivo_zip(input: &[u8], password: Option<String>) -> Result<ImportResult, RaivoImportError> {
+    let cursor = std::io::Cursor::new(input);
+    let mut archive = ZipArchive::new(cursor).map_err(|e| {
+        warn!("Error opening Raivo zip: {e:?}");
+        RaivoImportError::BadZip
+    })?;
+
+    let index = archive
+        .file_names()
+        .position(|name| name.rsplit('/').next() == Some(EXPORT_FILE_NAME))
+        .ok_or(RaivoImportError::MissingDataFile)?;
+
+    let file = match &password {
+        Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+        None => archive.by_index(index),
+    };
+    let mut file = file.map_err(|e| match e {
+        ZipError::InvalidPassword => RaivoImportError::BadPassword,
+        ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => RaivoImportError::MissingPassword,
+        e => {
+            warn!("Error opening Raivo export inside zip: {e:?}");
+            RaivoImportError::BadZip
+        }
+    })?;
+
+    let mut content = String::new();
+    file.read_to_string(&mut content).map_err(|e| {
+        // ZipCrypto only has a one byte check, so a wrong password can also end up here
+        warn!("Error reading Raivo export inside zip: {e:?}");
+        if password.is_some() {
+            RaivoImportError::BadPassword
+        } else {
+            RaivoImportError::BadContent
+        }
+    })?;
+
+    parse_raivo_json(&content)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{get_file_contents, get_file_contents_raw};
+
+    #[test]
+    fn can_import_aes_zip() {
+        let input = get_file_contents_raw("raivo/raivo-otp-export.zip");
+        let password = get_file_contents("raivo/password");
+        let res = parse_raivo_zip(&input, Some(password)).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 3);
+        assert_eq!(res.errors.len(), 2);
+        assert_eq!("Proton", res.entries[0].issuer());
+    }
+
+    #[test]
+    fn can_import_zipcrypto_zip() {
+        let input = get_file_contents_raw("raivo/raivo-otp-export-zipcrypto.zip");
+        let password = get_file_contents("raivo/password");
+        let res = parse_raivo_zip(&input, Some(password)).expect("should be able to parse");
+        assert_eq!(res.entries.len(), 3);
+        assert_eq!("Amazon", res.entries[1].issuer());
+    }
+
+    #[test]
+    fn missing_password_returns_error() {
+        let input = get_file_contents_raw("raivo/raivo-otp-export.zip");
+        let err = parse_raivo_zip(&input, None).expect_err("should fail");
+        assert!(matches!(err, RaivoImportError::MissingPassword));
+    }
+
+    #[test]
+    fn wrong_password_returns_error() {
+        for file in ["raivo/raivo-otp-export.zip", "raivo/raivo-otp-export-zipcrypto.zip"] {
+            let input = get_file_contents_raw(file);
+            let err = parse_raivo_zip(&input, Some("invalid".to_string())).expect_err("should fail");
+            assert!(matches!(err, RaivoImportError::BadPassword));
+        }
+    }
+
+    #[test]
+    fn fails_on_bad_zip() {
+        let err = parse_raivo_zip(b"not a zip", None).expect_err("should fail");
+        assert!(matches!(err, RaivoImportError::BadZip));
+    }
+}
@@ -0,0 +1,306 @@
+use super::SteamImportError;
+use crate::parser::{ImportError, ImportResult};
+use crate::steam::SteamTotp;
+use crate::{AuthenticatorEntry, AuthenticatorEntryContent};
+use aes::Aes256;
+use base64::{Engine as _, engine::general_purpose};
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use hmac::Hmac;
+use pbkdf2::pbkdf2;
+use sha1::Sha1;
+use zeroize::Zeroizing;
+
+// Steam Desktop Authenticator encrypts each maFile with AES-256-CBC, with a key derived from the password with
+// PBKDF2-HMAC-SHA1. The salt and IV of every file are stored in the manifest.json of the maFiles folder.
+const ITERATIONS: u32 = 50_000;
+const KEY_LENGTH: usize = 32;
+
+type Aes256CbcDec = cbc::Decryptor<Aes256>;
+
+#[derive(serde::Deserialize)]
+struct MaFile {
+    shared_secret: String,
+    #[serde(default)]
+    account_name: Option<String>,
+    #[serde(default)]
+    revocation_code: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct SdaManifest {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    entries: Vec<SdaManifestEntry>,
+}
+
+#[derive(serde::Deserialize)]
+struct SdaManifestEntry {
+    filename: String,
+    #[serde(default)]
+    encryption_iv: Option<String>,
+    #[serde(default)]
+    encryption_salt: Option<String>,
+}
+
+/// A file of the maFiles folder of Steam Desktop Authenticator
+#[derive(Clone, Debug)]
+pub struct SteamMaFile {
+    pub file_name: String,
+    pub contents: String,
+}
+
+impl TryFrom<MaFile> for AuthenticatorEntry {
+    type Error = SteamImportError;
+
+    fn try_from(mafile: MaFile) -> Result<Self, Self::Error> {
+        let secret = general_purpose::STANDARD
+            .decode(mafile.shared_secret.trim())
+            .map_err(|e| {
+                warn!("Invalid maFile shared secret: {e:?}");
+                SteamImportError::BadContent
+            })?;
+        if secret.is_empty() {
+            return Err(SteamImportError::BadContent);
+        }
+
+        let mut steam = SteamTotp::new_from_raw(secret);
+        steam.set_name(mafile.account_name.clone());
+        steam.set_account_name(mafile.account_name);
+        steam.set_revocation_code(mafile.revocation_code);
+
+        Ok(AuthenticatorEntry {
+            content: AuthenticatorEntryContent::Steam(steam),
+            note: None,
+            id: Self::generate_id(),
+            tags: Vec::new(),
+            favourite: false,
+        })
+    }
+}
+
+fn parse_mafile_entry(input: &str) -> Result<AuthenticatorEntry, SteamImportError> {
+    let mafile: MaFile = serde_json::from_str(input.trim_start_matches('\u{feff}')).map_err(|e| {
+        warn!("Error parsing maFile: {e:?}");
+        SteamImportError::BadContent
+    })?;
+    AuthenticatorEntry::try_from(mafile)
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, SteamImportError> {
+    let without_whitespace: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    general_purpose::STANDARD.decode(without_whitespace).map_err(|e| {
+        warn!("Invalid base64 in encrypted maFile: {e:?}");
+        SteamImportError::BadContent
+    })
+}
+
+fn decrypt(input: &str, password: &str, iv: &str, salt: &str) -> Result<Zeroizing<String>, SteamImportError> {
+    let ciphertext = decode_base64(input)?;
+    let iv = decode_base64(iv)?;
+    let salt = decode_base64(salt)?;
+
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    pbkdf2::<Hmac<Sha1>>(password.as_bytes(), &salt, ITERATIONS, key.as_mut_slice()).map_err(|e| {
+        warn!("Error deriving maFile key: {e:?}");
+        SteamImportError::UnableToDecrypt
+    })?;
+
+    let decryptor = Aes256CbcDec::new_from_slices(key.as_slice(), &iv).map_err(|e| {
+        warn!("Error creating maFile cipher: {e:?}");
+        SteamImportError::BadContent
+    })?;
+    // CBC has no authentication, so a wrong password shows up as invalid padding or garbage contents
+    let decrypted = decryptor
+        .decrypt_padded_vec_mut::<Pkcs7>(&ciphertext)
+        .map_err(|_| SteamImportError::BadPassword)?;
+    let decrypted = String::from_utf8(decrypted).map_err(|e| {
+        let mut bytes = e.into_bytes();
+        zeroize::Zeroize::zeroize(&mut bytes);
+        SteamImportError::BadPassword
+    })?;
+
+    let decrypted = Zeroizing::new(decrypted);
+    if !decrypted.trim_start().starts_with('{') {
+        return Err(SteamImportError::BadPassword);
+    }
+    Ok(decrypted)
+}
+
+/// Parses a single unencrypted `.maFile`
+pub fn parse_steam_mafile(input: &str) -> Result<ImportResult, SteamImportError> {
+    Ok(ImportResult {
+        entries: vec![parse_mafile_entry(input)?],
+        errors: Vec::new(),
+    })
+}
+
+/// Parses a single `.maFile` encrypted by Steam Desktop Authenticator. The IV and salt are the `encryption_iv` and
+/// `encryption_salt` of the file in the `manifest.json` of the maFiles folder.
+pub fn parse_steam_mafile_encrypted(
+    input: &str,
+    password: &str,
+    iv: &str,
+    salt: &str,
+) -> Result<ImportResult, SteamImportError> {
+    let decrypted = decrypt(input, password, iv, salt)?;
+    parse_steam_mafile(&decrypted)
+}
+
+/// Parses the maFiles folder of Steam Desktop Authenticator, given its `manifest.json` and the `.maFile`s it lists.
+pub fn parse_steam_mafiles(
+    manifest: &str,
+    files: &[SteamMaFile],
+    password: Option<String>,
+) -> Result<ImportResult, SteamImportError> {
+    let manifest: SdaManifest = serde_json::from_str(manifest.trim_start_matches('\u{feff}')).map_err(|e| {
+        warn!("Error parsing maFiles manifest: {e:?}");
+        SteamImportError::BadContent
+    })?;
+    if manifest.encrypted && password.is_none() {
+        return Err(SteamImportError::MissingPassword);
+    }
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for entry in manifest.entries {
+        let context = format!("Error in file {}", entry.filename);
+        let Some(file) = files.iter().find(|file| file.file_name == entry.filename) else {
+            errors.push(ImportError {
+                context,
+                message: "File not found".to_string(),
+            });
+            continue;
+        };
+
+        let parsed = match (&password, manifest.encrypted) {
+            (Some(password), true) => match (&entry.encryption_iv, &entry.encryption_salt) {
+                (Some(iv), Some(salt)) => {
+                    decrypt(&file.contents, password, iv, salt).and_then(|decrypted| parse_mafile_entry(&decrypted))
+                }
+                _ => Err(SteamImportError::BadContent),
+            },
+            _ => parse_mafile_entry(&file.contents),
+        };
+
+        match parsed {
+            Ok(entry) => entries.push(entry),
+            // All the files share the password, so there's no point in trying the rest
+            Err(SteamImportError::BadPassword) => return Err(SteamImportError::BadPassword),
+            Err(e) => errors.push(ImportError {
+                context,
+                message: format!("Error importing maFile: {e:?}"),
+            }),
+        }
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_file_contents;
+
+    const PLAIN_FILE: &str = "steam/76561198000000001.maFile";
+    const ENCRYPTED_FILE: &str = "steam/encrypted/76561198000000002.maFile";
+    const MANIFEST_FILE: &str = "steam/encrypted/manifest.json";
+    const PASSWORD: &str = "Passw0rd";
+
+    fn steam_of(entry: &AuthenticatorEntry) -> &SteamTotp {
+        match &entry.content {
+            AuthenticatorEntryContent::Steam(steam) => steam,
+            _ => panic!("Should be a Steam entry"),
+        }
+    }
+
+    fn encrypted_files() -> Vec<SteamMaFile> {
+        vec![SteamMaFile {
+            file_name: "76561198000000002.maFile".to_string(),
+            contents: get_file_contents(ENCRYPTED_FILE),
+        }]
+    }
+
+    #[test]
+    fn can_import_mafile() {
+        let res = parse_steam_mafile(&get_file_contents(PLAIN_FILE)).expect("should be able to parse");
+        assert_eq!(1, res.entries.len());
+        assert!(res.errors.is_empty());
+
+        let entry = &res.entries[0];
+        assert_eq!("protontest", entry.name());
+        assert_eq!("Steam", entry.issuer());
+        let steam = steam_of(entry);
+        assert_eq!("cHJvdG9uLXN0ZWFtLXNlY3JldDE", steam.secret());
+        assert_eq!(Some("protontest".to_string()), steam.account_name());
+        assert_eq!(Some("R12345".to_string()), steam.revocation_code());
+
+        // The enrollment data survives storing the entry
+        let stored = AuthenticatorEntry::deserialize(&entry.clone().serialize().unwrap()).unwrap();
+        assert_eq!(entry.content, stored.content);
+    }
+
+    #[test]
+    fn can_import_encrypted_mafile() {
+        let manifest: serde_json::Value = serde_json::from_str(&get_file_contents(MANIFEST_FILE)).unwrap();
+        let iv = manifest["entries"][0]["encryption_iv"].as_str().unwrap();
+        let salt = manifest["entries"][0]["encryption_salt"].as_str().unwrap();
+
+        let res = parse_steam_mafile_encrypted(&get_file_contents(ENCRYPTED_FILE), PASSWORD, iv, salt)
+            .expect("should be able to parse");
+        assert_eq!(1, res.entries.len());
+        let steam = steam_of(&res.entries[0]);
+        assert_eq!("cHJvdG9uLXN0ZWFtLXNlY3JldDI", steam.secret());
+        assert_eq!(Some("protonsecond".to_string()), steam.account_name());
+        assert_eq!(Some("R67890".to_string()), steam.revocation_code());
+    }
+
+    #[test]
+    fn can_import_mafiles_folder() {
+        let res = parse_steam_mafiles(
+            &get_file_contents(MANIFEST_FILE),
+            &encrypted_files(),
+            Some(PASSWORD.to_string()),
+        )
+        .expect("should be able to parse");
+        assert_eq!(1, res.entries.len());
+        assert_eq!("protonsecond", res.entries[0].name());
+        assert!(res.errors.is_empty());
+
+        // Unencrypted folder with a file missing
+        let manifest = r#"{"encrypted": false, "entries": [
+            {"filename": "76561198000000001.maFile", "steamid": 76561198000000001},
+            {"filename": "missing.maFile", "steamid": 76561198000000003}
+        ]}"#;
+        let files = vec![SteamMaFile {
+            file_name: "76561198000000001.maFile".to_string(),
+            contents: get_file_contents(PLAIN_FILE),
+        }];
+        let res = parse_steam_mafiles(manifest, &files, None).expect("should be able to parse");
+        assert_eq!(1, res.entries.len());
+        assert_eq!(1, res.errors.len());
+        assert_eq!("Error in file missing.maFile", res.errors[0].context);
+    }
+
+    #[test]
+    fn fails_with_wrong_or_missing_password() {
+        let manifest = get_file_contents(MANIFEST_FILE);
+
+        let err = parse_steam_mafiles(&manifest, &encrypted_files(), None).expect_err("should fail");
+        assert!(matches!(err, SteamImportError::MissingPassword));
+
+        let err =
+            parse_steam_mafiles(&manifest, &encrypted_files(), Some("wrong".to_string())).expect_err("should fail");
+        assert!(matches!(err, SteamImportError::BadPassword));
+    }
+
+    #[test]
+    fn fails_on_bad_content() {
+        let err = parse_steam_mafile(r#"{"account_name": "test"}"#).expect_err("should fail");
+        assert!(matches!(err, SteamImportError::BadContent));
+
+        let err = parse_steam_mafile(r#"{"shared_secret": "not base64!"}"#).expect_err("should fail");
+        assert!(matches!(err, SteamImportError::BadContent));
+    }
+}
@@ -0,0 +1,24 @@
+use crate::parser::ThirdPartyImportError;
+
+mod mafile;
+
+#[derive(Clone, Debug)]
+pub enum SteamImportError {
+    BadContent,
+    BadPassword,
+    MissingPassword,
+    UnableToDecrypt,
+}
+
+impl From<SteamImportError> for ThirdPartyImportError {
+    fn from(e: SteamImportError) -> Self {
+        match e {
+            SteamImportError::BadContent => Self::BadContent,
+            SteamImportError::BadPassword => Self::BadPassword,
+            SteamImportError::MissingPassword => Self::MissingPassword,
+            SteamImportError::UnableToDecrypt => Self::DecryptionFailed,
+        }
+    }
+}
+
+pub use mafile::{SteamMaFile, parse_steam_mafile, parse_steam_mafile_encrypted, parse_steam_mafiles};
@@ -13,6 +13,8 @@ use pbkdf2::pbkdf2;
 use proton_pass_totp::algorithm::Algorithm;
 use proton_pass_totp::totp::TOTP;
 use sha2::Sha256;
+use std::collections::HashMap;
+use zeroize::Zeroizing;
 
 const ITERATION_COUNT: u32 = 10_000;
 const KEY_SIZE: usize = 32; // 256 bits
@@ -52,6 +54,31 @@ struct TwoFasEntry {
     pub name: String,
     pub secret: String,
     pub otp: Otp,
+    #[serde(default, rename = "groupId")]
+    pub group_id: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+struct TwoFasGroup {
+    id: String,
+    name: String,
+}
+
+// Groups are never encrypted, even in encrypted backups
+fn parse_2fas_groups(json_data: &str) -> HashMap<String, String> {
+    #[derive(serde::Deserialize)]
+    struct Groups {
+        #[serde(default)]
+        groups: Vec<TwoFasGroup>,
+    }
+
+    match serde_json::from_str::<Groups>(json_data) {
+        Ok(parsed) => parsed.groups.into_iter().map(|g| (g.id, g.name)).collect(),
+        Err(e) => {
+            warn!("Error parsing 2FAS groups: {e:?}");
+            HashMap::new()
+        }
+    }
 }
 
 fn parse_2fas_export(json_data: &str) -> Result<TwoFasState, TwoFasImportError> {
@@ -119,13 +146,13 @@ fn decrypt_2fas_encrypted_state(
     };
 
     // 1. Derive the 256-bit AES key
-    let mut derived_key = [0u8; KEY_SIZE];
-    pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, ITERATION_COUNT, &mut derived_key)
+    let mut derived_key = Zeroizing::new([0u8; KEY_SIZE]);
+    pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, ITERATION_COUNT, derived_key.as_mut_slice())
         .map_err(|_| TwoFasImportError::UnableToDecrypt)?;
 
     // 2. Decrypt with AES-GCM. Nonce must be 12 bytes.
     //    If iv.len() != 12, you'll get an error. That means your data is not GCM or your IV is truncated.
-    let cipher = Aes256Gcm::new_from_slice(&derived_key).map_err(|e| {
+    let cipher = Aes256Gcm::new_from_slice(derived_key.as_slice()).map_err(|e| {
         warn!("Error creating Aes256Gcm: {}", e);
         TwoFasImportError::UnableToDecrypt
     })?;
@@ -143,11 +170,12 @@ fn decrypt_2fas_encrypted_state(
     })?;
 
     // 3. Parse the decrypted JSON array
-    let decrypted_str = String::from_utf8(decrypted).map_err(|e| {
+    let decrypted = Zeroizing::new(decrypted);
+    let decrypted_str = std::str::from_utf8(&decrypted).map_err(|e| {
         warn!("Error parsing decrypted 2FAS backup data: {}", e);
         TwoFasImportError::BadContent
     })?;
-    let decrypted_arr: Vec<TwoFasEntry> = serde_json::from_str(&decrypted_str).map_err(|e| {
+    let decrypted_arr: Vec<TwoFasEntry> = serde_json::from_str(decrypted_str).map_err(|e| {
         warn!("Error parsing decrypted 2FAS encrypted backup data: {}", e);
         TwoFasImportError::BadContent
     })?;
@@ -247,18 +275,27 @@ fn get_content_from_entry(obj: TwoFasEntry) -> Result<AuthenticatorEntryContent,
     }
 }
 
-fn parse_entry(obj: TwoFasEntry) -> Result<AuthenticatorEntry, TwoFasImportError> {
+fn parse_entry(obj: TwoFasEntry, groups: &HashMap<String, String>) -> Result<AuthenticatorEntry, TwoFasImportError> {
+    let tags = obj
+        .group_id
+        .as_ref()
+        .and_then(|id| groups.get(id))
+        .map(|name| vec![name.to_string()])
+        .unwrap_or_default();
     let content = get_content_from_entry(obj)?;
 
     Ok(AuthenticatorEntry {
         content,
         note: None,
         id: AuthenticatorEntry::generate_id(),
+        tags,
+        favourite: false,
     })
 }
 
 pub fn parse_2fas_file(json_data: &str, password: Option<String>) -> Result<ImportResult, TwoFasImportError> {
     let state = parse_2fas_export(json_data)?;
+    let groups = parse_2fas_groups(json_data);
 
     let parsed = match state {
         TwoFasState::Decrypted(entries) => entries,
@@ -268,7 +305,7 @@ pub fn parse_2fas_file(json_data: &str, password: Option<String>) -> Result<Impo
     let mut entries = Vec::new();
     let mut errors = Vec::new();
     for (idx, entry) in parsed.into_iter().enumerate() {
-        match parse_entry(entry.clone()) {
+        match parse_entry(entry.clone(), &groups) {
             Ok(e) => entries.push(e),
             Err(e) => {
                 errors.push(ImportError {
@@ -437,4 +474,14 @@ mod test {
         assert!(error_message.contains("InvalidConfig"));
         assert!(error_message.contains("period"));
     }
+
+    #[test]
+    fn imports_groups_as_tags() {
+        let content = r#"{"schemaVersion": 4, "groups": [{"id": "g1", "name": "Work", "isExpanded": true}], "services": [{"name": "Grouped", "secret": "JBSWY3DPEHPK3PXP", "groupId": "g1", "otp": {"tokenType": "TOTP", "source": "Manual"}}, {"name": "Unknown group", "secret": "JBSWY3DPEHPK3PXP", "groupId": "g2", "otp": {"tokenType": "TOTP", "source": "Manual"}}, {"name": "No group", "secret": "JBSWY3DPEHPK3PXP", "otp": {"tokenType": "TOTP", "source": "Manual"}}]}"#;
+        let res = parse_2fas_file(content, None).expect("should parse");
+        assert!(res.errors.is_empty());
+        assert_eq!(vec!["Work".to_string()], res.entries[0].tags);
+        assert!(res.entries[1].tags.is_empty());
+        assert!(res.entries[2].tags.is_empty());
+    }
 }
@@ -1,28 +1,36 @@
+use crate::parser::{ImportError, ImportResult};
+use crate::{AuthenticatorEntry, parse_google_authenticator_totp};
+use rxing::multi::{GenericMultipleBarcodeReader, MultipleBarcodeReader};
 use rxing::{
     BarcodeFormat, BinaryBitmap, DecodeHintValue, DecodeHints, Luma8LuminanceSource, MultiFormatReader, Reader,
     common::HybridBinarizer,
 };
 use std::collections::HashSet;
 
-pub fn parse_qr_code(input: &[u8]) -> Option<String> {
-    let img = match image::load_from_memory(input) {
-        Ok(img) => img.to_rgba8(),
-        Err(e) => {
-            warn!("Error loading image from memory: {e:?}");
-            return None;
-        }
-    };
+const OTPAUTH_PREFIX: &str = "otpauth://";
+const OTPAUTH_MIGRATION_PREFIX: &str = "otpauth-migration://";
 
-    let image_bytes = img.to_vec();
-    let luma = convert_image_to_luma(&image_bytes);
+#[derive(Debug, PartialEq, Eq)]
+pub enum QrScanError {
+    InvalidImage,
+    NoCodeFound,
+}
+
+struct LumaImage {
+    luma: Vec<u8>,
+    width: u32,
+    height: u32,
+}
 
-    let width = img.width();
-    let height = img.height();
+pub fn parse_qr_code(input: &[u8]) -> Option<String> {
+    let img = load_luma_image(input).ok()?;
 
     let mut multi_format_reader = MultiFormatReader::default();
     let hints = get_hints();
     match multi_format_reader.decode_with_hints(
-        &mut BinaryBitmap::new(HybridBinarizer::new(Luma8LuminanceSource::new(luma, width, height))),
+        &mut BinaryBitmap::new(HybridBinarizer::new(Luma8LuminanceSource::new(
+            img.luma, img.width, img.height,
+        ))),
         &hints,
     ) {
         Ok(decoded) => Some(decoded.getText().to_string()),
@@ -33,6 +41,126 @@ pub fn parse_qr_code(input: &[u8]) -> Option<String> {
     }
 }
 
+/// Decodes all the QR codes present in the image, in the order they were detected.
+pub fn parse_qr_codes(input: &[u8]) -> Result<Vec<String>, QrScanError> {
+    let img = load_luma_image(input)?;
+
+    let mut reader = GenericMultipleBarcodeReader::new(MultiFormatReader::default());
+    let hints = get_hints();
+    let decoded = match reader.decode_multiple_with_hints(
+        &mut BinaryBitmap::new(HybridBinarizer::new(Luma8LuminanceSource::new(
+            img.luma.clone(),
+            img.width,
+            img.height,
+        ))),
+        &hints,
+    ) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            warn!("Error decoding multiple QR codes: {:?}", e);
+            vec![]
+        }
+    };
+
+    let mut codes: Vec<String> = vec![];
+    for result in decoded {
+        let text = result.getText().to_string();
+        if !codes.contains(&text) {
+            codes.push(text);
+        }
+    }
+
+    // The multiple reader splits the image to look for codes, which can miss big codes that the
+    // single reader is able to find
+    if codes.is_empty() {
+        let mut single_reader = MultiFormatReader::default();
+        if let Ok(decoded) = single_reader.decode_with_hints(
+            &mut BinaryBitmap::new(HybridBinarizer::new(Luma8LuminanceSource::new(
+                img.luma, img.width, img.height,
+            ))),
+            &hints,
+        ) {
+            codes.push(decoded.getText().to_string());
+        }
+    }
+
+    if codes.is_empty() {
+        Err(QrScanError::NoCodeFound)
+    } else {
+        Ok(codes)
+    }
+}
+
+/// Scans all the QR codes from a list of images and imports the `otpauth://` and Google Authenticator
+/// `otpauth-migration://` codes they contain. Errors are reported per image.
+pub fn import_from_qr_images(images: &[Vec<u8>]) -> ImportResult {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, image) in images.iter().enumerate() {
+        let codes = match parse_qr_codes(image) {
+            Ok(codes) => codes,
+            Err(e) => {
+                errors.push(ImportError {
+                    context: format!("Error in image {idx}"),
+                    message: format!("Could not read QR codes: {e:?}"),
+                });
+                continue;
+            }
+        };
+
+        for code in codes {
+            if code.starts_with(OTPAUTH_MIGRATION_PREFIX) {
+                match parse_google_authenticator_totp(&code) {
+                    Ok(res) => {
+                        entries.extend(res.entries);
+                        errors.extend(res.errors.into_iter().map(|e| ImportError {
+                            context: format!("Error in image {idx}: {}", e.context),
+                            message: e.message,
+                        }));
+                    }
+                    Err(e) => errors.push(ImportError {
+                        context: format!("Error in image {idx}"),
+                        message: format!("Error parsing Google Authenticator QR code: {e:?}"),
+                    }),
+                }
+            } else if code.starts_with(OTPAUTH_PREFIX) {
+                match AuthenticatorEntry::from_uri(&code, None) {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => errors.push(ImportError {
+                        context: format!("Error in image {idx}"),
+                        message: format!("Error parsing otpauth QR code: {e:?}"),
+                    }),
+                }
+            } else {
+                errors.push(ImportError {
+                    context: format!("Error in image {idx}"),
+                    message: "Unsupported QR code content".to_string(),
+                });
+            }
+        }
+    }
+
+    ImportResult { entries, errors }
+}
+
+fn load_luma_image(input: &[u8]) -> Result<LumaImage, QrScanError> {
+    let img = match image::load_from_memory(input) {
+        Ok(img) => img.to_rgba8(),
+        Err(e) => {
+            warn!("Error loading image from memory: {e:?}");
+            return Err(QrScanError::InvalidImage);
+        }
+    };
+
+    let image_bytes = img.to_vec();
+    Ok(LumaImage {
+        luma: convert_image_to_luma(&image_bytes),
+        width: img.width(),
+        height: img.height(),
+    })
+}
+
 fn convert_image_to_luma(data: &[u8]) -> Vec<u8> {
     let mut luma_data = Vec::with_capacity(data.len() / 4);
     for src_pixel in data.chunks_exact(4) {
@@ -119,6 +247,53 @@ mod tests {
         parse_google_authenticator_qr("GoogleAuthenticator_ScreenshotBigQR.jpeg")
     }
 
+    #[test]
+    fn parse_multiple_codes_in_image() {
+        let input = read_file("MultipleCodes.png");
+        let mut result = parse_qr_codes(&input).expect("Should parse QR codes");
+        result.sort();
+
+        assert_eq!(2, result.len());
+        assert!(result[0].starts_with("otpauth://totp/Amazon"));
+        assert!(result[1].starts_with("otpauth://totp/Proton"));
+    }
+
+    #[test]
+    fn parse_multiple_codes_falls_back_to_big_code() {
+        let input = read_file("GoogleAuthenticator_ScreenshotBigQR.jpeg");
+        let result = parse_qr_codes(&input).expect("Should parse QR codes");
+        assert_eq!(1, result.len());
+        assert!(result[0].starts_with("otpauth-migration://"));
+    }
+
+    #[test]
+    fn import_from_multiple_images() {
+        let images = vec![
+            read_file("GoogleAuthenticatorExport_1.png"),
+            read_file("MultipleCodes.png"),
+            read_file("example.png"),
+            b"not an image".to_vec(),
+        ];
+        let result = import_from_qr_images(&images);
+
+        let google_entries = parse_google_authenticator_totp(
+            &parse_qr_code(&images[0]).expect("Should parse Google Authenticator QR code"),
+        )
+        .expect("Should import Google Authenticator QR code")
+        .entries;
+        assert_eq!(google_entries.len() + 2, result.entries.len());
+
+        let issuers: Vec<String> = result.entries.iter().map(|e| e.issuer()).collect();
+        assert!(issuers.contains(&"Proton".to_string()));
+        assert!(issuers.contains(&"Amazon".to_string()));
+
+        assert_eq!(2, result.errors.len());
+        assert_eq!("Error in image 2", result.errors[0].context);
+        assert!(result.errors[0].message.contains("Unsupported"));
+        assert_eq!("Error in image 3", result.errors[1].context);
+        assert!(result.errors[1].message.contains("InvalidImage"));
+    }
+
     #[test]
     fn parse_invalid_image() {
         let invalid_data = b"not an image";
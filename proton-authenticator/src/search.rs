@@ -0,0 +1,447 @@
+use crate::AuthenticatorEntry;
+use crate::issuer_mapper::TOTPIssuerMapper;
+use std::collections::HashMap;
+
+// How good a single query token matches a field token
+const EXACT_MATCH: u32 = 10;
+const PREFIX_MATCH: u32 = 7;
+const SUBSTRING_MATCH: u32 = 4;
+const FUZZY_MATCH: u32 = 3;
+// Extra score when the whole query appears in a field
+const PHRASE_MATCH: u32 = 2;
+
+// Shorter query tokens need to be at least this long to match in the middle of a word
+const MIN_SUBSTRING_LEN: usize = 3;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SearchField {
+    Issuer,
+    Name,
+    Tag,
+    Domain,
+    Note,
+}
+
+impl SearchField {
+    fn weight(self) -> u32 {
+        match self {
+            SearchField::Issuer => 5,
+            SearchField::Name => 4,
+            SearchField::Tag => 3,
+            SearchField::Domain => 2,
+            SearchField::Note => 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchResult {
+    pub entry_id: String,
+    pub score: u32,
+}
+
+struct IndexedField {
+    field: SearchField,
+    text: String,
+    tokens: Vec<String>,
+}
+
+struct IndexedEntry {
+    position: u64,
+    fields: Vec<IndexedField>,
+}
+
+/// Search index over authenticator entries.
+///
+/// Matching ignores case and diacritics, and tolerates typos. Every word of the query needs to match
+/// the entry for it to be returned. Results are ranked by score, and entries with the same score keep
+/// the order in which they were added to the index.
+pub struct EntrySearchIndex {
+    mapper: TOTPIssuerMapper,
+    entries: HashMap<String, IndexedEntry>,
+    next_position: u64,
+}
+
+impl Default for EntrySearchIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntrySearchIndex {
+    pub fn new() -> Self {
+        Self {
+            mapper: TOTPIssuerMapper::new(),
+            entries: HashMap::new(),
+            next_position: 0,
+        }
+    }
+
+    /// Replaces the contents of the index. The order of `entries` is used to rank entries with the same score
+    pub fn set_entries(&mut self, entries: &[AuthenticatorEntry]) {
+        self.entries.clear();
+        self.next_position = 0;
+        for entry in entries {
+            self.upsert(entry);
+        }
+    }
+
+    /// Adds the entry to the index, or updates it if it was already indexed.
+    /// Updated entries keep their position.
+    pub fn upsert(&mut self, entry: &AuthenticatorEntry) {
+        let position = match self.entries.get(&entry.id) {
+            Some(existing) => existing.position,
+            None => {
+                self.next_position += 1;
+                self.next_position
+            }
+        };
+        let fields = self.index_fields(entry);
+        self.entries.insert(entry.id.clone(), IndexedEntry { position, fields });
+    }
+
+    /// Returns true if the entry was indexed
+    pub fn remove(&mut self, entry_id: &str) -> bool {
+        self.entries.remove(entry_id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries matching the query, best matches first. An empty query returns every entry.
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let folded_query = fold(query);
+        let query_tokens = tokenize(&folded_query);
+
+        let mut matches: Vec<(u64, SearchResult)> = self
+            .entries
+            .iter()
+            .filter_map(|(id, entry)| {
+                score_entry(entry, &folded_query, &query_tokens).map(|score| {
+                    (
+                        entry.position,
+                        SearchResult {
+                            entry_id: id.to_string(),
+                            score,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        matches.sort_by(|(a_pos, a), (b_pos, b)| b.score.cmp(&a.score).then(a_pos.cmp(b_pos)));
+        matches.into_iter().map(|(_, result)| result).collect()
+    }
+
+    fn index_fields(&self, entry: &AuthenticatorEntry) -> Vec<IndexedField> {
+        let issuer = entry.issuer();
+        let mut fields = vec![
+            IndexedField::new(SearchField::Issuer, &issuer),
+            IndexedField::new(SearchField::Name, &entry.name()),
+        ];
+        fields.extend(entry.tags.iter().map(|tag| IndexedField::new(SearchField::Tag, tag)));
+        if let Some(info) = self.mapper.lookup(&issuer) {
+            // The top level domain would match too many entries
+            let without_tld = info
+                .domain
+                .rsplit_once('.')
+                .map(|(name, _)| name)
+                .unwrap_or(&info.domain);
+            fields.push(IndexedField::new(SearchField::Domain, without_tld));
+        }
+        if let Some(note) = &entry.note {
+            fields.push(IndexedField::new(SearchField::Note, note));
+        }
+        fields.retain(|f| !f.tokens.is_empty());
+        fields
+    }
+}
+
+impl IndexedField {
+    fn new(field: SearchField, value: &str) -> Self {
+        let text = fold(value);
+        let tokens = tokenize(&text);
+        Self { field, text, tokens }
+    }
+}
+
+pub(crate) fn fold(value: &str) -> String {
+    deunicode::deunicode(value).to_lowercase()
+}
+
+fn tokenize(folded: &str) -> Vec<String> {
+    folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect()
+}
+
+fn score_entry(entry: &IndexedEntry, folded_query: &str, query_tokens: &[String]) -> Option<u32> {
+    let mut score = 0;
+    for query_token in query_tokens {
+        let best = entry
+            .fields
+            .iter()
+            .filter_map(|field| {
+                field
+                    .tokens
+                    .iter()
+                    .filter_map(|token| match_token(query_token, token))
+                    .max()
+                    .map(|quality| quality * field.field.weight())
+            })
+            .max()?;
+        score += best;
+    }
+
+    if query_tokens.len() > 1 {
+        let query = query_tokens.join(" ");
+        score += entry
+            .fields
+            .iter()
+            .filter(|field| field.tokens.join(" ").contains(&query) || field.text.contains(folded_query.trim()))
+            .map(|field| PHRASE_MATCH * field.field.weight())
+            .max()
+            .unwrap_or_default();
+    }
+
+    Some(score)
+}
+
+fn match_token(query: &str, token: &str) -> Option<u32> {
+    if token == query {
+        return Some(EXACT_MATCH);
+    }
+    if token.starts_with(query) {
+        return Some(PREFIX_MATCH);
+    }
+    if query.len() >= MIN_SUBSTRING_LEN && token.contains(query) {
+        return Some(SUBSTRING_MATCH);
+    }
+
+    let max_distance = allowed_typos(query);
+    if max_distance == 0 {
+        return None;
+    }
+    // Compare against the whole token and its prefixes, as the user may not have finished typing
+    let query: Vec<char> = query.chars().collect();
+    let token: Vec<char> = token.chars().collect();
+    let min_len = query.len().saturating_sub(max_distance).max(1);
+    let max_len = (query.len() + max_distance).min(token.len());
+    let distance = (min_len..=max_len)
+        .map(|len| edit_distance(&query, &token[..len]))
+        .min()
+        .filter(|distance| *distance <= max_distance)?;
+
+    Some(FUZZY_MATCH.saturating_sub(distance as u32 - 1))
+}
+
+fn allowed_typos(query: &str) -> usize {
+    match query.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+// Optimal string alignment distance: insertions, deletions, substitutions and adjacent transpositions
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, value) in rows[0].iter_mut().enumerate() {
+        *value = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut value = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = value;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(issuer: &str, label: &str, note: Option<&str>, tags: &[&str]) -> AuthenticatorEntry {
+        let mut entry = AuthenticatorEntry::from_uri(
+            &format!(
+                "otpauth://totp/{}?secret=MYSECRET&issuer={}",
+                urlencode(label),
+                urlencode(issuer)
+            ),
+            note.map(|n| n.to_string()),
+        )
+        .expect("should be able to create entry");
+        entry.tags = tags.iter().map(|t| t.to_string()).collect();
+        entry
+    }
+
+    fn urlencode(value: &str) -> String {
+        url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.entry_id.as_str()).collect()
+    }
+
+    fn index(entries: &[AuthenticatorEntry]) -> EntrySearchIndex {
+        let mut index = EntrySearchIndex::new();
+        index.set_entries(entries);
+        index
+    }
+
+    #[test]
+    fn empty_query_returns_all_entries_in_order() {
+        let entries = vec![
+            entry("GitHub", "user", None, &[]),
+            entry("Amazon", "user", None, &[]),
+            entry("Proton", "user", None, &[]),
+        ];
+        let index = index(&entries);
+
+        let res = index.search("  ");
+        assert_eq!(vec![&entries[0].id, &entries[1].id, &entries[2].id], ids(&res));
+    }
+
+    #[test]
+    fn ranks_issuer_matches_above_note_matches() {
+        let entries = vec![
+            entry("Bank", "user", Some("Backup codes stored in github"), &[]),
+            entry("GitHub", "user", None, &[]),
+        ];
+        let index = index(&entries);
+
+        let res = index.search("github");
+        assert_eq!(vec![&entries[1].id, &entries[0].id], ids(&res));
+    }
+
+    #[test]
+    fn matches_prefixes_and_substrings() {
+        let entries = vec![entry("GitHub", "user", None, &[]), entry("Gitlab", "user", None, &[])];
+        let index = index(&entries);
+
+        assert_eq!(2, index.search("git").len());
+        assert_eq!(vec![&entries[0].id], ids(&index.search("hub")));
+        // Short queries only match the start of words
+        assert!(index.search("ub").is_empty());
+    }
+
+    #[test]
+    fn ignores_case_and_diacritics() {
+        let entries = vec![entry("Zürich Bänk", "Ólafur", None, &[])];
+        let index = index(&entries);
+
+        assert_eq!(1, index.search("zurich").len());
+        assert_eq!(1, index.search("ZÜRICH bank").len());
+        assert_eq!(1, index.search("olafur").len());
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        let entries = vec![
+            entry("GitHub", "user", None, &[]),
+            entry("Amazon", "user", None, &[]),
+            entry("Cloudflare", "user", None, &[]),
+        ];
+        let index = index(&entries);
+
+        assert_eq!(vec![&entries[0].id], ids(&index.search("gihtub")));
+        assert_eq!(vec![&entries[1].id], ids(&index.search("amazom")));
+        assert_eq!(vec![&entries[2].id], ids(&index.search("clouflare")));
+        // Still typing, with a typo
+        assert_eq!(vec![&entries[2].id], ids(&index.search("clouf")));
+        // Too short to allow typos
+        assert!(index.search("amz").is_empty());
+    }
+
+    #[test]
+    fn exact_matches_rank_above_fuzzy_matches() {
+        let entries = vec![entry("Amazom", "user", None, &[]), entry("Amazon", "user", None, &[])];
+        let index = index(&entries);
+
+        let res = index.search("amazon");
+        assert_eq!(vec![&entries[1].id, &entries[0].id], ids(&res));
+        assert!(res[0].score > res[1].score);
+    }
+
+    #[test]
+    fn every_query_word_must_match() {
+        let entries = vec![
+            entry("Google", "work@company.com", None, &[]),
+            entry("Google", "personal@gmail.com", None, &[]),
+        ];
+        let index = index(&entries);
+
+        assert_eq!(vec![&entries[0].id], ids(&index.search("google work")));
+        assert!(index.search("google nothing").is_empty());
+    }
+
+    #[test]
+    fn searches_tags_and_domains() {
+        let entries = vec![
+            entry("Amazon Web Services", "admin", None, &["Infrastructure"]),
+            entry("Other", "admin", None, &[]),
+        ];
+        let index = index(&entries);
+
+        assert_eq!(vec![&entries[0].id], ids(&index.search("infra")));
+        // aws.amazon.com
+        assert_eq!(vec![&entries[0].id], ids(&index.search("aws")));
+        // The top level domain is not indexed
+        assert!(index.search("com").is_empty());
+    }
+
+    #[test]
+    fn supports_incremental_updates() {
+        let mut entries = vec![entry("GitHub", "user", None, &[]), entry("Amazon", "user", None, &[])];
+        let mut index = index(&entries);
+        assert_eq!(2, index.len());
+
+        let added = entry("GitLab", "user", None, &[]);
+        index.upsert(&added);
+        assert_eq!(vec![&entries[0].id, &added.id], ids(&index.search("git")));
+
+        entries[0] = entry("Renamed", "user", None, &[]);
+        entries[0].id = index.search("github")[0].entry_id.clone();
+        index.upsert(&entries[0]);
+        assert!(index.search("github").is_empty());
+        assert_eq!(vec![&entries[0].id], ids(&index.search("renamed")));
+        // Updated entries keep their position
+        assert_eq!(entries[0].id, index.search("")[0].entry_id);
+
+        assert!(index.remove(&added.id));
+        assert!(!index.remove(&added.id));
+        assert!(index.search("gitlab").is_empty());
+        assert_eq!(2, index.len());
+    }
+
+    #[test]
+    fn can_search_many_entries() {
+        let entries: Vec<AuthenticatorEntry> = (0..1000)
+            .map(|idx| entry(&format!("Issuer {idx}"), &format!("user{idx}@proton.me"), None, &[]))
+            .collect();
+        let index = index(&entries);
+
+        let res = index.search("issuer 999");
+        assert_eq!(&entries[999].id, &res[0].entry_id);
+        assert_eq!(1000, index.search("").len());
+    }
+}
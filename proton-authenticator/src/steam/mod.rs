@@ -1,5 +1,6 @@
 use base64::Engine;
 use url::Url;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 pub static PERIOD: u16 = 30;
 pub static STEAM_ISSUER: &str = "Steam";
@@ -19,9 +20,29 @@ pub enum SteamTotpError {
 pub struct SteamTotp {
     pub(crate) secret: Vec<u8>,
     pub(crate) name: Option<String>,
+    /// Steam account the authenticator was enrolled for
+    pub(crate) account_name: Option<String>,
+    /// Code that removes the authenticator from the account when the device is lost
+    pub(crate) revocation_code: Option<String>,
     was_b32: bool,
 }
 
+impl Zeroize for SteamTotp {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+        self.revocation_code.zeroize();
+    }
+}
+
+// The secret is wiped when the SteamTotp is dropped
+impl Drop for SteamTotp {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SteamTotp {}
+
 impl SteamTotp {
     pub fn new(secret: &str) -> Result<SteamTotp, SteamTotpError> {
         if secret.is_empty() {
@@ -32,12 +53,16 @@ impl SteamTotp {
             Some(secret) => Ok(SteamTotp {
                 secret,
                 name: None,
+                account_name: None,
+                revocation_code: None,
                 was_b32: true,
             }),
             None => match base64::engine::general_purpose::STANDARD_NO_PAD.decode(secret) {
                 Ok(secret) => Ok(SteamTotp {
                     secret,
                     name: None,
+                    account_name: None,
+                    revocation_code: None,
                     was_b32: false,
                 }),
                 Err(_) => Err(SteamTotpError::BadSecret),
@@ -84,6 +109,8 @@ impl SteamTotp {
         SteamTotp {
             secret,
             name: None,
+            account_name: None,
+            revocation_code: None,
             was_b32: false,
         }
     }
@@ -94,6 +121,23 @@ impl SteamTotp {
         self.name = name;
     }
 
+    pub fn set_account_name(&mut self, account_name: Option<String>) {
+        self.account_name = account_name.filter(|name| !name.trim().is_empty());
+    }
+
+    pub fn set_revocation_code(&mut self, revocation_code: Option<String>) {
+        self.revocation_code.zeroize();
+        self.revocation_code = revocation_code.filter(|code| !code.trim().is_empty());
+    }
+
+    pub fn account_name(&self) -> Option<String> {
+        self.account_name.clone()
+    }
+
+    pub fn revocation_code(&self) -> Option<String> {
+        self.revocation_code.clone()
+    }
+
     pub fn generate(&self, time: u64) -> String {
         // 8-byte big-endian representation of the current interval
         let interval = Self::code_interval(time).to_be_bytes();
@@ -215,4 +259,24 @@ mod tests {
 
         assert_ne!(code1, code2);
     }
+
+    #[test]
+    fn zeroize_clears_secrets() {
+        let mut totp = SteamTotp::new_from_raw(generate_code());
+        totp.set_revocation_code(Some("R12345".to_string()));
+        let secret_len = totp.secret.len();
+
+        totp.zeroize();
+
+        // The buffer is kept, so its former contents can be checked without reading freed memory
+        let mut buffer = std::mem::take(&mut totp.secret);
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= secret_len);
+        // SAFETY: zeroize has written every byte of the capacity, so the spare capacity is initialized
+        let wiped = buffer.spare_capacity_mut()[..secret_len]
+            .iter()
+            .all(|byte| unsafe { byte.assume_init() } == 0);
+        assert!(wiped);
+        assert!(totp.revocation_code.is_none());
+    }
 }
@@ -1,8 +1,147 @@
-use qrcode::QrResult;
+use base64::Engine;
 use qrcode::render::svg;
+use qrcode::types::QrError;
+use qrcode::{EcLevel, QrCode, QrResult};
+
+/// Size of each module of the SVG codes
+const SVG_MODULE_SIZE: u32 = 8;
+/// Modules of blank space around the code
+const QUIET_ZONE_MODULES: u32 = 4;
+/// Portion of the code width covered by the logo. The highest error correction level can recover up to 30% of the
+/// code, so the logo and its margin must stay well below that
+const LOGO_RATIO: f32 = 0.2;
+/// Mime types of the logos that can be embedded in SVG codes
+const SVG_LOGO_MIME_TYPES: [&str; 3] = ["image/png", "image/jpeg", "image/svg+xml"];
+
+/// Image placed in the center of a QR code, such as the icon of a service
+pub struct QrLogo<'a> {
+    pub data: &'a [u8],
+    pub mime_type: &'a str,
+}
+
+#[derive(Debug)]
+pub enum QrImageError {
+    QrCode(QrError),
+    /// The logo could not be decoded
+    InvalidLogo,
+    /// The mime type of the logo is not one of the supported image types
+    UnsupportedLogoType(String),
+    /// The requested size is above the maximum
+    SizeTooLarge(u32),
+    Image(String),
+}
+
+impl std::fmt::Display for QrImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QrImageError::QrCode(e) => write!(f, "QR code error: {e}"),
+            QrImageError::InvalidLogo => write!(f, "Invalid logo"),
+            QrImageError::UnsupportedLogoType(mime_type) => write!(f, "Unsupported logo type: {mime_type}"),
+            QrImageError::SizeTooLarge(size) => write!(f, "Size too large: {size}"),
+            QrImageError::Image(msg) => write!(f, "Image error: {msg}"),
+        }
+    }
+}
+
+impl From<QrError> for QrImageError {
+    fn from(err: QrError) -> Self {
+        QrImageError::QrCode(err)
+    }
+}
 
 pub fn generate_svg_qr_code(value: &str) -> QrResult<String> {
     let code = qrcode::QrCode::new(value)?;
     let svg = code.render::<svg::Color>().build();
     Ok(svg)
 }
+
+/// Same as [`generate_svg_qr_code`], with the logo embedded in the center of the code if present.
+/// The logo must be a PNG, JPEG or SVG image.
+pub fn generate_svg_qr_code_with_logo(value: &str, logo: Option<&QrLogo>) -> Result<String, QrImageError> {
+    let Some(logo) = logo else {
+        return Ok(generate_svg_qr_code(value)?);
+    };
+    // The mime type is written as is in the SVG, so it can only be one of the known ones
+    if !SVG_LOGO_MIME_TYPES.contains(&logo.mime_type) {
+        return Err(QrImageError::UnsupportedLogoType(logo.mime_type.to_string()));
+    }
+
+    let code = QrCode::with_error_correction_level(value, EcLevel::H)?;
+    let svg = code
+        .render::<svg::Color>()
+        .module_dimensions(SVG_MODULE_SIZE, SVG_MODULE_SIZE)
+        .build();
+
+    let size = (code.width() as u32 + 2 * QUIET_ZONE_MODULES) * SVG_MODULE_SIZE;
+    let (background, logo_area) = logo_layout(size, code.width() as u32 * SVG_MODULE_SIZE);
+    let encoded = base64::engine::general_purpose::STANDARD.encode(logo.data);
+    let logo_svg = format!(
+        r##"<rect x="{x}" y="{x}" width="{bg}" height="{bg}" fill="#fff"/><image x="{lx}" y="{lx}" width="{ls}" height="{ls}" href="data:{mime};base64,{encoded}"/>"##,
+        x = background.0,
+        bg = background.1,
+        lx = logo_area.0,
+        ls = logo_area.1,
+        mime = logo.mime_type,
+    );
+
+    Ok(svg.replacen("</svg>", &format!("{logo_svg}</svg>"), 1))
+}
+
+/// Returns the (offset, size) of the logo background and of the logo itself, centered on an image of `size`
+fn logo_layout(size: u32, code_size: u32) -> ((u32, u32), (u32, u32)) {
+    let logo_size = ((code_size as f32 * LOGO_RATIO) as u32).max(1);
+    let margin = (logo_size / 10).max(1);
+    let background_size = logo_size + 2 * margin;
+    (
+        ((size - background_size) / 2, background_size),
+        ((size - logo_size) / 2, logo_size),
+    )
+}
+
+#[cfg(feature = "qr-png")]
+mod png {
+    use super::{QUIET_ZONE_MODULES, QrImageError, logo_layout};
+    use image::imageops::{FilterType, overlay};
+    use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+    use qrcode::{EcLevel, QrCode};
+    use std::io::Cursor;
+
+    /// Maximum size in pixels of the PNG codes
+    pub const MAX_PNG_SIZE: u32 = 2048;
+
+    /// Renders the value as a PNG QR code of at least `size` pixels, with the logo in the center if present.
+    /// The logo can be any PNG, JPEG or WebP image. Sizes above [`MAX_PNG_SIZE`] are rejected.
+    pub fn generate_png_qr_code(value: &str, size: u32, logo: Option<&[u8]>) -> Result<Vec<u8>, QrImageError> {
+        if size > MAX_PNG_SIZE {
+            return Err(QrImageError::SizeTooLarge(size));
+        }
+        let ec_level = if logo.is_some() { EcLevel::H } else { EcLevel::M };
+        let code = QrCode::with_error_correction_level(value, ec_level)?;
+        let mut img: RgbaImage = code.render::<Rgba<u8>>().min_dimensions(size, size).build();
+
+        if let Some(logo) = logo {
+            let logo = image::load_from_memory(logo).map_err(|_| QrImageError::InvalidLogo)?;
+            let modules = code.width() as u32 + 2 * QUIET_ZONE_MODULES;
+            let module_size = img.width() / modules;
+            let (background, logo_area) = logo_layout(img.width(), code.width() as u32 * module_size);
+
+            let white = RgbaImage::from_pixel(background.1, background.1, Rgba([255, 255, 255, 255]));
+            overlay(&mut img, &white, background.0 as i64, background.0 as i64);
+
+            let resized = logo.resize(logo_area.1, logo_area.1, FilterType::Lanczos3);
+            // Keep the logo centered when its aspect ratio is not square
+            let x = (img.width() - resized.width()) / 2;
+            let y = (img.height() - resized.height()) / 2;
+            overlay(&mut img, &resized.to_rgba8(), x as i64, y as i64);
+        }
+
+        let mut output = Vec::new();
+        DynamicImage::ImageRgba8(img)
+            .write_to(&mut Cursor::new(&mut output), ImageFormat::Png)
+            .map_err(|e| QrImageError::Image(e.to_string()))?;
+        Ok(output)
+    }
+}
+
+#[cfg(feature = "qr-png")]
+pub use png::{MAX_PNG_SIZE, generate_png_qr_code};
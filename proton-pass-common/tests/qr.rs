@@ -0,0 +1,88 @@
+use proton_pass_common::qr::{QrImageError, QrLogo, generate_svg_qr_code, generate_svg_qr_code_with_logo};
+
+const VALUE: &str = "otpauth://totp/MYLABEL?secret=MYSECRET&issuer=MYISSUER";
+
+#[test]
+fn svg_without_logo_is_the_plain_code() {
+    let plain = generate_svg_qr_code(VALUE).expect("should generate");
+    let res = generate_svg_qr_code_with_logo(VALUE, None).expect("should generate");
+    assert_eq!(plain, res);
+}
+
+#[test]
+fn svg_embeds_logo() {
+    let logo = QrLogo {
+        data: &[1, 2, 3],
+        mime_type: "image/png",
+    };
+    let res = generate_svg_qr_code_with_logo(VALUE, Some(&logo)).expect("should generate");
+    assert!(res.contains(r#"href="data:image/png;base64,AQID""#));
+    assert!(res.ends_with("</svg>"));
+}
+
+#[test]
+fn svg_rejects_unsupported_logo_types() {
+    for mime_type in ["image/webp", "text/html", r#"image/png" onload="alert(1)"#] {
+        let logo = QrLogo {
+            data: &[1, 2, 3],
+            mime_type,
+        };
+        let err = generate_svg_qr_code_with_logo(VALUE, Some(&logo)).expect_err("should fail");
+        assert!(matches!(err, QrImageError::UnsupportedLogoType(m) if m == mime_type));
+    }
+}
+
+#[cfg(feature = "qr-png")]
+mod png {
+    use super::VALUE;
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use proton_pass_common::qr::{MAX_PNG_SIZE, QrImageError, generate_png_qr_code};
+    use std::io::Cursor;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    fn red_logo() -> Vec<u8> {
+        let mut output = Vec::new();
+        RgbaImage::from_pixel(16, 16, RED)
+            .write_to(&mut Cursor::new(&mut output), ImageFormat::Png)
+            .expect("should encode logo");
+        output
+    }
+
+    fn center_pixel(png: &[u8]) -> Rgba<u8> {
+        let img = image::load_from_memory_with_format(png, ImageFormat::Png)
+            .expect("should decode")
+            .to_rgba8();
+        *img.get_pixel(img.width() / 2, img.height() / 2)
+    }
+
+    #[test]
+    fn png_can_be_decoded() {
+        let png = generate_png_qr_code(VALUE, 256, None).expect("should generate");
+        let img = image::load_from_memory_with_format(&png, ImageFormat::Png).expect("should decode");
+        assert!(img.width() >= 256);
+        assert_eq!(img.width(), img.height());
+    }
+
+    #[test]
+    fn png_overlays_logo_in_the_center() {
+        let without_logo = generate_png_qr_code(VALUE, 256, None).expect("should generate");
+        assert_ne!(RED, center_pixel(&without_logo));
+
+        let with_logo = generate_png_qr_code(VALUE, 256, Some(&red_logo())).expect("should generate");
+        assert_eq!(RED, center_pixel(&with_logo));
+    }
+
+    #[test]
+    fn png_rejects_invalid_logo() {
+        let err = generate_png_qr_code(VALUE, 256, Some(&[1, 2, 3])).expect_err("should fail");
+        assert!(matches!(err, QrImageError::InvalidLogo));
+    }
+
+    #[test]
+    fn png_rejects_oversized_size() {
+        generate_png_qr_code(VALUE, MAX_PNG_SIZE, None).expect("max size should be allowed");
+        let err = generate_png_qr_code(VALUE, MAX_PNG_SIZE + 1, None).expect_err("should fail");
+        assert!(matches!(err, QrImageError::SizeTooLarge(size) if size == MAX_PNG_SIZE + 1));
+    }
+}
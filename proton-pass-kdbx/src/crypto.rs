@@ -0,0 +1,135 @@
+use crate::KdbxError;
+use crate::header::{Cipher, Kdf, OuterHeader};
+use crate::reader::Reader;
+use aes::cipher::{BlockDecryptMut, BlockEncrypt, KeyInit, KeyIvInit, StreamCipher, block_padding::Pkcs7};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use std::io::Read;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Verifies the header and block HMACs and returns the decrypted (and decompressed) payload,
+/// which starts with the inner header.
+pub(crate) fn decrypt_payload(input: &[u8], header: &OuterHeader, password: &str) -> Result<Vec<u8>, KdbxError> {
+    let mut reader = Reader::new(input);
+    let header_bytes = reader.read_bytes(header.len)?;
+    let header_hash = reader.read_bytes(32)?;
+    let header_hmac = reader.read_bytes(32)?;
+
+    if Sha256::digest(header_bytes).as_slice() != header_hash {
+        return Err(KdbxError::CorruptedData);
+    }
+
+    let composite_key = Sha256::digest(Sha256::digest(password.as_bytes()));
+    let transformed_key = transform_key(&composite_key, &header.kdf)?;
+
+    let mut hasher = Sha512::new();
+    hasher.update(&header.master_seed);
+    hasher.update(&transformed_key);
+    hasher.update([1u8]);
+    let hmac_key = hasher.finalize();
+
+    // A mismatch in the header HMAC is the only way to tell that the password is wrong
+    let mut mac = block_hmac(u64::MAX, &hmac_key)?;
+    mac.update(header_bytes);
+    mac.verify_slice(header_hmac).map_err(|_| KdbxError::BadPassword)?;
+
+    let mut ciphertext = Vec::new();
+    let mut block_index = 0u64;
+    loop {
+        let block_mac = reader.read_bytes(32)?;
+        let size = reader.read_i32()?;
+        let data = reader.read_bytes(usize::try_from(size).map_err(|_| KdbxError::CorruptedData)?)?;
+
+        let mut mac = block_hmac(block_index, &hmac_key)?;
+        mac.update(&block_index.to_le_bytes());
+        mac.update(&size.to_le_bytes());
+        mac.update(data);
+        mac.verify_slice(block_mac).map_err(|_| KdbxError::CorruptedData)?;
+
+        if data.is_empty() {
+            break;
+        }
+        ciphertext.extend_from_slice(data);
+        block_index += 1;
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(&header.master_seed);
+    hasher.update(&transformed_key);
+    let encryption_key = hasher.finalize();
+
+    let decrypted = match header.cipher {
+        Cipher::Aes256 => cbc::Decryptor::<aes::Aes256>::new_from_slices(&encryption_key, &header.iv)
+            .map_err(|_| KdbxError::BadContent)?
+            .decrypt_padded_vec_mut::<Pkcs7>(&ciphertext)
+            .map_err(|_| KdbxError::CorruptedData)?,
+        Cipher::ChaCha20 => {
+            let mut cipher =
+                chacha20::ChaCha20::new_from_slices(&encryption_key, &header.iv).map_err(|_| KdbxError::BadContent)?;
+            cipher.apply_keystream(&mut ciphertext);
+            ciphertext
+        }
+    };
+
+    if !header.compressed {
+        return Ok(decrypted);
+    }
+
+    let mut decompressed = Vec::new();
+    flate2::read::GzDecoder::new(decrypted.as_slice())
+        .read_to_end(&mut decompressed)
+        .map_err(|_| KdbxError::CorruptedData)?;
+    Ok(decompressed)
+}
+
+/// Keystream used to hide protected values inside the XML document
+pub(crate) fn inner_stream_cipher(stream_key: &[u8]) -> Result<chacha20::ChaCha20, KdbxError> {
+    let hash = Sha512::digest(stream_key);
+    chacha20::ChaCha20::new_from_slices(&hash[..32], &hash[32..44]).map_err(|_| KdbxError::BadContent)
+}
+
+fn block_hmac(index: u64, hmac_key: &[u8]) -> Result<HmacSha256, KdbxError> {
+    let mut hasher = Sha512::new();
+    hasher.update(index.to_le_bytes());
+    hasher.update(hmac_key);
+    <HmacSha256 as Mac>::new_from_slice(&hasher.finalize()).map_err(|_| KdbxError::BadContent)
+}
+
+fn transform_key(composite_key: &[u8], kdf: &Kdf) -> Result<Vec<u8>, KdbxError> {
+    match kdf {
+        Kdf::Aes { rounds, seed } => {
+            let cipher = aes::Aes256::new_from_slice(seed).map_err(|_| KdbxError::BadContent)?;
+            let mut blocks = [
+                aes::Block::clone_from_slice(&composite_key[..16]),
+                aes::Block::clone_from_slice(&composite_key[16..32]),
+            ];
+            for _ in 0..*rounds {
+                cipher.encrypt_blocks(&mut blocks);
+            }
+
+            let mut hasher = Sha256::new();
+            hasher.update(blocks[0]);
+            hasher.update(blocks[1]);
+            Ok(hasher.finalize().to_vec())
+        }
+        Kdf::Argon2 {
+            algorithm,
+            salt,
+            iterations,
+            memory_kib,
+            parallelism,
+            version,
+        } => {
+            let version = argon2::Version::try_from(*version).map_err(|_| KdbxError::UnsupportedKdf)?;
+            let params = argon2::Params::new(*memory_kib, *iterations, *parallelism, Some(32))
+                .map_err(|_| KdbxError::UnsupportedKdf)?;
+
+            let mut output = vec![0u8; 32];
+            argon2::Argon2::new(*algorithm, version, params)
+                .hash_password_into(composite_key, salt, &mut output)
+                .map_err(|_| KdbxError::UnsupportedKdf)?;
+            Ok(output)
+        }
+    }
+}
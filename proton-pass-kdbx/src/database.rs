@@ -0,0 +1,68 @@
+use crate::otp;
+
+pub const TITLE_KEY: &str = "Title";
+pub const USERNAME_KEY: &str = "UserName";
+pub const PASSWORD_KEY: &str = "Password";
+pub const URL_KEY: &str = "URL";
+pub const NOTES_KEY: &str = "Notes";
+
+const STANDARD_KEYS: [&str; 5] = [TITLE_KEY, USERNAME_KEY, PASSWORD_KEY, URL_KEY, NOTES_KEY];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KdbxDatabase {
+    pub entries: Vec<KdbxEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KdbxField {
+    pub key: String,
+    pub value: String,
+    pub protected: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KdbxEntry {
+    pub uuid: String,
+    /// Names of the groups containing the entry, starting from the root group
+    pub group: Vec<String>,
+    pub fields: Vec<KdbxField>,
+}
+
+impl KdbxEntry {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|f| f.key == key).map(|f| f.value.as_str())
+    }
+
+    pub fn title(&self) -> &str {
+        self.get(TITLE_KEY).unwrap_or_default()
+    }
+
+    pub fn username(&self) -> &str {
+        self.get(USERNAME_KEY).unwrap_or_default()
+    }
+
+    pub fn password(&self) -> &str {
+        self.get(PASSWORD_KEY).unwrap_or_default()
+    }
+
+    pub fn url(&self) -> &str {
+        self.get(URL_KEY).unwrap_or_default()
+    }
+
+    pub fn notes(&self) -> &str {
+        self.get(NOTES_KEY).unwrap_or_default()
+    }
+
+    /// Fields added by the user, leaving out the standard ones and the ones used to store OTP settings
+    pub fn custom_fields(&self) -> impl Iterator<Item = &KdbxField> {
+        self.fields
+            .iter()
+            .filter(|f| !STANDARD_KEYS.contains(&f.key.as_str()) && !otp::is_otp_key(&f.key))
+    }
+
+    /// OTP configuration of the entry as an `otpauth://` URI, if it has any.
+    /// Steam entries get the `encoder=steam` parameter, the same way KeePassXC exports them.
+    pub fn otp_uri(&self) -> Option<String> {
+        otp::otp_uri(self)
+    }
+}
@@ -0,0 +1,12 @@
+use proton_pass_derive::Error;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum KdbxError {
+    BadContent,
+    UnsupportedVersion(u16),
+    UnsupportedCipher,
+    UnsupportedKdf,
+    BadPassword,
+    CorruptedData,
+    BadXml(String),
+}
@@ -0,0 +1,247 @@
+use crate::KdbxError;
+use crate::reader::Reader;
+use std::collections::HashMap;
+
+const SIGNATURE_1: u32 = 0x9AA2D903;
+const SIGNATURE_2: u32 = 0xB54BFB67;
+const SUPPORTED_MAJOR_VERSION: u16 = 4;
+
+const CIPHER_AES256: [u8; 16] = hex16("31c1f2e6bf714350be5805216afc5aff");
+const CIPHER_CHACHA20: [u8; 16] = hex16("d6038a2b8b6f4cb5a524339a31dbb59a");
+
+const KDF_AES: [u8; 16] = hex16("c9d9f39a628a4460bf740d08c18a4fea");
+const KDF_ARGON2D: [u8; 16] = hex16("ef636ddf8c29444b91f7a9a403e30a0c");
+const KDF_ARGON2ID: [u8; 16] = hex16("9e298b1956db4773b23dfc3ec6f0a1e6");
+
+const INNER_STREAM_CHACHA20: u32 = 3;
+
+const fn hex16(input: &str) -> [u8; 16] {
+    const fn nibble(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            _ => panic!("invalid hex"),
+        }
+    }
+
+    let bytes = input.as_bytes();
+    let mut out = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        out[i] = (nibble(bytes[i * 2]) << 4) | nibble(bytes[i * 2 + 1]);
+        i += 1;
+    }
+    out
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Cipher {
+    Aes256,
+    ChaCha20,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Kdf {
+    Aes {
+        rounds: u64,
+        seed: Vec<u8>,
+    },
+    Argon2 {
+        algorithm: argon2::Algorithm,
+        salt: Vec<u8>,
+        iterations: u32,
+        memory_kib: u32,
+        parallelism: u32,
+        version: u32,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct OuterHeader {
+    pub cipher: Cipher,
+    pub compressed: bool,
+    pub master_seed: Vec<u8>,
+    pub iv: Vec<u8>,
+    pub kdf: Kdf,
+    /// Length of the serialized header, which is what the header hash and HMAC cover
+    pub len: usize,
+}
+
+impl OuterHeader {
+    pub fn parse(input: &[u8]) -> Result<Self, KdbxError> {
+        let mut reader = Reader::new(input);
+        if reader.read_u32()? != SIGNATURE_1 || reader.read_u32()? != SIGNATURE_2 {
+            return Err(KdbxError::BadContent);
+        }
+
+        let _minor = reader.read_u16()?;
+        let major = reader.read_u16()?;
+        if major != SUPPORTED_MAJOR_VERSION {
+            return Err(KdbxError::UnsupportedVersion(major));
+        }
+
+        let mut cipher = None;
+        let mut compressed = false;
+        let mut master_seed = None;
+        let mut iv = None;
+        let mut kdf = None;
+        loop {
+            let field_id = reader.read_u8()?;
+            let len = reader.read_u32()? as usize;
+            let data = reader.read_bytes(len)?;
+            match field_id {
+                0 => break,
+                2 => cipher = Some(parse_cipher(data)?),
+                3 => compressed = Reader::new(data).read_u32()? == 1,
+                4 => master_seed = Some(data.to_vec()),
+                7 => iv = Some(data.to_vec()),
+                11 => kdf = Some(parse_kdf(data)?),
+                // Comment and public custom data are not needed for decryption
+                _ => {}
+            }
+        }
+
+        let master_seed = master_seed.ok_or(KdbxError::BadContent)?;
+        if master_seed.len() != 32 {
+            return Err(KdbxError::BadContent);
+        }
+
+        Ok(Self {
+            cipher: cipher.ok_or(KdbxError::BadContent)?,
+            compressed,
+            master_seed,
+            iv: iv.ok_or(KdbxError::BadContent)?,
+            kdf: kdf.ok_or(KdbxError::BadContent)?,
+            len: reader.position(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct InnerHeader {
+    pub stream_key: Vec<u8>,
+}
+
+impl InnerHeader {
+    /// Returns the parsed inner header and the offset at which the XML document starts
+    pub fn parse(payload: &[u8]) -> Result<(Self, usize), KdbxError> {
+        let mut reader = Reader::new(payload);
+        let mut stream_id = None;
+        let mut stream_key = None;
+        loop {
+            let field_id = reader.read_u8()?;
+            let len = reader.read_u32()? as usize;
+            let data = reader.read_bytes(len)?;
+            match field_id {
+                0 => break,
+                1 => stream_id = Some(Reader::new(data).read_u32()?),
+                2 => stream_key = Some(data.to_vec()),
+                // Attachments are not imported
+                _ => {}
+            }
+        }
+
+        // KDBX4 databases written by KeePass and KeePassXC always use ChaCha20 for protected values
+        if stream_id != Some(INNER_STREAM_CHACHA20) {
+            return Err(KdbxError::UnsupportedCipher);
+        }
+
+        let header = Self {
+            stream_key: stream_key.ok_or(KdbxError::BadContent)?,
+        };
+        Ok((header, reader.position()))
+    }
+}
+
+fn parse_cipher(data: &[u8]) -> Result<Cipher, KdbxError> {
+    if data == CIPHER_AES256 {
+        Ok(Cipher::Aes256)
+    } else if data == CIPHER_CHACHA20 {
+        Ok(Cipher::ChaCha20)
+    } else {
+        Err(KdbxError::UnsupportedCipher)
+    }
+}
+
+fn parse_kdf(data: &[u8]) -> Result<Kdf, KdbxError> {
+    let params = parse_variant_dictionary(data)?;
+    let bytes = |key: &str| match params.get(key) {
+        Some(Variant::Bytes(value)) => Ok(value.clone()),
+        _ => Err(KdbxError::BadContent),
+    };
+    let number = |key: &str| match params.get(key) {
+        Some(Variant::UInt32(value)) => Ok(*value as u64),
+        Some(Variant::UInt64(value)) => Ok(*value),
+        _ => Err(KdbxError::BadContent),
+    };
+    let narrow = |value: u64| u32::try_from(value).map_err(|_| KdbxError::UnsupportedKdf);
+
+    let uuid = bytes("$UUID")?;
+    if uuid == KDF_AES {
+        return Ok(Kdf::Aes {
+            rounds: number("R")?,
+            seed: bytes("S")?,
+        });
+    }
+
+    let algorithm = if uuid == KDF_ARGON2D {
+        argon2::Algorithm::Argon2d
+    } else if uuid == KDF_ARGON2ID {
+        argon2::Algorithm::Argon2id
+    } else {
+        return Err(KdbxError::UnsupportedKdf);
+    };
+
+    Ok(Kdf::Argon2 {
+        algorithm,
+        salt: bytes("S")?,
+        iterations: narrow(number("I")?)?,
+        // Stored in bytes, argon2 expects KiB
+        memory_kib: narrow(number("M")? / 1024)?,
+        parallelism: narrow(number("P")?)?,
+        version: narrow(number("V")?)?,
+    })
+}
+
+#[derive(Clone, Debug)]
+enum Variant {
+    UInt32(u32),
+    UInt64(u64),
+    Bytes(Vec<u8>),
+    Other,
+}
+
+fn parse_variant_dictionary(data: &[u8]) -> Result<HashMap<String, Variant>, KdbxError> {
+    let mut reader = Reader::new(data);
+    let version = reader.read_u16()?;
+    if version >> 8 != 1 {
+        return Err(KdbxError::BadContent);
+    }
+
+    let mut values = HashMap::new();
+    loop {
+        let value_type = reader.read_u8()?;
+        if value_type == 0 {
+            break;
+        }
+
+        let key_len = reader.read_i32()?;
+        let key = reader.read_bytes(usize::try_from(key_len).map_err(|_| KdbxError::BadContent)?)?;
+        let key = String::from_utf8(key.to_vec()).map_err(|_| KdbxError::BadContent)?;
+        let value_len = reader.read_i32()?;
+        let value = reader.read_bytes(usize::try_from(value_len).map_err(|_| KdbxError::BadContent)?)?;
+
+        let variant = match value_type {
+            0x04 => Variant::UInt32(Reader::new(value).read_u32()?),
+            0x05 => {
+                let bytes: [u8; 8] = value.try_into().map_err(|_| KdbxError::BadContent)?;
+                Variant::UInt64(u64::from_le_bytes(bytes))
+            }
+            0x42 => Variant::Bytes(value.to_vec()),
+            _ => Variant::Other,
+        };
+        values.insert(key, variant);
+    }
+
+    Ok(values)
+}
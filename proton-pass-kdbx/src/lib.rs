@@ -0,0 +1,19 @@
+mod crypto;
+mod database;
+mod error;
+mod header;
+mod otp;
+mod reader;
+mod xml;
+
+pub use database::{KdbxDatabase, KdbxEntry, KdbxField};
+pub use error::KdbxError;
+
+/// Decrypts and parses a KDBX4 database protected with a password.
+pub fn read_kdbx(input: &[u8], password: &str) -> Result<KdbxDatabase, KdbxError> {
+    let header = header::OuterHeader::parse(input)?;
+    let payload = crypto::decrypt_payload(input, &header, password)?;
+    let (inner, xml_start) = header::InnerHeader::parse(&payload)?;
+    let xml = std::str::from_utf8(&payload[xml_start..]).map_err(|_| KdbxError::BadContent)?;
+    xml::parse_database(xml, &inner)
+}
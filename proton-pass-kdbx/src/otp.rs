@@ -0,0 +1,89 @@
+use crate::KdbxEntry;
+
+const OTP_KEY: &str = "otp";
+// Used by KeePassXC before 2.7 and by the KeeTrayTOTP plugin
+const LEGACY_SEED_KEY: &str = "TOTP Seed";
+const LEGACY_SETTINGS_KEY: &str = "TOTP Settings";
+
+const STEAM_DIGITS: &str = "S";
+
+pub(crate) fn is_otp_key(key: &str) -> bool {
+    key == OTP_KEY || key == LEGACY_SEED_KEY || key == LEGACY_SETTINGS_KEY
+}
+
+pub(crate) fn otp_uri(entry: &KdbxEntry) -> Option<String> {
+    if let Some(otp) = non_empty(entry.get(OTP_KEY)) {
+        if otp.starts_with("otpauth://") {
+            return Some(otp.to_string());
+        }
+        return keeotp_uri(entry, otp);
+    }
+
+    let seed = non_empty(entry.get(LEGACY_SEED_KEY))?;
+    let settings = non_empty(entry.get(LEGACY_SETTINGS_KEY)).unwrap_or("30;6");
+    let mut parts = settings.split(';').map(str::trim);
+    let period = parts.next().unwrap_or("30");
+    let digits = parts.next().unwrap_or("6");
+    build_uri(entry, seed, period, digits, None)
+}
+
+// KeeOtp stores the settings as `key=SECRET&size=6&step=30&otpHashMode=Sha256`
+fn keeotp_uri(entry: &KdbxEntry, value: &str) -> Option<String> {
+    let mut secret = None;
+    let mut digits = "6".to_string();
+    let mut period = "30".to_string();
+    let mut algorithm = None;
+    for pair in value.split('&') {
+        let (key, value) = pair.split_once('=')?;
+        let value = urlencoding::decode(value).ok()?.into_owned();
+        match key {
+            "key" => secret = Some(value),
+            "size" => digits = value,
+            "step" => period = value,
+            "otpHashMode" => algorithm = Some(value.to_uppercase()),
+            "type" if !value.eq_ignore_ascii_case("totp") => return None,
+            _ => {}
+        }
+    }
+
+    build_uri(entry, &secret?, &period, &digits, algorithm.as_deref())
+}
+
+fn build_uri(entry: &KdbxEntry, secret: &str, period: &str, digits: &str, algorithm: Option<&str>) -> Option<String> {
+    let secret: String = secret.chars().filter(|c| !c.is_whitespace()).collect();
+    if secret.is_empty() {
+        return None;
+    }
+
+    let period: u32 = period.parse().ok()?;
+    let mut query = format!("secret={}&period={period}", urlencoding::encode(&secret));
+    if digits == STEAM_DIGITS {
+        query.push_str("&digits=5&encoder=steam");
+    } else {
+        let digits: u32 = digits.parse().ok()?;
+        query.push_str(&format!("&digits={digits}"));
+    }
+    if let Some(algorithm) = algorithm {
+        query.push_str(&format!("&algorithm={algorithm}"));
+    }
+
+    let issuer = entry.title().trim();
+    let account = match entry.username().trim() {
+        "" => issuer,
+        username => username,
+    };
+    if !issuer.is_empty() {
+        query.push_str(&format!("&issuer={}", urlencoding::encode(issuer)));
+    }
+
+    let label = if issuer.is_empty() || issuer == account {
+        urlencoding::encode(account).into_owned()
+    } else {
+        format!("{}:{}", urlencoding::encode(issuer), urlencoding::encode(account))
+    };
+    Some(format!("otpauth://totp/{label}?{query}"))
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
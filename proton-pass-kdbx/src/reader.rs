@@ -0,0 +1,42 @@
+use crate::KdbxError;
+
+/// Little-endian cursor over a byte slice. Reading past the end is reported as `BadContent`.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], KdbxError> {
+        let end = self.pos.checked_add(len).ok_or(KdbxError::BadContent)?;
+        let bytes = self.data.get(self.pos..end).ok_or(KdbxError::BadContent)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, KdbxError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, KdbxError> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, KdbxError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, KdbxError> {
+        Ok(self.read_u32()? as i32)
+    }
+}
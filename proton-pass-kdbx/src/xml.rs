@@ -0,0 +1,121 @@
+use crate::crypto::inner_stream_cipher;
+use crate::header::InnerHeader;
+use crate::{KdbxDatabase, KdbxEntry, KdbxError, KdbxField};
+use aes::cipher::StreamCipher;
+use base64::Engine;
+use roxmltree::{Document, Node, NodeId};
+use std::collections::HashMap;
+
+pub(crate) fn parse_database(xml: &str, inner: &InnerHeader) -> Result<KdbxDatabase, KdbxError> {
+    let doc = Document::parse(xml).map_err(|e| KdbxError::BadXml(e.to_string()))?;
+    let root = doc.root_element();
+    if !root.has_tag_name("KeePassFile") {
+        return Err(KdbxError::BadXml("Missing KeePassFile element".to_string()));
+    }
+
+    let protected = decrypt_protected_values(&doc, inner)?;
+    let recycle_bin = child(root, "Meta").and_then(recycle_bin_uuid);
+
+    let root_group = child(root, "Root")
+        .and_then(|r| child(r, "Group"))
+        .ok_or_else(|| KdbxError::BadXml("Missing root group".to_string()))?;
+
+    let mut entries = Vec::new();
+    collect_entries(
+        root_group,
+        &mut Vec::new(),
+        recycle_bin.as_deref(),
+        &protected,
+        &mut entries,
+    );
+    Ok(KdbxDatabase { entries })
+}
+
+// Protected values share a single keystream, so they have to be decrypted in document order,
+// including the ones belonging to history entries we are not going to import
+fn decrypt_protected_values(doc: &Document, inner: &InnerHeader) -> Result<HashMap<NodeId, String>, KdbxError> {
+    let mut cipher = inner_stream_cipher(&inner.stream_key)?;
+    let mut values = HashMap::new();
+    for node in doc.descendants() {
+        if !node.has_tag_name("Value") || node.attribute("Protected") != Some("True") {
+            continue;
+        }
+
+        let mut value = base64::engine::general_purpose::STANDARD
+            .decode(node.text().unwrap_or_default().trim())
+            .map_err(|_| KdbxError::CorruptedData)?;
+        cipher.apply_keystream(&mut value);
+        let value = String::from_utf8(value).map_err(|_| KdbxError::CorruptedData)?;
+        values.insert(node.id(), value);
+    }
+
+    Ok(values)
+}
+
+fn recycle_bin_uuid(meta: Node) -> Option<String> {
+    let enabled = child_text(meta, "RecycleBinEnabled")?;
+    if !enabled.eq_ignore_ascii_case("true") {
+        return None;
+    }
+    child_text(meta, "RecycleBinUUID")
+}
+
+fn collect_entries(
+    group: Node,
+    path: &mut Vec<String>,
+    recycle_bin: Option<&str>,
+    protected: &HashMap<NodeId, String>,
+    entries: &mut Vec<KdbxEntry>,
+) {
+    if recycle_bin.is_some() && child_text(group, "UUID").as_deref() == recycle_bin {
+        return;
+    }
+
+    path.push(child_text(group, "Name").unwrap_or_default());
+    for node in group.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "Entry" => entries.push(parse_entry(node, path, protected)),
+            "Group" => collect_entries(node, path, recycle_bin, protected, entries),
+            _ => {}
+        }
+    }
+    path.pop();
+}
+
+fn parse_entry(entry: Node, path: &[String], protected: &HashMap<NodeId, String>) -> KdbxEntry {
+    let fields = entry
+        .children()
+        .filter(|n| n.has_tag_name("String"))
+        .filter_map(|string| {
+            let key = child_text(string, "Key")?;
+            let value_node = child(string, "Value")?;
+            let field = match protected.get(&value_node.id()) {
+                Some(value) => KdbxField {
+                    key,
+                    value: value.clone(),
+                    protected: true,
+                },
+                None => KdbxField {
+                    key,
+                    value: value_node.text().unwrap_or_default().to_string(),
+                    protected: false,
+                },
+            };
+            Some(field)
+        })
+        .collect();
+
+    KdbxEntry {
+        uuid: child_text(entry, "UUID").unwrap_or_default(),
+        group: path.to_vec(),
+        fields,
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name).map(|n| n.text().unwrap_or_default().trim().to_string())
+}
@@ -0,0 +1,91 @@
+use proton_pass_kdbx::{KdbxDatabase, KdbxError, read_kdbx};
+
+const ARGON2_CHACHA20: &[u8] = include_bytes!("../test_data/keepass_argon2_chacha20.kdbx");
+const AESKDF_AES: &[u8] = include_bytes!("../test_data/keepass_aeskdf_aes.kdbx");
+const PASSWORD: &str = include_str!("../test_data/password");
+
+fn check_database(db: &KdbxDatabase) {
+    let titles: Vec<&str> = db.entries.iter().map(|e| e.title()).collect();
+    // History entries and the recycle bin are not returned
+    assert_eq!(titles, vec!["Proton", "Amazon", "Steam", "Bank", "KeeOtp", "GitHub"]);
+
+    let proton = &db.entries[0];
+    assert_eq!(proton.group, vec!["Passwords".to_string()]);
+    assert_eq!(proton.username(), "sometestaccount@proton.me");
+    assert_eq!(proton.password(), "pr0t0n");
+    assert_eq!(proton.url(), "https://account.proton.me");
+    assert_eq!(proton.notes(), "Main account");
+    assert_eq!(
+        proton.otp_uri().as_deref(),
+        Some(
+            "otpauth://totp/Proton:sometestaccount%40proton.me?secret=JBSWY3DPEHPK3PXP&period=30&digits=6&issuer=Proton"
+        )
+    );
+
+    let custom: Vec<(&str, &str, bool)> = proton
+        .custom_fields()
+        .map(|f| (f.key.as_str(), f.value.as_str(), f.protected))
+        .collect();
+    assert_eq!(
+        custom,
+        vec![("Recovery", "recovery-phrase", true), ("Hint", "my hint", false)]
+    );
+
+    let github = &db.entries[5];
+    assert_eq!(github.group, vec!["Passwords".to_string(), "Work".to_string()]);
+    assert_eq!(github.password(), "gith4b");
+}
+
+#[test]
+fn can_read_argon2_chacha20_database() {
+    let db = read_kdbx(ARGON2_CHACHA20, PASSWORD).expect("should be able to read");
+    check_database(&db);
+}
+
+#[test]
+fn can_read_aeskdf_aes_database() {
+    let db = read_kdbx(AESKDF_AES, PASSWORD).expect("should be able to read");
+    check_database(&db);
+}
+
+#[test]
+fn converts_legacy_totp_settings() {
+    let db = read_kdbx(AESKDF_AES, PASSWORD).expect("should be able to read");
+
+    assert_eq!(
+        db.entries[1].otp_uri().as_deref(),
+        Some(
+            "otpauth://totp/Amazon:some%40account.test?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&period=15&digits=8&issuer=Amazon"
+        )
+    );
+    assert_eq!(
+        db.entries[2].otp_uri().as_deref(),
+        Some("otpauth://totp/Steam:steamuser?secret=STEAMKEY&period=30&digits=5&encoder=steam&issuer=Steam")
+    );
+    assert_eq!(db.entries[3].otp_uri(), None);
+    assert_eq!(
+        db.entries[4].otp_uri().as_deref(),
+        Some("otpauth://totp/KeeOtp:keeotp?secret=JBSWY3DPEHPK3PXP&period=60&digits=7&algorithm=SHA512&issuer=KeeOtp")
+    );
+}
+
+#[test]
+fn wrong_password_returns_error() {
+    let err = read_kdbx(ARGON2_CHACHA20, "invalid").expect_err("should fail");
+    assert_eq!(err, KdbxError::BadPassword);
+}
+
+#[test]
+fn bad_content_returns_error() {
+    let err = read_kdbx(b"not a keepass database", PASSWORD).expect_err("should fail");
+    assert_eq!(err, KdbxError::BadContent);
+}
+
+#[test]
+fn tampered_payload_returns_error() {
+    let mut contents = AESKDF_AES.to_vec();
+    let last = contents.len() - 100;
+    contents[last] ^= 0xff;
+    let err = read_kdbx(&contents, PASSWORD).expect_err("should fail");
+    assert_eq!(err, KdbxError::CorruptedData);
+}
@@ -4,6 +4,7 @@ use crate::queries::Queries;
 use crate::sanitizer::sanitize_secret;
 use proton_pass_derive::ffi_type;
 use url::Url;
+use zeroize::{Zeroize, Zeroizing};
 
 #[ffi_type(web_name = "WasmTOTP")]
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -16,6 +17,14 @@ pub struct TOTP {
     pub period: Option<u16>,
 }
 
+// TOTP is an FFI record, which is moved field by field across the boundary and so can't wipe itself on drop.
+// The types holding it, such as the authenticator entries, wipe it when they are dropped.
+impl Zeroize for TOTP {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
 pub const OTP_SCHEME: &str = "otpauth";
 pub const TOTP_HOST: &str = "totp";
 pub const QUERY_SECRET: &str = "secret";
@@ -297,10 +306,11 @@ impl TOTP {
     }
 
     pub fn generate_token(&self, current_time: u64) -> Result<String, TOTPError> {
-        let sanitized_secret = sanitize_secret(self.secret.as_str());
-        let secret = match totp_rs::Secret::Encoded(sanitized_secret.clone()).to_bytes() {
+        // The intermediate copies of the secret are wiped on drop, as is the generator
+        let sanitized_secret = Zeroizing::new(sanitize_secret(self.secret.as_str()));
+        let secret = match totp_rs::Secret::Encoded(sanitized_secret.to_string()).to_bytes() {
             Ok(secret) => secret,
-            Err(_) => match totp_rs::Secret::Raw(sanitized_secret.into_bytes()).to_bytes() {
+            Err(_) => match totp_rs::Secret::Raw(sanitized_secret.as_bytes().to_vec()).to_bytes() {
                 Ok(secret) => secret,
                 Err(_) => return Err(TOTPError::SecretParseError),
             },
@@ -939,3 +949,27 @@ mod test_generate_token {
         assert_eq!(expected, secret_token);
     }
 }
+
+#[cfg(test)]
+mod test_zeroize {
+    use super::*;
+
+    #[test]
+    fn zeroize_wipes_secret_and_keeps_other_fields() {
+        let mut totp = TOTP::from_uri("otpauth://totp/label?secret=SOMESECRET&issuer=issuer").unwrap();
+        let secret_len = totp.secret.len();
+        totp.zeroize();
+
+        // The buffer is kept, so its former contents can be checked without reading freed memory
+        let mut buffer = std::mem::take(&mut totp.secret).into_bytes();
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= secret_len);
+        // SAFETY: zeroize has written every byte of the capacity, so the spare capacity is initialized
+        let wiped = buffer.spare_capacity_mut()[..secret_len]
+            .iter()
+            .all(|byte| unsafe { byte.assume_init() } == 0);
+        assert!(wiped);
+        assert_eq!(Some("label".to_string()), totp.label);
+        assert_eq!(Some("issuer".to_string()), totp.issuer);
+    }
+}
@@ -0,0 +1,140 @@
+/*
+ *  Copyright (c) 2026 Proton AG
+ *  This file is part of Proton AG and Proton Pass.
+ *
+ *  Proton Pass is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  Proton Pass is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU General Public License for more details.
+ *
+ *  You should have received a copy of the GNU General Public License
+ *  along with Proton Pass.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ */
+
+use crate::{ItemContent, ItemData, ItemExtraField, ItemExtraFieldContent, LoginItem};
+use anyhow::{Result, anyhow};
+use proton_pass_kdbx::KdbxEntry;
+
+const UNTITLED: &str = "Untitled";
+
+impl ItemData {
+    /// Reads every entry of a password protected KDBX4 database as a login item.
+    /// Entries in the recycle bin and history entries are not returned.
+    pub fn from_kdbx(input: &[u8], password: &str) -> Result<Vec<ItemData>> {
+        let database =
+            proton_pass_kdbx::read_kdbx(input, password).map_err(|e| anyhow!("Error reading KDBX database: {e}"))?;
+        Ok(database.entries.iter().map(ItemData::from).collect())
+    }
+}
+
+impl From<&KdbxEntry> for ItemData {
+    fn from(entry: &KdbxEntry) -> Self {
+        let username = entry.username().trim();
+        let (email, username) = if username.contains('@') {
+            (username.to_string(), String::new())
+        } else {
+            (String::new(), username.to_string())
+        };
+
+        let title = match entry.title().trim() {
+            "" if !email.is_empty() => email.clone(),
+            "" if !username.is_empty() => username.clone(),
+            "" => UNTITLED.to_string(),
+            title => title.to_string(),
+        };
+
+        let url = entry.url().trim();
+        let urls = if url.is_empty() { vec![] } else { vec![url.to_string()] };
+
+        let extra_fields = entry
+            .custom_fields()
+            .map(|field| ItemExtraField {
+                name: field.key.clone(),
+                content: if field.protected {
+                    ItemExtraFieldContent::Hidden(field.value.clone())
+                } else {
+                    ItemExtraFieldContent::Text(field.value.clone())
+                },
+            })
+            .collect();
+
+        ItemData {
+            title,
+            note: entry.notes().to_string(),
+            item_uuid: ItemData::generate_uuid(),
+            content: ItemContent::Login(LoginItem {
+                email,
+                username,
+                password: entry.password().to_string(),
+                urls,
+                totp_uri: entry.otp_uri().unwrap_or_default(),
+                passkeys: vec![],
+            }),
+            extra_fields,
+            platform_specific: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATABASE: &[u8] = include_bytes!("../../../../proton-pass-kdbx/test_data/keepass_argon2_chacha20.kdbx");
+
+    fn login(item: &ItemData) -> &LoginItem {
+        match &item.content {
+            ItemContent::Login(login) => login,
+            other => panic!("expected a login item, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn can_read_kdbx_logins() {
+        let items = ItemData::from_kdbx(DATABASE, "test").expect("should be able to read");
+        assert_eq!(items.len(), 6);
+
+        let proton = &items[0];
+        assert_eq!(proton.title, "Proton");
+        assert_eq!(proton.note, "Main account");
+        let proton_login = login(proton);
+        assert_eq!(proton_login.email, "sometestaccount@proton.me");
+        assert!(proton_login.username.is_empty());
+        assert_eq!(proton_login.password, "pr0t0n");
+        assert_eq!(proton_login.urls, vec!["https://account.proton.me".to_string()]);
+        assert!(proton_login.totp_uri.starts_with("otpauth://totp/Proton:"));
+        assert_eq!(
+            proton.extra_fields,
+            vec![
+                ItemExtraField {
+                    name: "Recovery".to_string(),
+                    content: ItemExtraFieldContent::Hidden("recovery-phrase".to_string()),
+                },
+                ItemExtraField {
+                    name: "Hint".to_string(),
+                    content: ItemExtraFieldContent::Text("my hint".to_string()),
+                },
+            ]
+        );
+
+        let bank = login(&items[3]);
+        assert_eq!(bank.username, "user");
+        assert!(bank.totp_uri.is_empty());
+
+        // Every item can be serialized
+        for item in items {
+            item.serialize().expect("should be able to serialize");
+        }
+    }
+
+    #[test]
+    fn wrong_password_returns_error() {
+        assert!(ItemData::from_kdbx(DATABASE, "invalid").is_err());
+    }
+}
@@ -20,6 +20,8 @@
 mod attachment;
 mod field;
 mod flags;
+#[cfg(feature = "kdbx")]
+mod kdbx;
 
 use crate::protos::item::item_v1;
 use anyhow::{Context, Result, anyhow};
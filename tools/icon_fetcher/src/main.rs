@@ -1,5 +1,8 @@
 use futures::StreamExt;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
 use indicatif::{ProgressBar, ProgressStyle};
+use proton_authenticator::IconBundleBuilder;
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -38,6 +41,16 @@ struct Config {
     domains_file: String,
     max_parallel: usize,
     timeout_secs: u64,
+    /// If set, the icons are downloaded and stored in a bundle so clients don't need to request them to third parties
+    icon_bundle_file: Option<String>,
+    /// Where to write the manifest of the bundle, to be versioned along with the output file
+    icon_manifest_file: Option<String>,
+    #[serde(default = "default_icon_size")]
+    icon_size: u32,
+}
+
+fn default_icon_size() -> u32 {
+    64
 }
 
 impl Default for Config {
@@ -47,6 +60,9 @@ impl Default for Config {
             domains_file: "2faDomains.txt".to_string(),
             max_parallel: 50,
             timeout_secs: 5,
+            icon_bundle_file: None,
+            icon_manifest_file: None,
+            icon_size: default_icon_size(),
         }
     }
 }
@@ -190,6 +206,111 @@ async fn find_first_favicon(
     None
 }
 
+async fn download_icon(client: &Client, config: &Config, url: &str) -> Option<Vec<u8>> {
+    let timeout_duration = Duration::from_secs(config.timeout_secs);
+    match timeout(timeout_duration, client.get(url).send()).await {
+        Ok(Ok(resp)) if resp.status().is_success() => match resp.bytes().await {
+            Ok(bytes) => Some(bytes.to_vec()),
+            Err(err) => {
+                eprintln!("GET {url} body failed: {err}");
+                None
+            }
+        },
+        Ok(Ok(resp)) => {
+            eprintln!("GET {} -> status {}", url, resp.status());
+            None
+        }
+        Ok(Err(err)) => {
+            eprintln!("GET {url} failed: {err}");
+            None
+        }
+        Err(_) => {
+            eprintln!("GET {} timed out after {}s", url, config.timeout_secs);
+            None
+        }
+    }
+}
+
+/// Resizes the icon and converts it to WebP
+fn convert_icon(bytes: &[u8], size: u32) -> Result<Vec<u8>, image::ImageError> {
+    let img = image::load_from_memory(bytes)?;
+    let img = if img.width() > size || img.height() > size {
+        img.resize(size, size, FilterType::Lanczos3)
+    } else {
+        img
+    };
+
+    let mut output = Vec::new();
+    img.to_rgba8()
+        .write_with_encoder(WebPEncoder::new_lossless(&mut output))?;
+    Ok(output)
+}
+
+async fn build_icon_bundle(
+    client: Arc<Client>,
+    config: &Config,
+    favicons: &[FaviconInfo],
+    issuer_infos: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(bundle_file) = &config.icon_bundle_file else {
+        return Ok(());
+    };
+
+    let pb = ProgressBar::new(favicons.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} icons ({eta})")
+            .unwrap()
+            .progress_chars("=>-"),
+    );
+
+    let downloads = favicons.iter().map(|favicon| {
+        let client = client.clone();
+        let pb = pb.clone();
+        async move {
+            let icon = download_icon(&client, config, &favicon.icon_url).await;
+            pb.inc(1);
+            (favicon, icon)
+        }
+    });
+    let downloaded: Vec<_> = futures::stream::iter(downloads)
+        .buffered(config.max_parallel)
+        .collect()
+        .await;
+    pb.finish_with_message("Icon download complete.");
+
+    let mut builder = IconBundleBuilder::new(issuer_infos, "image/webp");
+    for (favicon, icon) in downloaded {
+        let Some(icon) = icon else {
+            continue;
+        };
+        match convert_icon(&icon, config.icon_size) {
+            Ok(converted) => {
+                builder.add(&favicon.name, &favicon.domain, converted);
+            }
+            Err(e) => eprintln!("Cannot convert icon of {}: {e}", favicon.domain),
+        }
+    }
+
+    let bundle = builder
+        .build()
+        .map_err(|e| format!("Cannot build icon bundle: {e:?}"))?;
+    tokio_fs::write(bundle_file, &bundle).await?;
+
+    let manifest = builder.manifest();
+    if let Some(manifest_file) = &config.icon_manifest_file {
+        tokio_fs::write(manifest_file, serde_json::to_string_pretty(&manifest)?).await?;
+    }
+
+    println!(
+        "Icon bundle with {} icons written to {bundle_file} ({} bytes).",
+        manifest.icons.len(),
+        bundle.len()
+    );
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration
@@ -294,12 +415,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Sort alphabetically by name (first part of the line)
     results.sort_by(|a, b| a.name.cmp(&b.name));
 
-    let mut output = File::create(config.output_file)?;
-    for line in results {
-        writeln!(output, "{}", line.to_line())?;
+    let mut output = File::create(&config.output_file)?;
+    let mut issuer_infos = String::new();
+    for line in &results {
+        issuer_infos.push_str(&line.to_line());
+        issuer_infos.push('\n');
     }
+    output.write_all(issuer_infos.as_bytes())?;
 
     println!("Async favicon check complete. Results written to favicons.txt.");
 
+    build_icon_bundle(client, &config, &results, &issuer_infos).await?;
+
     Ok(())
 }